@@ -1,17 +1,410 @@
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::fmt;
 
 use crate::amount_limit::AmountLimit;
 #[cfg(feature = "std")]
+use crate::validate_contract_id;
+use crate::IostAction;
+#[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use serde_json::Value;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct ABI {
     /// interface name
     pub name: String,
     /// arguments of the interface
+    #[cfg_attr(feature = "std", serde(default))]
     pub args: Vec<String>,
-    /// The limits on the amount
+    /// The limits on the amount, `amountLimit` in ABI files
+    #[cfg_attr(feature = "std", serde(default, alias = "amountLimit"))]
+    pub amount_limit: Vec<AmountLimit>,
+}
+
+/// A call of a contract with the amount limits its ABI declares, to add to a transaction with
+/// `TxBuilder::call`
+#[derive(Clone, Debug)]
+pub struct ContractCall {
+    pub action: IostAction,
     pub amount_limit: Vec<AmountLimit>,
 }
+
+/// Type of an argument of an ABI
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AbiArgType {
+    /// a JSON string
+    String,
+    /// a JSON number
+    Number,
+    /// `true` or `false`
+    Bool,
+    /// any JSON value, given to the contract as is
+    Json,
+}
+
+impl AbiArgType {
+    /// Type of its name in an ABI, such as "string"
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "string" => Some(AbiArgType::String),
+            "number" => Some(AbiArgType::Number),
+            "bool" => Some(AbiArgType::Bool),
+            "json" => Some(AbiArgType::Json),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AbiArgType::String => "string",
+            AbiArgType::Number => "number",
+            AbiArgType::Bool => "bool",
+            AbiArgType::Json => "json",
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn accepts(self, value: &Value) -> bool {
+        match self {
+            AbiArgType::String => value.is_string(),
+            AbiArgType::Number => value.is_number(),
+            AbiArgType::Bool => value.is_boolean(),
+            AbiArgType::Json => true,
+        }
+    }
+}
+
+/// An error which can be returned when encoding or decoding the arguments of an action with the
+/// ABI of its contract.
+#[derive(Debug, PartialEq, Clone)]
+pub enum AbiError {
+    /// The contract has no ABI of this name.
+    UnknownAbi(String),
+    /// The ABI declares an argument of an unknown type.
+    UnknownArgType {
+        abi: String,
+        index: usize,
+        arg_type: String,
+    },
+    /// The number of arguments differs from the ABI.
+    ArityMismatch {
+        abi: String,
+        expected: usize,
+        found: usize,
+    },
+    /// An argument does not have the type of the ABI.
+    TypeMismatch {
+        abi: String,
+        index: usize,
+        expected: AbiArgType,
+        found: String,
+    },
+    /// The data of the action is not a JSON array.
+    MalformedData(String),
+}
+
+impl fmt::Display for AbiError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownAbi(name) => write!(f, "no ABI named {}", name),
+            Self::UnknownArgType {
+                abi,
+                index,
+                arg_type,
+            } => write!(
+                f,
+                "argument {} of {} has the unknown type {}",
+                index, abi, arg_type
+            ),
+            Self::ArityMismatch {
+                abi,
+                expected,
+                found,
+            } => write!(f, "{} takes {} arguments, {} given", abi, expected, found),
+            Self::TypeMismatch {
+                abi,
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "argument {} of {} must be a {}, not {}",
+                index,
+                abi,
+                expected.name(),
+                found
+            ),
+            Self::MalformedData(data) => write!(f, "action data {} is not a JSON array", data),
+        }
+    }
+}
+
+impl From<AbiError> for crate::error::Error {
+    fn from(e: AbiError) -> crate::error::Error {
+        crate::Error::AbiErr(e)
+    }
+}
+
+/// The ABI named `name` among the ABIs of a contract
+pub fn find_abi<'a>(abis: &'a [ABI], name: &str) -> Result<&'a ABI, AbiError> {
+    abis.iter()
+        .find(|abi| abi.name == name)
+        .ok_or_else(|| AbiError::UnknownAbi(name.into()))
+}
+
+impl ABI {
+    pub fn arg_types(&self) -> Result<Vec<AbiArgType>, AbiError> {
+        self.args
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                AbiArgType::parse(arg).ok_or_else(|| AbiError::UnknownArgType {
+                    abi: self.name.clone(),
+                    index,
+                    arg_type: arg.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Check the number and the types of the arguments of a call
+    #[cfg(feature = "std")]
+    pub fn check_args(&self, args: &[Value]) -> Result<(), AbiError> {
+        let types = self.arg_types()?;
+        if types.len() != args.len() {
+            return Err(AbiError::ArityMismatch {
+                abi: self.name.clone(),
+                expected: types.len(),
+                found: args.len(),
+            });
+        }
+        for (index, (arg_type, arg)) in types.iter().zip(args).enumerate() {
+            if !arg_type.accepts(arg) {
+                return Err(AbiError::TypeMismatch {
+                    abi: self.name.clone(),
+                    index,
+                    expected: *arg_type,
+                    found: arg.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// `IostAction.data` of a call, once the arguments are checked
+    #[cfg(feature = "std")]
+    pub fn encode_args(&self, args: &[Value]) -> Result<Vec<u8>, AbiError> {
+        self.check_args(args)?;
+        Ok(Value::Array(args.to_vec()).to_string().into_bytes())
+    }
+
+    /// Arguments of a call from its `IostAction.data`, checked against the ABI
+    #[cfg(feature = "std")]
+    pub fn decode_args(&self, data: &[u8]) -> Result<Vec<Value>, AbiError> {
+        let malformed = || AbiError::MalformedData(String::from_utf8_lossy(data).into_owned());
+        let args = match serde_json::from_slice(data).map_err(|_| malformed())? {
+            Value::Array(args) => args,
+            _ => return Err(malformed()),
+        };
+        self.check_args(&args)?;
+        Ok(args)
+    }
+
+    /// Call of this ABI of `contract`
+    #[cfg(feature = "std")]
+    pub fn action(&self, contract: &str, args: &[Value]) -> crate::Result<IostAction> {
+        validate_contract_id(contract)?;
+        Ok(IostAction {
+            contract: contract.as_bytes().to_vec(),
+            action_name: self.name.as_bytes().to_vec(),
+            data: self.encode_args(args)?,
+        })
+    }
+
+    /// Call of this ABI of `contract` with its declared amount limits
+    #[cfg(feature = "std")]
+    pub fn call(&self, contract: &str, args: &[Value]) -> crate::Result<ContractCall> {
+        Ok(ContractCall {
+            action: self.action(contract, args)?,
+            amount_limit: self.amount_limit.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl IostAction {
+    /// Call of the ABI `action_name` of `contract`, the arguments checked against `abis`
+    pub fn from_abi(
+        contract: &str,
+        abis: &[ABI],
+        action_name: &str,
+        args: &[Value],
+    ) -> crate::Result<IostAction> {
+        find_abi(abis, action_name)?.action(contract, args)
+    }
+
+    /// Arguments of the action, checked against the ABIs of its contract
+    pub fn decode_args(&self, abis: &[ABI]) -> crate::Result<Vec<Value>> {
+        let action_name = String::from_utf8_lossy(&self.action_name);
+        Ok(find_abi(abis, &action_name)?.decode_args(&self.data)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Error, TxBuilder, MAINNET_CHAIN_ID};
+    use serde_json::json;
+
+    crate::contract_abi! {
+        #[iost_root_path = "crate"]
+        struct Exchange = "fixtures/exchange.abi";
+    }
+
+    fn abis() -> Vec<ABI> {
+        serde_json::from_value(json!([
+            {
+                "name": "transfer",
+                "args": ["string", "string", "string", "string", "string"],
+                "amount_limit": [],
+            },
+            {
+                "name": "setConfig",
+                "args": ["number", "bool", "json"],
+                "amount_limit": [],
+            },
+            {
+                "name": "broken",
+                "args": ["int"],
+                "amount_limit": [],
+            },
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn args_should_be_encoded_and_decoded() {
+        let args = vec![json!(10), json!(true), json!({"fee": [1, 2]})];
+        let action = IostAction::from_abi("Contract1234", &abis(), "setConfig", &args).unwrap();
+        assert_eq!(action.contract, b"Contract1234".to_vec());
+        assert_eq!(action.action_name, b"setConfig".to_vec());
+        assert_eq!(action.data, br#"[10,true,{"fee":[1,2]}]"#.to_vec());
+        assert_eq!(action.decode_args(&abis()).unwrap(), args);
+
+        let transfer = IostAction::transfer("admin", "lispczz3", "10", "").unwrap();
+        let args = transfer.decode_args(&abis()).unwrap();
+        assert_eq!(args[2], json!("lispczz3"));
+    }
+
+    #[test]
+    fn abis_of_files_and_of_node_should_be_parsed() {
+        let file: ABI = serde_json::from_value(json!({
+            "name": "deposit",
+            "args": ["string"],
+            "amountLimit": [{ "token": "iost", "val": "100" }],
+        }))
+        .unwrap();
+        let node: ABI = serde_json::from_value(json!({
+            "name": "deposit",
+            "args": ["string"],
+            "amount_limit": [{ "token": "iost", "value": "100" }],
+        }))
+        .unwrap();
+        for abi in [file, node].iter() {
+            assert_eq!(abi.args, vec!["string"]);
+            assert_eq!(abi.amount_limit[0].token, "iost");
+            assert_eq!(abi.amount_limit[0].value, "100");
+        }
+        let init: ABI = serde_json::from_value(json!({ "name": "init" })).unwrap();
+        assert!(init.args.is_empty() && init.amount_limit.is_empty());
+    }
+
+    #[test]
+    fn args_should_be_checked() {
+        let abis = abis();
+        let set_config = find_abi(&abis, "setConfig").unwrap();
+        assert_eq!(
+            set_config.encode_args(&[json!(10), json!(true)]),
+            Err(AbiError::ArityMismatch {
+                abi: "setConfig".into(),
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            set_config.encode_args(&[json!("10"), json!(true), json!(null)]),
+            Err(AbiError::TypeMismatch {
+                abi: "setConfig".into(),
+                index: 0,
+                expected: AbiArgType::Number,
+                found: "\"10\"".into()
+            })
+        );
+        assert!(matches!(
+            set_config.encode_args(&[json!(10), json!(1), json!(null)]),
+            Err(AbiError::TypeMismatch { index: 1, .. })
+        ));
+        assert!(matches!(
+            set_config.decode_args(b"{}"),
+            Err(AbiError::MalformedData(_))
+        ));
+        assert!(matches!(
+            find_abi(&abis, "broken").unwrap().encode_args(&[json!(1)]),
+            Err(AbiError::UnknownArgType { index: 0, .. })
+        ));
+        assert!(matches!(
+            IostAction::from_abi("Contract1234", &abis, "unknown", &[]),
+            Err(Error::AbiErr(AbiError::UnknownAbi(_)))
+        ));
+        assert!(IostAction::from_abi("x", &abis, "setConfig", &[]).is_err());
+    }
+
+    #[test]
+    fn binding_should_follow_abi() {
+        let exchange = Exchange::new("Contract1234");
+        assert_eq!(exchange.contract(), "Contract1234");
+        assert_eq!(Exchange::abis().len(), 3);
+
+        let call = exchange
+            .set_config(0.5, true, &json!({"fee": [1, 2]}))
+            .unwrap();
+        assert_eq!(call.action.action_name, b"setConfig".to_vec());
+        assert_eq!(call.action.data, br#"[0.5,true,{"fee":[1,2]}]"#.to_vec());
+        assert!(call.amount_limit.is_empty());
+        assert_eq!(
+            call.action.decode_args(&Exchange::abis()).unwrap()[0],
+            json!(0.5)
+        );
+        assert!(exchange.set_config(f64::NAN, true, &json!(null)).is_err());
+
+        let call = exchange.deposit("admin", "5").unwrap();
+        assert_eq!(call.action.data, br#"["admin","5"]"#.to_vec());
+        assert_eq!(call.amount_limit[0].token, "iost");
+        assert_eq!(call.amount_limit[0].value, "100");
+        assert!(Exchange::new("x").withdraw("admin", "5").is_err());
+    }
+
+    #[test]
+    fn calls_should_declare_amount_limit() {
+        let exchange = Exchange::new("Contract1234");
+        let builder = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time(1598918258274417000)
+            .publisher("admin")
+            .call(exchange.deposit("admin", "5").unwrap())
+            .call(exchange.deposit("admin", "6").unwrap())
+            .call(exchange.withdraw("admin", "1").unwrap())
+            .action(IostAction::transfer("admin", "lispczz3", "10.5", "").unwrap());
+        let tx = builder.clone().build().unwrap();
+        assert_eq!(tx.actions.len(), 4);
+        assert_eq!(tx.amount_limit.len(), 1);
+        assert_eq!(tx.amount_limit[0].token, "iost");
+        assert_eq!(tx.amount_limit[0].value, "210.5");
+
+        let tx = builder.amount_limit("iost", "20").build().unwrap();
+        assert_eq!(tx.amount_limit[0].value, "20");
+    }
+}
@@ -0,0 +1,274 @@
+//! IOST account names and contract ids
+//!
+//! <https://developers.iost.io/docs/en/3-goiost/Account.html>
+use alloc::string::{String, ToString};
+use core::{convert::TryFrom, fmt, str::FromStr};
+
+/// The minimum character length of an IOST account name.
+pub const ACCOUNT_NAME_LEN_MIN: usize = 5;
+
+/// The maximum character length of an IOST account name.
+pub const ACCOUNT_NAME_LEN_MAX: usize = 11;
+
+/// Prefix of the ids of deployed contracts.
+pub const CONTRACT_ID_PREFIX: &str = "Contract";
+
+/// Suffix of the system contracts.
+pub const SYSTEM_CONTRACT_SUFFIX: &str = ".iost";
+
+/// An error which can be returned when parsing an IOST account name or contract id.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseIostNameError {
+    /// The name is under the minimum allowed length.
+    TooShort(usize),
+    /// The name is over the maximum allowed length.
+    TooLong(usize),
+    /// The name contains an unallowed character.
+    BadChar(char),
+    /// The contract id is neither a `Contract...` id nor a `*.iost` system contract.
+    BadContractId(String),
+}
+
+impl fmt::Display for ParseIostNameError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooShort(len) => write!(
+                f,
+                "account name is too short ({} chars), must be {} chars or more",
+                len, ACCOUNT_NAME_LEN_MIN
+            ),
+            Self::TooLong(len) => write!(
+                f,
+                "account name is too long ({} chars), must be {} chars or less",
+                len, ACCOUNT_NAME_LEN_MAX
+            ),
+            Self::BadChar(c) => write!(
+                f,
+                "account name contains invalid character '{}'; must only contain lowercase letters, digits and '_'",
+                c
+            ),
+            Self::BadContractId(id) => write!(
+                f,
+                "invalid contract id '{}'; must be a {}... id or a *{} system contract",
+                id, CONTRACT_ID_PREFIX, SYSTEM_CONTRACT_SUFFIX
+            ),
+        }
+    }
+}
+
+impl From<ParseIostNameError> for crate::error::Error {
+    fn from(e: ParseIostNameError) -> crate::error::Error {
+        crate::Error::ParseIostNameErr(e)
+    }
+}
+
+#[inline]
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'
+}
+
+/// Checks a name against the IOST account rules: 5 to 11 chars, lowercase letters, digits and `_`.
+///
+/// # Examples
+///
+/// ```
+/// use iost_chain::{validate_account_name, ParseIostNameError};
+/// assert_eq!(validate_account_name("admin"), Ok(()));
+/// assert_eq!(validate_account_name("lisp_czz_01"), Ok(()));
+/// assert_eq!(validate_account_name("abcd"), Err(ParseIostNameError::TooShort(4)));
+/// assert_eq!(validate_account_name("abcdefghijkl"), Err(ParseIostNameError::TooLong(12)));
+/// assert_eq!(validate_account_name("Admin"), Err(ParseIostNameError::BadChar('A')));
+/// assert_eq!(validate_account_name("token.iost"), Err(ParseIostNameError::BadChar('.')));
+/// ```
+pub fn validate_account_name(name: &str) -> Result<(), ParseIostNameError> {
+    if let Some(c) = name.chars().find(|c| !is_name_char(*c)) {
+        return Err(ParseIostNameError::BadChar(c));
+    }
+    match name.len() {
+        len if len < ACCOUNT_NAME_LEN_MIN => Err(ParseIostNameError::TooShort(len)),
+        len if len > ACCOUNT_NAME_LEN_MAX => Err(ParseIostNameError::TooLong(len)),
+        _ => Ok(()),
+    }
+}
+
+/// Checks a contract id: either `Contract` followed by a base58 hash, or a `*.iost` system contract.
+///
+/// # Examples
+///
+/// ```
+/// use iost_chain::validate_contract_id;
+/// assert!(validate_contract_id("token.iost").is_ok());
+/// assert!(validate_contract_id("vote_producer.iost").is_ok());
+/// assert!(validate_contract_id("ContractEyRKXxgXHYBWnQMDLafdqDBFvbfxgVjS2XUGkM7VuNoB").is_ok());
+/// assert!(validate_contract_id("Contract").is_err());
+/// assert!(validate_contract_id("Contract0OIl").is_err());
+/// assert!(validate_contract_id(".iost").is_err());
+/// assert!(validate_contract_id("Token.iost").is_err());
+/// assert!(validate_contract_id("admin").is_err());
+/// ```
+pub fn validate_contract_id(id: &str) -> Result<(), ParseIostNameError> {
+    let valid = if let Some(hash) = id.strip_prefix(CONTRACT_ID_PREFIX) {
+        !hash.is_empty() && bs58::decode(hash).into_vec().is_ok()
+    } else if let Some(name) = id.strip_suffix(SYSTEM_CONTRACT_SUFFIX) {
+        !name.is_empty() && name.chars().all(is_name_char)
+    } else {
+        false
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(ParseIostNameError::BadContractId(id.to_string()))
+    }
+}
+
+macro_rules! declare_iost_name_types {
+    ($($ident:ident => $validate:ident;)*) => ($(
+        #[derive(Debug, PartialEq, Eq, Clone, Default, Hash, PartialOrd, Ord)]
+        pub struct $ident(String);
+
+        impl $ident {
+            #[inline]
+            pub fn as_str(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl FromStr for $ident {
+            type Err = ParseIostNameError;
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $validate(s)?;
+                Ok(Self(s.to_string()))
+            }
+        }
+
+        impl TryFrom<&str> for $ident {
+            type Error = ParseIostNameError;
+            #[inline]
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                Self::from_str(value)
+            }
+        }
+
+        impl TryFrom<String> for $ident {
+            type Error = ParseIostNameError;
+            #[inline]
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                $validate(value.as_str())?;
+                Ok(Self(value))
+            }
+        }
+
+        impl AsRef<str> for $ident {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl fmt::Display for $ident {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl From<$ident> for String {
+            #[inline]
+            fn from(i: $ident) -> Self {
+                i.0
+            }
+        }
+
+        impl PartialEq<str> for $ident {
+            #[inline]
+            fn eq(&self, other: &str) -> bool {
+                self.as_str() == other
+            }
+        }
+
+        impl PartialEq<String> for $ident {
+            #[inline]
+            fn eq(&self, other: &String) -> bool {
+                self.as_str() == other.as_str()
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl<'de> serde::Deserialize<'de> for $ident {
+            #[inline]
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let value = String::deserialize(deserializer)?;
+                Self::try_from(value).map_err(serde::de::Error::custom)
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl serde::Serialize for $ident {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+    )*)
+}
+
+declare_iost_name_types! {
+    IostAccountName => validate_account_name;
+    ContractId => validate_contract_id;
+}
+
+/// Checks a signer of a transaction, an account name optionally followed by `@permission`.
+pub fn validate_signer(signer: &str) -> Result<(), ParseIostNameError> {
+    let account = signer.split('@').next().unwrap_or_default();
+    validate_account_name(account)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_account_name_be_ok() {
+        let name = IostAccountName::from_str("lispczz3").unwrap();
+        assert_eq!(name.as_str(), "lispczz3");
+        assert_eq!(name, "lispczz3".to_string());
+        assert_eq!(
+            IostAccountName::try_from("lisp-czz".to_string()),
+            Err(ParseIostNameError::BadChar('-'))
+        );
+    }
+
+    #[test]
+    fn should_account_name_deserialize_be_validated() {
+        let name: IostAccountName = serde_json::from_str("\"admin\"").unwrap();
+        assert_eq!(serde_json::to_string(&name).unwrap(), "\"admin\"");
+        assert!(serde_json::from_str::<IostAccountName>("\"adm\"").is_err());
+    }
+
+    #[test]
+    fn should_signer_be_validated() {
+        assert!(validate_signer("lispczz3").is_ok());
+        assert!(validate_signer("lispczz3@active").is_ok());
+        assert_eq!(
+            validate_signer("LISPczz3@active"),
+            Err(ParseIostNameError::BadChar('L'))
+        );
+    }
+
+    #[test]
+    fn should_contract_id_be_ok() {
+        let id = ContractId::from_str("token.iost").unwrap();
+        assert_eq!(id.as_str(), "token.iost");
+        assert_eq!(
+            ContractId::from_str("token"),
+            Err(ParseIostNameError::BadContractId("token".to_string()))
+        );
+    }
+}
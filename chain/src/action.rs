@@ -4,14 +4,17 @@ use alloc::vec::Vec;
 use core::str::FromStr;
 
 use codec::{Decode, Encode};
-use lite_json::{JsonValue, Serialize};
+use lite_json::{parse_json, JsonValue, Serialize};
 #[cfg(feature = "std")]
 use serde::{
     ser::{SerializeStruct, Serializer},
     Deserialize, Serialize as SerSerialize,
 };
 
-use crate::{Error, NumberBytes, Read, ReadError, SerializeData, Write, WriteError};
+use crate::{
+    validate_account_name, validate_contract_id, Error, NumberBytes, Read, ReadError,
+    SerializeData, Write, WriteError,
+};
 
 #[derive(Clone, Default, Debug, PartialEq, Encode, Decode, SerializeData)]
 #[iost_root_path = "crate"]
@@ -117,6 +120,7 @@ impl IostAction {
         action_transfer: ActionTransfer,
     ) -> crate::Result<Self> {
         // let data = serde_json::to_string(&action_transfer).unwrap();
+        validate_contract_id(contract.as_ref())?;
         Ok(IostAction {
             contract: contract.as_ref().as_bytes().to_vec(),
             action_name: action_name.as_ref().as_bytes().to_vec(),
@@ -143,6 +147,16 @@ impl IostAction {
         }
     }
 
+    /// Parse `data` as the JSON array of call arguments
+    pub fn args(&self) -> crate::Result<Vec<JsonValue>> {
+        let data =
+            core::str::from_utf8(self.data.as_slice()).map_err(|_| Error::JsonParserError())?;
+        match parse_json(data).map_err(|_| Error::JsonParserError())? {
+            JsonValue::Array(args) => Ok(args),
+            _ => Err(Error::JsonParserError()),
+        }
+    }
+
     pub fn no_std_serialize(&self) -> JsonValue {
         let shadow_action = Action::from_action(self).unwrap();
         let object = JsonValue::Object(vec![
@@ -225,6 +239,10 @@ impl ActionTransfer {
     }
 
     pub fn from_str<T: AsRef<str>>(from: T, to: T, amount: T, memo: T) -> crate::Result<Self> {
+        validate_account_name(from.as_ref())?;
+        // contracts can hold tokens too
+        validate_account_name(to.as_ref())
+            .or_else(|e| validate_contract_id(to.as_ref()).map_err(|_| e))?;
         Ok(ActionTransfer {
             token_type: String::from("iost"),
             from: from.as_ref().to_string(),
@@ -307,8 +325,6 @@ impl FromStr for IostAction {
 mod test {
     use core::iter::FromIterator;
 
-    use lite_json::parse_json;
-
     use super::*;
 
     #[test]
@@ -1,12 +1,18 @@
-use alloc::string::String;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
+use core::iter::FromIterator;
 
 use lite_json::JsonValue;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
-use crate::{NumberBytes, Read, SerializeData, Write};
+use crate::Error::{
+    FixedParseAbnormalChar, FixedParseAmountFormat, FixedParseDoubleDot, FixedParseOverflow,
+    JsonParserError,
+};
+use crate::{IostAction, NumberBytes, Read, SerializeData, TokenTransfer, Write, UNLIMITED};
 
 #[derive(Clone, Default, Debug, NumberBytes, Write, Read, SerializeData)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -14,9 +20,8 @@ use crate::{NumberBytes, Read, SerializeData, Write};
 pub struct AmountLimit {
     /// token name
     pub token: String,
-    /// corresponding token limit
-    // #[cfg(feature = "std")]
-    // #[serde(rename = "val")]
+    /// corresponding token limit, `val` in ABI files
+    #[cfg_attr(feature = "std", serde(alias = "val"))]
     pub value: String,
 }
 //
@@ -63,11 +68,183 @@ pub struct AmountLimit {
 //     }
 // }
 
+/// Token spending actions: (contract, action name, token, index of the spender, index of the amount).
+/// A `None` token means the token is the first argument of the call.
+const SPENDING_ACTIONS: [(&str, &str, Option<&str>, usize, usize); 4] = [
+    ("token.iost", "transfer", None, 1, 3),
+    ("token.iost", "transferFreeze", None, 1, 3),
+    ("token.iost", "destroy", None, 1, 2),
+    ("gas.iost", "pledge", Some("iost"), 0, 2),
+];
+
+/// Parse a non negative decimal amount such as "10.5" into its digits and its number of decimals
+pub fn parse_amount(amount: &str) -> crate::Result<(u128, u32)> {
+    if amount.is_empty() || amount.starts_with('.') || amount.ends_with('.') {
+        return Err(FixedParseAmountFormat());
+    }
+    let mut value: u128 = 0;
+    let mut decimals: Option<u32> = None;
+    for c in amount.chars() {
+        match c {
+            '.' => {
+                if decimals.is_some() {
+                    return Err(FixedParseDoubleDot());
+                }
+                decimals = Some(0);
+            }
+            '0'..='9' => {
+                value = value
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add(c as u128 - '0' as u128))
+                    .ok_or(FixedParseOverflow())?;
+                if let Some(d) = decimals.as_mut() {
+                    *d += 1;
+                }
+            }
+            _ => return Err(FixedParseAbnormalChar()),
+        }
+    }
+    Ok((value, decimals.unwrap_or(0)))
+}
+
+fn rescale(value: u128, from: u32, to: u32) -> crate::Result<u128> {
+    10_u128
+        .checked_pow(to - from)
+        .and_then(|m| value.checked_mul(m))
+        .ok_or(FixedParseOverflow())
+}
+
+/// Format digits and a number of decimals back to a decimal amount
+pub fn format_amount(value: u128, decimals: u32) -> String {
+    let digits = value.to_string();
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return digits;
+    }
+    let digits = if digits.len() <= decimals {
+        let mut padded =
+            String::from_iter(core::iter::repeat('0').take(decimals + 1 - digits.len()));
+        padded.push_str(&digits);
+        padded
+    } else {
+        digits
+    };
+    let (int, frac) = digits.split_at(digits.len() - decimals);
+    let mut s = String::from(int);
+    s.push('.');
+    s.push_str(frac);
+    s
+}
+
+/// Sums amounts per token
+#[derive(Default)]
+struct AmountSum(BTreeMap<String, (u128, u32)>);
+
+impl AmountSum {
+    fn add(&mut self, token: &str, amount: &str) -> crate::Result<()> {
+        let (value, decimals) = parse_amount(amount)?;
+        let (sum, sum_decimals) = self.0.entry(token.to_string()).or_insert((0, 0));
+        let result = if decimals > *sum_decimals {
+            let sum = rescale(*sum, *sum_decimals, decimals)?;
+            *sum_decimals = decimals;
+            sum.checked_add(value)
+        } else {
+            sum.checked_add(rescale(value, decimals, *sum_decimals)?)
+        };
+        *sum = result.ok_or(FixedParseOverflow())?;
+        Ok(())
+    }
+
+    fn into_limits(self) -> Vec<AmountLimit> {
+        self.0
+            .into_iter()
+            .map(|(token, (value, decimals))| AmountLimit {
+                token,
+                value: format_amount(value, decimals),
+            })
+            .collect()
+    }
+}
+
+fn is_spender<S: AsRef<str>>(spenders: &[S], account: &str) -> bool {
+    spenders.iter().any(|s| s.as_ref() == account)
+}
+
 impl AmountLimit {
     pub fn new(token: String, value: String) -> Self {
         AmountLimit { token, value }
     }
 
+    /// Minimal amount limits covering the tokens `spenders` transfer, destroy or pledge in `actions`.
+    /// Spending hidden inside other contracts, such as buying RAM, is not visible here;
+    /// use the transfers of a pre executed receipt with `from_transfers` for those.
+    pub fn from_actions<S: AsRef<str>>(
+        spenders: &[S],
+        actions: &[IostAction],
+    ) -> crate::Result<Vec<AmountLimit>> {
+        let mut sum = AmountSum::default();
+        for action in actions {
+            let spending = SPENDING_ACTIONS.iter().find(|(contract, action_name, ..)| {
+                action.contract.as_slice() == contract.as_bytes()
+                    && action.action_name.as_slice() == action_name.as_bytes()
+            });
+            if let Some((_, _, token, spender_index, amount_index)) = spending {
+                let args = action.args()?;
+                let arg = |i: usize| match args.get(i) {
+                    Some(JsonValue::String(chars)) => Ok(String::from_iter(chars.iter())),
+                    _ => Err(JsonParserError()),
+                };
+                if !is_spender(spenders, &arg(*spender_index)?) {
+                    continue;
+                }
+                let token = match token {
+                    Some(token) => token.to_string(),
+                    None => arg(0)?,
+                };
+                sum.add(&token, &arg(*amount_index)?)?;
+            }
+        }
+        Ok(sum.into_limits())
+    }
+
+    /// Minimal amount limits covering the token transfers sent by `spenders`
+    pub fn from_transfers<S: AsRef<str>>(
+        spenders: &[S],
+        transfers: &[TokenTransfer],
+    ) -> crate::Result<Vec<AmountLimit>> {
+        let mut sum = AmountSum::default();
+        for transfer in transfers.iter().filter(|t| is_spender(spenders, &t.from)) {
+            sum.add(&transfer.token, &transfer.amount)?;
+        }
+        Ok(sum.into_limits())
+    }
+
+    /// Sum of amount limits per token, an "unlimited" limit absorbing the others of its token
+    pub fn sum(limits: &[AmountLimit]) -> crate::Result<Vec<AmountLimit>> {
+        let mut sum = AmountSum::default();
+        let mut unlimited: Vec<&str> = vec![];
+        for limit in limits {
+            if limit.value == UNLIMITED {
+                unlimited.push(&limit.token);
+            } else {
+                sum.add(&limit.token, &limit.value)?;
+            }
+        }
+        let mut limits: Vec<AmountLimit> = sum
+            .into_limits()
+            .into_iter()
+            .filter(|l| !unlimited.contains(&l.token.as_str()))
+            .collect();
+        unlimited.sort_unstable();
+        unlimited.dedup();
+        limits.extend(
+            unlimited
+                .into_iter()
+                .map(|token| AmountLimit::new(token.to_string(), UNLIMITED.to_string())),
+        );
+        Ok(limits)
+    }
+
     pub fn no_std_serialize(&self) -> JsonValue {
         let object = JsonValue::Object(vec![
             (
@@ -83,3 +260,68 @@ impl AmountLimit {
         // String::from_utf8(object.format(4)).unwrap()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn amount_should_be_parsed_and_formatted() {
+        assert_eq!(parse_amount("10").unwrap(), (10, 0));
+        assert_eq!(parse_amount("10.05").unwrap(), (1005, 2));
+        assert_eq!(format_amount(1005, 2), "10.05");
+        assert_eq!(format_amount(5, 3), "0.005");
+        assert!(matches!(parse_amount("1.2.3"), Err(FixedParseDoubleDot())));
+        assert!(matches!(parse_amount("-1"), Err(FixedParseAbnormalChar())));
+        assert!(matches!(parse_amount("1."), Err(FixedParseAmountFormat())));
+    }
+
+    #[test]
+    fn amount_limit_from_actions_should_be_ok() {
+        let actions = vec![
+            IostAction::transfer("admin", "lispczz3", "10.5", "").unwrap(),
+            IostAction::transfer("admin", "lispczz4", "0.25", "").unwrap(),
+            IostAction::transfer("lispczz3", "admin", "100", "").unwrap(),
+            IostAction::new(
+                "gas.iost".to_string(),
+                "pledge".to_string(),
+                r#"["admin","admin","20"]"#.to_string(),
+            ),
+            IostAction::new(
+                "token.iost".to_string(),
+                "transfer".to_string(),
+                r#"["bnb","admin","lispczz3","1",""]"#.to_string(),
+            ),
+        ];
+        let limits = AmountLimit::from_actions(&["admin"], &actions).unwrap();
+        assert_eq!(limits.len(), 2);
+        assert_eq!(limits[0].token, "bnb");
+        assert_eq!(limits[0].value, "1");
+        assert_eq!(limits[1].token, "iost");
+        assert_eq!(limits[1].value, "30.75");
+    }
+
+    #[test]
+    fn amount_limits_should_be_summed() {
+        let limit =
+            |token: &str, value: &str| AmountLimit::new(token.to_string(), value.to_string());
+        let limits = AmountLimit::sum(&[
+            limit("iost", "10.5"),
+            limit("bnb", "unlimited"),
+            limit("iost", "0.25"),
+            limit("bnb", "3"),
+            limit("bnb", "unlimited"),
+        ])
+        .unwrap();
+        assert_eq!(limits.len(), 2);
+        assert_eq!(
+            (limits[0].token.as_str(), limits[0].value.as_str()),
+            ("iost", "10.75")
+        );
+        assert_eq!(
+            (limits[1].token.as_str(), limits[1].value.as_str()),
+            ("bnb", UNLIMITED)
+        );
+        assert!(AmountLimit::sum(&[limit("iost", "ten")]).is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use lite_json::{JsonValue, Serialize};
+
+#[cfg(feature = "std")]
+use alloc::format;
+
+#[cfg(feature = "std")]
+use crate::{spv, Error::IOSTBlockVerifyError, Result, TxReceipt};
+use crate::{IostAction, Tx, TxBuilder};
+
+/// Contract holding the delayed transaction functions
+pub const SYSTEM_CONTRACT: &str = "system.iost";
+/// Action cancelling a delayed transaction which is not executed yet
+pub const CANCEL_DELAY_TX: &str = "cancelDelaytx";
+
+impl IostAction {
+    /// `system.iost/cancelDelaytx` for the delayed transaction `tx_hash`
+    pub fn cancel_delay_tx<T: AsRef<str>>(tx_hash: T) -> IostAction {
+        let data = JsonValue::Array(vec![JsonValue::String(
+            tx_hash.as_ref().chars().collect::<Vec<_>>(),
+        )]);
+        IostAction {
+            contract: SYSTEM_CONTRACT.as_bytes().to_vec(),
+            action_name: CANCEL_DELAY_TX.as_bytes().to_vec(),
+            data: data.serialize(),
+        }
+    }
+
+    /// Hash of the delayed transaction cancelled by this action, if it is a `cancelDelaytx`
+    pub fn cancelled_tx(&self) -> Option<String> {
+        if self.contract != SYSTEM_CONTRACT.as_bytes()
+            || self.action_name != CANCEL_DELAY_TX.as_bytes()
+        {
+            return None;
+        }
+        match self.args().ok()?.as_slice() {
+            [JsonValue::String(hash)] => Some(hash.iter().collect()),
+            _ => None,
+        }
+    }
+}
+
+impl Tx {
+    pub fn is_delayed(&self) -> bool {
+        self.delay > 0
+    }
+
+    /// The earliest time the transaction is executed. Unixepoch in nanoseconds
+    pub fn execution_time(&self) -> i64 {
+        self.time.saturating_add(self.delay)
+    }
+}
+
+impl TxBuilder {
+    /// Add a `cancelDelaytx` action revoking the delayed transaction `tx_hash`
+    pub fn cancel_delay_tx<T: AsRef<str>>(self, tx_hash: T) -> Self {
+        self.action(IostAction::cancel_delay_tx(tx_hash))
+    }
+}
+
+#[cfg(feature = "std")]
+impl spv::Tx {
+    /// Hash of the delayed transaction whose deferred execution is this transaction, decoded
+    /// from the base64 `referredTx` blocks carry
+    pub fn referring_tx_hash(&self) -> Result<Option<Vec<u8>>> {
+        match self.referred_tx.as_deref() {
+            None | Some("") => Ok(None),
+            Some(referred_tx) => base64::decode(referred_tx)
+                .map(Some)
+                .map_err(|_| IOSTBlockVerifyError(format!("invalid referred tx {}", referred_tx))),
+        }
+    }
+
+    /// Whether this transaction is the deferred execution of a delayed transaction
+    pub fn is_deferred(&self) -> bool {
+        self.referred_tx
+            .as_deref()
+            .map_or(false, |referred_tx| !referred_tx.is_empty())
+    }
+}
+
+#[cfg(feature = "std")]
+impl spv::Block {
+    /// Deferred executions in the block, with the hash of the delayed transaction they refer to.
+    /// Those whose `referredTx` is not base64 are left out, their hash can not be computed either
+    pub fn deferred_txs(&self) -> impl Iterator<Item = (Vec<u8>, &spv::Tx, &TxReceipt)> {
+        self.txs
+            .iter()
+            .zip(self.receipts.iter())
+            .filter_map(|(tx, receipt)| {
+                let hash = tx.referring_tx_hash().ok()??;
+                Some((hash, tx, receipt))
+            })
+    }
+
+    /// The deferred execution of the delayed transaction `tx_hash`, the base58 hash `sendTx`
+    /// returns, if it is in the block
+    pub fn find_deferred(&self, tx_hash: &str) -> Option<(&spv::Tx, &TxReceipt)> {
+        let tx_hash = bs58::decode(tx_hash).into_vec().ok()?;
+        self.deferred_txs()
+            .find(|(hash, _, _)| *hash == tx_hash)
+            .map(|(_, tx, receipt)| (tx, receipt))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spv::{Head, Sign, TxReceiptStatus};
+    use crate::MAINNET_CHAIN_ID;
+
+    const DELAYED_TX_HASH: &str = "6HfKqnHw8kqQoZDrNLHpwh9zTJqhAFDwQXbTuhyxuhps";
+
+    fn delayed_tx_hash() -> Vec<u8> {
+        bs58::decode(DELAYED_TX_HASH).into_vec().unwrap()
+    }
+
+    fn tx(referred_tx: &str) -> spv::Tx {
+        serde_json::from_str(&format!(
+            r#"{{
+                "time": "1598918258274417000",
+                "expiration": "1598918348274417000",
+                "gasRatio": "100",
+                "gasLimit": "100000000",
+                "delay": "0",
+                "chain_id": 1024,
+                "actions": [],
+                "amountLimit": [],
+                "publisher": "admin",
+                "publishSigns": [],
+                "signers": [],
+                "signs": [],
+                "referredTx": "{}",
+                "reserved": null
+            }}"#,
+            referred_tx
+        ))
+        .unwrap()
+    }
+
+    fn receipt(gas_usage: &str) -> TxReceipt {
+        TxReceipt {
+            tx_hash: String::new(),
+            gas_usage: gas_usage.to_string(),
+            ram_usage: Default::default(),
+            status: TxReceiptStatus {
+                code: 0,
+                message: String::new(),
+            },
+            returns: vec![],
+            receipts: vec![],
+        }
+    }
+
+    #[test]
+    fn cancel_delay_tx_action_should_be_ok() {
+        let action = IostAction::cancel_delay_tx(DELAYED_TX_HASH);
+        assert_eq!(action.contract, b"system.iost".to_vec());
+        assert_eq!(action.action_name, b"cancelDelaytx".to_vec());
+        assert_eq!(
+            action.data,
+            format!("[\"{}\"]", DELAYED_TX_HASH).into_bytes()
+        );
+        assert_eq!(action.cancelled_tx(), Some(DELAYED_TX_HASH.to_string()));
+
+        let transfer = IostAction::transfer("admin", "lispczz3", "1", "").unwrap();
+        assert_eq!(transfer.cancelled_tx(), None);
+    }
+
+    #[test]
+    fn delayed_tx_should_be_built() {
+        let time = 1598918258274417000;
+        let tx = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time(time)
+            .delay(3600 * 1_000_000_000)
+            .publisher("admin")
+            .action(IostAction::transfer("admin", "lispczz3", "10", "").unwrap())
+            .build()
+            .unwrap();
+        assert!(tx.is_delayed());
+        assert_eq!(tx.execution_time(), time + 3600 * 1_000_000_000);
+
+        let cancel = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time(time)
+            .publisher("admin")
+            .cancel_delay_tx(DELAYED_TX_HASH)
+            .build()
+            .unwrap();
+        assert!(!cancel.is_delayed());
+        assert_eq!(
+            cancel.actions[0].cancelled_tx(),
+            Some(DELAYED_TX_HASH.to_string())
+        );
+    }
+
+    #[test]
+    fn deferred_tx_should_refer_to_delayed_tx() {
+        // blocks carry the referred tx hash in base64
+        let deferred = tx(&base64::encode(delayed_tx_hash()));
+        assert!(deferred.is_deferred());
+        assert_eq!(
+            deferred.referring_tx_hash().unwrap(),
+            Some(delayed_tx_hash())
+        );
+
+        let normal = tx("");
+        assert!(!normal.is_deferred());
+        assert_eq!(normal.referring_tx_hash().unwrap(), None);
+        assert_ne!(deferred.hash().unwrap(), normal.hash().unwrap());
+
+        let malformed = tx("not base64!");
+        assert!(malformed.is_deferred());
+        assert!(malformed.referring_tx_hash().is_err());
+        assert!(malformed.hash().is_err());
+    }
+
+    #[test]
+    fn deferred_tx_should_be_found_by_the_hash_of_the_delayed_tx() {
+        let block = spv::Block {
+            head: Head {
+                version: 1,
+                parent_hash: vec![],
+                tx_merkle_hash: vec![],
+                tx_receipt_merkle_hash: vec![],
+                info: vec![],
+                number: 1200,
+                witness: String::new(),
+                time: 1598918258274417000,
+            },
+            sign: Sign {
+                algorithm: 2,
+                sig: String::new(),
+                pub_key: String::new(),
+            },
+            receipts: vec![receipt("100"), receipt("200"), receipt("300")],
+            txs: vec![
+                tx(""),
+                tx(&base64::encode(delayed_tx_hash())),
+                tx("not base64!"),
+            ],
+            tx_hashes: vec![],
+            receipt_hashes: vec![],
+            block_type: String::new(),
+        };
+        let deferred: Vec<_> = block.deferred_txs().collect();
+        assert_eq!(deferred.len(), 1);
+        assert_eq!(deferred[0].0, delayed_tx_hash());
+
+        let (_, receipt) = block.find_deferred(DELAYED_TX_HASH).unwrap();
+        assert_eq!(receipt.gas_usage, "200");
+        // the hash sendTx returns is base58, not the base64 of the block
+        assert!(block
+            .find_deferred(&base64::encode(delayed_tx_hash()))
+            .is_none());
+        assert!(block.find_deferred("").is_none());
+    }
+}
@@ -0,0 +1,289 @@
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::string::ToString;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use serde::Deserialize;
+#[cfg(feature = "std")]
+use serde_json::{json, Value};
+
+#[cfg(feature = "std")]
+use crate::{validate_contract_id, IostAction, ABI, CONTRACT_ID_PREFIX};
+
+/// Language of the contracts the node runs
+pub const JAVASCRIPT: &str = "javascript";
+
+/// Version of a contract whose ABI file has none
+pub const DEFAULT_CONTRACT_VERSION: &str = "1.0.0";
+
+/// An error which can be returned when building the deployment of a contract or reading the id of
+/// the deployed contract.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DeployError {
+    /// The ABI file is not a JSON object with an "abi" array of ABIs.
+    MalformedAbiFile(String),
+    /// The contract has no code.
+    EmptyCode,
+    /// No return value of the transaction is a contract id.
+    MissingContractId,
+}
+
+impl fmt::Display for DeployError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MalformedAbiFile(reason) => write!(f, "malformed ABI file: {}", reason),
+            Self::EmptyCode => write!(f, "the contract has no code"),
+            Self::MissingContractId => {
+                write!(f, "no contract id in the returns of the transaction")
+            }
+        }
+    }
+}
+
+impl From<DeployError> for crate::error::Error {
+    fn from(e: DeployError) -> crate::error::Error {
+        crate::Error::DeployErr(e)
+    }
+}
+
+/// Builds the `system.iost` action deploying a contract: `setCode` for a new contract, or
+/// `updateCode` for a deployed one. The contract goes in the envelope the node expects,
+/// `{"ID", "info": {"lang", "version", "abi"}, "code"}`
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct ContractDeploy {
+    code: String,
+    lang: String,
+    version: String,
+    abi: Vec<ABI>,
+    /// id of the deployed contract and data given to its `can_update`
+    update: Option<(String, String)>,
+}
+
+#[cfg(feature = "std")]
+fn malformed<T: ToString>(reason: T) -> DeployError {
+    DeployError::MalformedAbiFile(reason.to_string())
+}
+
+#[cfg(feature = "std")]
+impl ContractDeploy {
+    /// Deployment of the JavaScript `code` of a new contract with its ABI file,
+    /// `{"lang", "version", "abi": [{"name", "args", "amountLimit"}]}`
+    pub fn new(code: &str, abi_file: &str) -> crate::Result<Self> {
+        if code.trim().is_empty() {
+            return Err(DeployError::EmptyCode.into());
+        }
+        let file: Value = serde_json::from_str(abi_file).map_err(malformed)?;
+        let abi = match &file["abi"] {
+            abis @ Value::Array(_) => Vec::<ABI>::deserialize(abis).map_err(malformed)?,
+            _ => return Err(malformed("no abi array").into()),
+        };
+        for abi in abi.iter() {
+            abi.arg_types()?;
+        }
+        let lang = file["lang"].as_str().unwrap_or(JAVASCRIPT);
+        let version = file["version"].as_str().unwrap_or(DEFAULT_CONTRACT_VERSION);
+        Ok(ContractDeploy {
+            code: code.to_string(),
+            lang: lang.to_string(),
+            version: version.to_string(),
+            abi,
+            update: None,
+        })
+    }
+
+    /// Update the deployed contract `contract` instead, `data` being given to its `can_update`
+    pub fn update<C: AsRef<str>, D: AsRef<str>>(mut self, contract: C, data: D) -> Self {
+        self.update = Some((contract.as_ref().to_string(), data.as_ref().to_string()));
+        self
+    }
+
+    pub fn abi(&self) -> &[ABI] {
+        &self.abi
+    }
+
+    /// JSON of the contract as `setCode` and `updateCode` take it
+    pub fn envelope(&self) -> String {
+        let abi: Vec<Value> = self
+            .abi
+            .iter()
+            .map(|abi| {
+                json!({
+                    "name": abi.name,
+                    "args": abi.args,
+                    "amountLimit": abi.amount_limit.iter().map(|limit| json!({
+                        "token": limit.token,
+                        "val": limit.value,
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let id = self.update.as_ref().map_or("", |(id, _)| id.as_str());
+        json!({
+            "ID": id,
+            "info": {
+                "lang": self.lang,
+                "version": self.version,
+                "abi": abi,
+            },
+            "code": self.code,
+        })
+        .to_string()
+    }
+
+    pub fn action(&self) -> crate::Result<IostAction> {
+        let (action_name, args) = match &self.update {
+            Some((id, data)) => {
+                validate_contract_id(id)?;
+                ("updateCode", json!([self.envelope(), data]))
+            }
+            None => ("setCode", json!([self.envelope()])),
+        };
+        Ok(IostAction::new(
+            "system.iost".to_string(),
+            action_name.to_string(),
+            args.to_string(),
+        ))
+    }
+
+    /// Id of the contract once the transaction is executed, read from the `returns` of its
+    /// receipt for a new contract
+    pub fn contract_id(&self, returns: &[String]) -> crate::Result<String> {
+        match &self.update {
+            Some((id, _)) => Ok(id.clone()),
+            None => deployed_contract_id(returns),
+        }
+    }
+}
+
+/// Id of the contract deployed by a transaction, from the `returns` of its receipt: `setCode`
+/// returns `["Contract..."]` while the other actions return their own values
+#[cfg(feature = "std")]
+pub fn deployed_contract_id(returns: &[String]) -> crate::Result<String> {
+    returns
+        .iter()
+        .filter_map(|r| match serde_json::from_str(r) {
+            Ok(Value::Array(values)) => match values.as_slice() {
+                [Value::String(id)] => Some(id.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .find(|id| id.starts_with(CONTRACT_ID_PREFIX) && validate_contract_id(id).is_ok())
+        .ok_or_else(|| DeployError::MissingContractId.into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AbiError, Error};
+
+    const CODE: &str =
+        "class Exchange { init() {} deposit(account, amount) {} }\nmodule.exports = Exchange;";
+
+    const ABI_FILE: &str = r#"{
+        "lang": "javascript",
+        "version": "1.0.0",
+        "abi": [
+            {
+                "name": "deposit",
+                "args": ["string", "string"],
+                "amountLimit": [{ "token": "iost", "val": "100" }]
+            },
+            { "name": "can_update", "args": ["string"] }
+        ]
+    }"#;
+
+    #[test]
+    fn set_code_should_wrap_contract() {
+        let deploy = ContractDeploy::new(CODE, ABI_FILE).unwrap();
+        assert_eq!(deploy.abi().len(), 2);
+        assert_eq!(deploy.abi()[0].amount_limit[0].value, "100");
+
+        let action = deploy.action().unwrap();
+        assert_eq!(action.contract, b"system.iost".to_vec());
+        assert_eq!(action.action_name, b"setCode".to_vec());
+        let args: Vec<String> = serde_json::from_slice(&action.data).unwrap();
+        assert_eq!(args.len(), 1);
+        let contract: Value = serde_json::from_str(&args[0]).unwrap();
+        assert_eq!(contract["ID"], json!(""));
+        assert_eq!(contract["code"], json!(CODE));
+        assert_eq!(contract["info"]["lang"], json!("javascript"));
+        assert_eq!(contract["info"]["version"], json!("1.0.0"));
+        assert_eq!(
+            contract["info"]["abi"][0],
+            json!({
+                "name": "deposit",
+                "args": ["string", "string"],
+                "amountLimit": [{ "token": "iost", "val": "100" }],
+            })
+        );
+    }
+
+    #[test]
+    fn update_code_should_target_contract() {
+        let id = "Contract6uHwbaqtHjnf3fpBGd5nsxHKbHGQR2JBzNUxH9XkBCYm";
+        let deploy = ContractDeploy::new(CODE, ABI_FILE)
+            .unwrap()
+            .update(id.to_string(), "admin");
+        let action = deploy.action().unwrap();
+        assert_eq!(action.action_name, b"updateCode".to_vec());
+        let args: Vec<String> = serde_json::from_slice(&action.data).unwrap();
+        assert_eq!(args[1], "admin");
+        let contract: Value = serde_json::from_str(&args[0]).unwrap();
+        assert_eq!(contract["ID"], json!(id));
+        assert_eq!(deploy.contract_id(&[]).unwrap(), id);
+
+        let deploy = ContractDeploy::new(CODE, ABI_FILE)
+            .unwrap()
+            .update("admin", "");
+        assert!(deploy.action().is_err());
+    }
+
+    #[test]
+    fn contract_id_should_be_read_from_returns() {
+        let deploy = ContractDeploy::new(CODE, ABI_FILE).unwrap();
+        let returns = vec![
+            "[]".to_string(),
+            r#"["Contract6uHwbaqtHjnf3fpBGd5nsxHKbHGQR2JBzNUxH9XkBCYm"]"#.to_string(),
+        ];
+        assert_eq!(
+            deploy.contract_id(&returns).unwrap(),
+            "Contract6uHwbaqtHjnf3fpBGd5nsxHKbHGQR2JBzNUxH9XkBCYm"
+        );
+        assert!(matches!(
+            deploy.contract_id(&["[\"admin\"]".to_string()]),
+            Err(Error::DeployErr(DeployError::MissingContractId))
+        ));
+    }
+
+    #[test]
+    fn malformed_contract_should_be_rejected() {
+        assert!(matches!(
+            ContractDeploy::new(" ", ABI_FILE),
+            Err(Error::DeployErr(DeployError::EmptyCode))
+        ));
+        for file in [
+            "",
+            "{}",
+            r#"{"abi": [{"args": []}]}"#,
+            r#"{"abi": [{"name": "f", "amountLimit": [{"token": "iost"}]}]}"#,
+            r#"{"abi": [{"name": "f", "args": "string"}]}"#,
+        ]
+        .iter()
+        {
+            assert!(matches!(
+                ContractDeploy::new(CODE, file),
+                Err(Error::DeployErr(DeployError::MalformedAbiFile(_)))
+            ));
+        }
+        assert!(matches!(
+            ContractDeploy::new(CODE, r#"{"abi": [{"name": "f", "args": ["int"]}]}"#),
+            Err(Error::AbiErr(AbiError::UnknownArgType { .. }))
+        ));
+    }
+}
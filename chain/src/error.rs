@@ -1,4 +1,7 @@
-use crate::{ErrorMessage, ParseNameError, ReadError, WriteError};
+use crate::{
+    AbiError, DeployError, ErrorMessage, ParseIostNameError, ParseNameError, ReadError,
+    SignUpError, TxBuildError, WriteError,
+};
 use alloc::string::String;
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -13,6 +16,7 @@ pub enum Error {
     ErrorMessage(ErrorMessage),
 
     ParseNameErr(ParseNameError),
+    ParseIostNameErr(ParseIostNameError),
 
     FixedParseOverflow(),
     FixedParseAbnormalChar(),
@@ -22,6 +26,13 @@ pub enum Error {
 
     InvalidSignature(),
     InvalidPublisherSignature(),
+    ///The secret key is malformed or out of range for its algorithm
+    InvalidSecretKey(),
+
+    TxBuildErr(TxBuildError),
+    AbiErr(AbiError),
+    DeployErr(DeployError),
+    SignUpErr(SignUpError),
 
     InvalidSPVStartBlock(i64),
     IOSTBlockError(),
@@ -30,4 +41,8 @@ pub enum Error {
     IOSTInvalidBlockSignature(),
     IOSTUpdateEpochError(String),
     IOSTBlockWitnessError(String),
+    IOSTSnapshotError(String),
+    IOSTDepositProofError(String),
+
+    ReceiptDecodeError(String),
 }
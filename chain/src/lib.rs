@@ -3,11 +3,12 @@
 extern crate alloc;
 
 pub mod abi;
+pub mod account_name;
 pub mod action;
 pub mod amount_limit;
 pub mod bytes;
-
-mod chain_test;
+pub mod delay_tx;
+pub mod deploy;
 
 pub mod spv;
 pub mod verify;
@@ -24,7 +25,9 @@ pub mod net_work_info;
 pub mod permission;
 pub mod pledge_info;
 pub mod ram_info;
+pub mod receipt_event;
 pub mod receipts;
+pub mod sign_up;
 pub mod signature;
 pub mod status;
 pub mod status_code;
@@ -32,6 +35,7 @@ pub mod test;
 pub mod time_point;
 pub mod transaction;
 pub mod tx;
+pub mod tx_builder;
 pub mod tx_receipt;
 pub mod tx_response;
 pub mod unsigned_int;
@@ -40,11 +44,15 @@ pub mod vote_info;
 mod get_chain_info;
 
 pub use iost_derive::*;
+/// Used by the bindings `contract_abi!` generates
+#[cfg(feature = "std")]
+pub use serde_json;
 
 pub use self::{
-    abi::*, action::*, amount_limit::*, bytes::*, error::*, frozen_balance::*, group::*, info::*,
-    item::*, key_field::*, message::*, names::*, net_work_info::*, permission::*, pledge_info::*,
-    ram_info::*, receipts::*, signature::*, status::*, status_code::*, transaction::*, tx::*,
+    abi::*, account_name::*, action::*, amount_limit::*, bytes::*, delay_tx::*, deploy::*,
+    error::*, frozen_balance::*, group::*, info::*, item::*, key_field::*, message::*, names::*,
+    net_work_info::*, permission::*, pledge_info::*, ram_info::*, receipt_event::*, receipts::*,
+    sign_up::*, signature::*, status::*, status_code::*, transaction::*, tx::*, tx_builder::*,
     tx_receipt::*, tx_response::*, unsigned_int::*, vote_info::*,
 };
 
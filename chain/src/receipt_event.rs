@@ -0,0 +1,404 @@
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::{format, vec::Vec};
+
+#[cfg(feature = "std")]
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::spv::WitnessStatus;
+#[cfg(feature = "std")]
+use crate::{spv::Block, Error::ReceiptDecodeError, Receipt, Result, TxReceipt};
+
+/// `token.iost/transfer`, content is `[token, from, to, amount, memo]`
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TokenTransfer {
+    /// token symbol, such as "iost"
+    pub token: String,
+    /// account the token is transferred from
+    pub from: String,
+    /// account the token is transferred to
+    pub to: String,
+    /// transferred amount, kept as the decimal string written by the contract
+    pub amount: String,
+    /// transfer memo
+    pub memo: String,
+}
+
+/// `token.iost/issue`, content is `[token, to, amount]`
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TokenIssue {
+    /// token symbol
+    pub token: String,
+    /// account receiving the issued token
+    pub to: String,
+    /// issued amount
+    pub amount: String,
+}
+
+/// `token.iost/transferFreeze`, content is `[token, from, to, amount, unfreeze_time, memo]`
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TokenTransferFreeze {
+    /// token symbol
+    pub token: String,
+    /// account the token is transferred from
+    pub from: String,
+    /// account the frozen token is transferred to
+    pub to: String,
+    /// transferred amount
+    pub amount: String,
+    /// time the amount is unfrozen. Unixepoch in nanoseconds
+    pub unfreeze_time: i64,
+    /// transfer memo
+    pub memo: String,
+}
+
+/// Receipts emitted by `ram.iost`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum RamEvent {
+    /// `ram.iost/buy`, content is `[payer, account, amount]`
+    Buy {
+        payer: String,
+        account: String,
+        amount: i64,
+    },
+    /// `ram.iost/sell`, content is `[account, receiver, amount]`
+    Sell {
+        account: String,
+        receiver: String,
+        amount: i64,
+    },
+    /// `ram.iost/lend`, content is `[from, to, amount]`
+    Lend {
+        from: String,
+        to: String,
+        amount: i64,
+    },
+    /// any other `ram.iost` receipt, with its raw content
+    Other { func_name: String, content: String },
+}
+
+/// Receipts emitted by `vote_producer.iost`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum VoteProducerEvent {
+    /// `vote_producer.iost/stat`, the witness lists of the next epoch
+    Stat(WitnessStatus),
+    /// `vote_producer.iost/vote`, content is `[voter, producer, amount]`
+    Vote {
+        voter: String,
+        producer: String,
+        amount: String,
+    },
+    /// `vote_producer.iost/unvote`, content is `[voter, producer, amount]`
+    Unvote {
+        voter: String,
+        producer: String,
+        amount: String,
+    },
+    /// any other `vote_producer.iost` receipt, with its raw content
+    Other { func_name: String, content: String },
+}
+
+/// A decoded `Receipt`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum ReceiptEvent {
+    Transfer(TokenTransfer),
+    Issue(TokenIssue),
+    TransferFreeze(TokenTransferFreeze),
+    Ram(RamEvent),
+    VoteProducer(VoteProducerEvent),
+    /// receipt of a contract or function without a typed decoder
+    Unknown {
+        func_name: String,
+        content: String,
+    },
+}
+
+#[cfg(feature = "std")]
+fn parse_content<T: DeserializeOwned>(func_name: &str, content: &str) -> Result<T> {
+    serde_json::from_str(content).map_err(|e| {
+        ReceiptDecodeError(format!(
+            "invalid content of receipt {}: {}, content: {}",
+            func_name, e, content
+        ))
+    })
+}
+
+#[cfg(feature = "std")]
+fn parse_i64(func_name: &str, value: &str) -> Result<i64> {
+    value.parse::<i64>().map_err(|_| {
+        ReceiptDecodeError(format!(
+            "invalid integer {} in receipt {}",
+            value, func_name
+        ))
+    })
+}
+
+/// The node writes integers in receipts either as JSON numbers or as strings.
+#[cfg(feature = "std")]
+fn value_to_i64(func_name: &str, value: &serde_json::Value) -> Result<i64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_i64().ok_or_else(|| {
+            ReceiptDecodeError(format!("invalid integer {} in receipt {}", n, func_name))
+        }),
+        serde_json::Value::String(s) => parse_i64(func_name, s),
+        _ => Err(ReceiptDecodeError(format!(
+            "invalid integer {} in receipt {}",
+            value, func_name
+        ))),
+    }
+}
+
+#[cfg(feature = "std")]
+impl Receipt {
+    /// Decode the receipt content according to `func_name`.
+    /// Receipts without a typed decoder are returned as `ReceiptEvent::Unknown`.
+    pub fn decode(&self) -> Result<ReceiptEvent> {
+        let func_name = self.func_name.as_str();
+        let content = self.content.as_str();
+        let event = match func_name {
+            "token.iost/transfer" => {
+                let (token, from, to, amount, memo) = parse_content(func_name, content)?;
+                ReceiptEvent::Transfer(TokenTransfer {
+                    token,
+                    from,
+                    to,
+                    amount,
+                    memo,
+                })
+            }
+            "token.iost/issue" => {
+                let (token, to, amount) = parse_content(func_name, content)?;
+                ReceiptEvent::Issue(TokenIssue { token, to, amount })
+            }
+            "token.iost/transferFreeze" => {
+                let (token, from, to, amount, unfreeze_time, memo): (
+                    String,
+                    String,
+                    String,
+                    String,
+                    serde_json::Value,
+                    String,
+                ) = parse_content(func_name, content)?;
+                ReceiptEvent::TransferFreeze(TokenTransferFreeze {
+                    token,
+                    from,
+                    to,
+                    amount,
+                    unfreeze_time: value_to_i64(func_name, &unfreeze_time)?,
+                    memo,
+                })
+            }
+            "ram.iost/buy" => {
+                let (payer, account, amount): (String, String, serde_json::Value) =
+                    parse_content(func_name, content)?;
+                ReceiptEvent::Ram(RamEvent::Buy {
+                    payer,
+                    account,
+                    amount: value_to_i64(func_name, &amount)?,
+                })
+            }
+            "ram.iost/sell" => {
+                let (account, receiver, amount): (String, String, serde_json::Value) =
+                    parse_content(func_name, content)?;
+                ReceiptEvent::Ram(RamEvent::Sell {
+                    account,
+                    receiver,
+                    amount: value_to_i64(func_name, &amount)?,
+                })
+            }
+            "ram.iost/lend" => {
+                let (from, to, amount): (String, String, serde_json::Value) =
+                    parse_content(func_name, content)?;
+                ReceiptEvent::Ram(RamEvent::Lend {
+                    from,
+                    to,
+                    amount: value_to_i64(func_name, &amount)?,
+                })
+            }
+            "vote_producer.iost/stat" => ReceiptEvent::VoteProducer(VoteProducerEvent::Stat(
+                parse_content(func_name, content)?,
+            )),
+            "vote_producer.iost/vote" => {
+                let (voter, producer, amount) = parse_content(func_name, content)?;
+                ReceiptEvent::VoteProducer(VoteProducerEvent::Vote {
+                    voter,
+                    producer,
+                    amount,
+                })
+            }
+            "vote_producer.iost/unvote" => {
+                let (voter, producer, amount) = parse_content(func_name, content)?;
+                ReceiptEvent::VoteProducer(VoteProducerEvent::Unvote {
+                    voter,
+                    producer,
+                    amount,
+                })
+            }
+            _ if func_name.starts_with("ram.iost/") => ReceiptEvent::Ram(RamEvent::Other {
+                func_name: self.func_name.clone(),
+                content: self.content.clone(),
+            }),
+            _ if func_name.starts_with("vote_producer.iost/") => {
+                ReceiptEvent::VoteProducer(VoteProducerEvent::Other {
+                    func_name: self.func_name.clone(),
+                    content: self.content.clone(),
+                })
+            }
+            _ => ReceiptEvent::Unknown {
+                func_name: self.func_name.clone(),
+                content: self.content.clone(),
+            },
+        };
+        Ok(event)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TxReceipt {
+    /// Status code 0 means the transaction was executed successfully
+    pub fn is_success(&self) -> bool {
+        self.status.code == 0
+    }
+
+    /// Decode every receipt emitted by this transaction, in order
+    pub fn events(&self) -> Result<Vec<ReceiptEvent>> {
+        self.receipts.iter().map(|r| r.decode()).collect()
+    }
+
+    /// All `token.iost/transfer` receipts of this transaction
+    pub fn transfers(&self) -> Result<Vec<TokenTransfer>> {
+        let mut transfers = Vec::new();
+        for event in self.events()? {
+            if let ReceiptEvent::Transfer(transfer) = event {
+                transfers.push(transfer);
+            }
+        }
+        Ok(transfers)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Block {
+    /// Decode the receipts of every transaction in the block, paired with the transaction hash.
+    /// Receipts of failed transactions are skipped since their effects are not applied.
+    pub fn receipt_events(&self) -> Result<Vec<(String, ReceiptEvent)>> {
+        let mut events = Vec::new();
+        for tx_receipt in self.receipts.iter().filter(|r| r.is_success()) {
+            for event in tx_receipt.events()? {
+                events.push((tx_receipt.tx_hash.clone(), event));
+            }
+        }
+        Ok(events)
+    }
+
+    /// All token transfers of successful transactions in the block, paired with the transaction hash
+    pub fn transfers(&self) -> Result<Vec<(String, TokenTransfer)>> {
+        Ok(self
+            .receipt_events()?
+            .into_iter()
+            .filter_map(|(hash, event)| match event {
+                ReceiptEvent::Transfer(transfer) => Some((hash, transfer)),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn receipt(func_name: &str, content: &str) -> Receipt {
+        Receipt {
+            func_name: func_name.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn should_decode_token_receipts() {
+        let transfer = receipt(
+            "token.iost/transfer",
+            r#"["iost","admin","lispczz3","100.00000000","deposit"]"#,
+        );
+        assert_eq!(
+            transfer.decode().unwrap(),
+            ReceiptEvent::Transfer(TokenTransfer {
+                token: "iost".to_string(),
+                from: "admin".to_string(),
+                to: "lispczz3".to_string(),
+                amount: "100.00000000".to_string(),
+                memo: "deposit".to_string(),
+            })
+        );
+
+        let issue = receipt("token.iost/issue", r#"["iost","admin","21000000000"]"#);
+        assert_eq!(
+            issue.decode().unwrap(),
+            ReceiptEvent::Issue(TokenIssue {
+                token: "iost".to_string(),
+                to: "admin".to_string(),
+                amount: "21000000000".to_string(),
+            })
+        );
+
+        let freeze = receipt(
+            "token.iost/transferFreeze",
+            r#"["iost","admin","lispczz3","1",1598918348274417000,""]"#,
+        );
+        match freeze.decode().unwrap() {
+            ReceiptEvent::TransferFreeze(f) => assert_eq!(f.unfreeze_time, 1598918348274417000),
+            e => panic!("unexpected event {:?}", e),
+        }
+    }
+
+    #[test]
+    fn should_decode_system_receipts() {
+        let buy = receipt("ram.iost/buy", r#"["admin","lispczz3",1024]"#);
+        assert_eq!(
+            buy.decode().unwrap(),
+            ReceiptEvent::Ram(RamEvent::Buy {
+                payer: "admin".to_string(),
+                account: "lispczz3".to_string(),
+                amount: 1024,
+            })
+        );
+
+        let stat = receipt(
+            "vote_producer.iost/stat",
+            r#"{"pendingList":["a","b"],"currentList":["c"]}"#,
+        );
+        match stat.decode().unwrap() {
+            ReceiptEvent::VoteProducer(VoteProducerEvent::Stat(ws)) => {
+                assert_eq!(ws.pending_list.len(), 2);
+                assert_eq!(ws.current_list.len(), 1);
+            }
+            e => panic!("unexpected event {:?}", e),
+        }
+
+        let other = receipt("ram.iost/issue", r#"["1"]"#);
+        assert!(matches!(
+            other.decode().unwrap(),
+            ReceiptEvent::Ram(RamEvent::Other { .. })
+        ));
+    }
+
+    #[test]
+    fn should_keep_unknown_and_reject_malformed_receipts() {
+        let unknown = receipt("Contract1234/hello", "world");
+        assert!(matches!(
+            unknown.decode().unwrap(),
+            ReceiptEvent::Unknown { .. }
+        ));
+
+        let malformed = receipt("token.iost/transfer", r#"["iost","admin"]"#);
+        assert!(malformed.decode().is_err());
+    }
+}
@@ -0,0 +1,331 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+pub use keys::public::PublicKey;
+
+use crate::{
+    parse_amount, validate_account_name, AmountLimit, ContractCall, IostAction, TxBuilder,
+    IOST_DECIMALS,
+};
+
+/// IOST pledged for the gas of a new account by default, and the least it can be
+pub const MIN_SIGN_UP_PLEDGE: &str = "10";
+
+/// Bytes of RAM bought for a new account by default
+pub const DEFAULT_SIGN_UP_RAM: i64 = 1024;
+
+/// An error which can be returned when creating an account.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SignUpError {
+    /// Less IOST than `MIN_SIGN_UP_PLEDGE` is pledged.
+    PledgeTooLow(String),
+    /// An amount of IOST has more than 8 decimals.
+    TooManyDecimals(String),
+    /// The RAM to buy is negative.
+    NegativeRam(i64),
+    /// RAM is bought without a budget of IOST to pay for it.
+    MissingRamBudget,
+}
+
+impl fmt::Display for SignUpError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::PledgeTooLow(pledge) => write!(
+                f,
+                "pledge {} is lower than {} IOST",
+                pledge, MIN_SIGN_UP_PLEDGE
+            ),
+            Self::TooManyDecimals(amount) => {
+                write!(f, "{} has more than {} decimals", amount, IOST_DECIMALS)
+            }
+            Self::NegativeRam(ram) => write!(f, "cannot buy {} bytes of RAM", ram),
+            Self::MissingRamBudget => write!(f, "no budget to buy RAM"),
+        }
+    }
+}
+
+impl From<SignUpError> for crate::error::Error {
+    fn from(e: SignUpError) -> crate::error::Error {
+        crate::Error::SignUpErr(e)
+    }
+}
+
+/// Digits of an amount of IOST scaled to 8 decimals
+fn iost_amount(amount: &str) -> crate::Result<u128> {
+    let (value, decimals) = parse_amount(amount)?;
+    if decimals > IOST_DECIMALS {
+        return Err(SignUpError::TooManyDecimals(amount.to_string()).into());
+    }
+    value
+        .checked_mul(10_u128.pow(IOST_DECIMALS - decimals))
+        .ok_or(crate::Error::FixedParseOverflow())
+}
+
+/// Creation of an account by a creator paying for its resources: `auth.iost` `signUp` with the
+/// owner and active keys, `gas.iost` `pledge` and `ram.iost` `buy` for the new account, and an
+/// optional transfer of IOST to it
+#[derive(Clone, Debug)]
+pub struct SignUp {
+    name: String,
+    owner: PublicKey,
+    active: PublicKey,
+    pledge: String,
+    ram: i64,
+    ram_budget: Option<String>,
+    balance: Option<String>,
+}
+
+impl SignUp {
+    pub fn new(name: &str, owner: &PublicKey, active: &PublicKey) -> crate::Result<Self> {
+        validate_account_name(name)?;
+        Ok(SignUp {
+            name: name.to_string(),
+            owner: owner.clone(),
+            active: active.clone(),
+            pledge: MIN_SIGN_UP_PLEDGE.to_string(),
+            ram: DEFAULT_SIGN_UP_RAM,
+            ram_budget: None,
+            balance: None,
+        })
+    }
+
+    /// IOST pledged for the gas of the account, at least `MIN_SIGN_UP_PLEDGE`
+    pub fn pledge<T: AsRef<str>>(mut self, pledge: T) -> Self {
+        self.pledge = pledge.as_ref().to_string();
+        self
+    }
+
+    /// Bytes of RAM bought for the account, none when 0
+    pub fn ram(mut self, ram: i64) -> Self {
+        self.ram = ram;
+        self
+    }
+
+    /// Most IOST spent buying the RAM, whose price the node sets. It goes into the amount limit
+    /// of the transaction
+    pub fn ram_budget<T: AsRef<str>>(mut self, budget: T) -> Self {
+        self.ram_budget = Some(budget.as_ref().to_string());
+        self
+    }
+
+    /// IOST transferred to the account once created
+    pub fn balance<T: AsRef<str>>(mut self, balance: T) -> Self {
+        self.balance = Some(balance.as_ref().to_string());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn validate(&self) -> crate::Result<()> {
+        if iost_amount(&self.pledge)? < iost_amount(MIN_SIGN_UP_PLEDGE)? {
+            return Err(SignUpError::PledgeTooLow(self.pledge.clone()).into());
+        }
+        if self.ram < 0 {
+            return Err(SignUpError::NegativeRam(self.ram).into());
+        }
+        if let Some(budget) = &self.ram_budget {
+            iost_amount(budget)?;
+        }
+        if let Some(balance) = &self.balance {
+            iost_amount(balance)?;
+        }
+        Ok(())
+    }
+
+    fn sign_up_action(&self) -> IostAction {
+        IostAction::new(
+            "auth.iost".to_string(),
+            "signUp".to_string(),
+            format!(
+                r#"["{}","{}","{}"]"#,
+                self.name,
+                self.owner.to_base58(),
+                self.active.to_base58()
+            ),
+        )
+    }
+
+    fn pledge_action(&self, creator: &str) -> IostAction {
+        IostAction::new(
+            "gas.iost".to_string(),
+            "pledge".to_string(),
+            format!(r#"["{}","{}","{}"]"#, creator, self.name, self.pledge),
+        )
+    }
+
+    fn buy_ram_action(&self, creator: &str) -> IostAction {
+        IostAction::new(
+            "ram.iost".to_string(),
+            "buy".to_string(),
+            format!(r#"["{}","{}",{}]"#, creator, self.name, self.ram),
+        )
+    }
+
+    /// Actions creating the account, paid by `creator`
+    pub fn actions(&self, creator: &str) -> crate::Result<Vec<IostAction>> {
+        validate_account_name(creator)?;
+        self.validate()?;
+        let mut actions = vec![self.sign_up_action(), self.pledge_action(creator)];
+        if self.ram > 0 {
+            actions.push(self.buy_ram_action(creator));
+        }
+        if let Some(balance) = &self.balance {
+            actions.push(IostAction::transfer(
+                creator,
+                self.name.as_str(),
+                balance.as_str(),
+                "",
+            )?);
+        }
+        Ok(actions)
+    }
+
+    /// Transaction creating the account, published by `creator`. The amount limit covers the
+    /// pledge, the transfer and the budget of the RAM, which has to be set to buy RAM
+    pub fn tx_builder(&self, chain_id: u32, creator: &str) -> crate::Result<TxBuilder> {
+        let actions = self.actions(creator)?;
+        let budget = match &self.ram_budget {
+            Some(budget) => budget.clone(),
+            None if self.ram > 0 => return Err(SignUpError::MissingRamBudget.into()),
+            None => "0".to_string(),
+        };
+        let mut builder = TxBuilder::new(chain_id).publisher(creator);
+        for action in actions {
+            // the builder does not see what buying RAM costs
+            builder = if action.contract == b"ram.iost" {
+                builder.call(ContractCall {
+                    action,
+                    amount_limit: vec![AmountLimit::new("iost".to_string(), budget.clone())],
+                })
+            } else {
+                builder.action(action)
+            };
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Error, MAINNET_CHAIN_ID};
+
+    const TIME: i64 = 1598918258274417000;
+
+    fn sign_up() -> SignUp {
+        let owner: PublicKey = "Gcv8c2tH8qZrUYnKdEEdTtASsxivic2834MQW6mgxqto"
+            .parse()
+            .unwrap();
+        let active: PublicKey = "iWgLQj3VTPN4dZnomuJMMCggv22LFw4nAkA6bmrVsmCo"
+            .parse()
+            .unwrap();
+        SignUp::new("lispczz5", &owner, &active).unwrap()
+    }
+
+    #[test]
+    fn sign_up_should_create_account_with_resources() {
+        let actions = sign_up()
+            .pledge("20")
+            .ram(2048)
+            .balance("1.5")
+            .actions("admin")
+            .unwrap();
+        let calls: Vec<(&[u8], &[u8], &[u8])> = actions
+            .iter()
+            .map(|a| {
+                (
+                    a.contract.as_slice(),
+                    a.action_name.as_slice(),
+                    a.data.as_slice(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            calls[..3],
+            [
+                (
+                    &b"auth.iost"[..],
+                    &b"signUp"[..],
+                    &br#"["lispczz5","Gcv8c2tH8qZrUYnKdEEdTtASsxivic2834MQW6mgxqto","iWgLQj3VTPN4dZnomuJMMCggv22LFw4nAkA6bmrVsmCo"]"#[..]
+                ),
+                (
+                    &b"gas.iost"[..],
+                    &b"pledge"[..],
+                    &br#"["admin","lispczz5","20"]"#[..]
+                ),
+                (
+                    &b"ram.iost"[..],
+                    &b"buy"[..],
+                    &br#"["admin","lispczz5",2048]"#[..]
+                ),
+            ]
+        );
+        assert_eq!(
+            actions[3],
+            IostAction::transfer("admin", "lispczz5", "1.5", "").unwrap()
+        );
+
+        let actions = sign_up().ram(0).actions("admin").unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[1].data, br#"["admin","lispczz5","10"]"#.to_vec());
+    }
+
+    #[test]
+    fn sign_up_tx_should_limit_amount() {
+        let tx = sign_up()
+            .ram_budget("2.5")
+            .balance("1")
+            .tx_builder(MAINNET_CHAIN_ID, "admin")
+            .unwrap()
+            .time(TIME)
+            .build()
+            .unwrap();
+        assert_eq!(tx.publisher, "admin");
+        assert_eq!(tx.actions.len(), 4);
+        assert_eq!(tx.actions[2].action_name, b"buy".to_vec());
+        assert_eq!(tx.amount_limit.len(), 1);
+        assert_eq!(tx.amount_limit[0].token, "iost");
+        assert_eq!(tx.amount_limit[0].value, "13.5");
+
+        let tx = sign_up()
+            .ram(0)
+            .tx_builder(MAINNET_CHAIN_ID, "admin")
+            .unwrap()
+            .time(TIME)
+            .build()
+            .unwrap();
+        assert_eq!(tx.amount_limit[0].value, "10");
+    }
+
+    #[test]
+    fn sign_up_should_be_validated() {
+        let owner: PublicKey = "Gcv8c2tH8qZrUYnKdEEdTtASsxivic2834MQW6mgxqto"
+            .parse()
+            .unwrap();
+        assert!(SignUp::new("Lispczz5", &owner, &owner).is_err());
+        assert!(sign_up().actions("x").is_err());
+        assert!(matches!(
+            sign_up().pledge("9.99999999").actions("admin"),
+            Err(Error::SignUpErr(SignUpError::PledgeTooLow(_)))
+        ));
+        assert!(matches!(
+            sign_up().balance("0.000000001").actions("admin"),
+            Err(Error::SignUpErr(SignUpError::TooManyDecimals(_)))
+        ));
+        assert!(matches!(
+            sign_up().ram(-1).actions("admin"),
+            Err(Error::SignUpErr(SignUpError::NegativeRam(-1)))
+        ));
+        assert!(matches!(
+            sign_up().tx_builder(MAINNET_CHAIN_ID, "admin"),
+            Err(Error::SignUpErr(SignUpError::MissingRamBudget))
+        ));
+        assert!(sign_up().pledge("ten").actions("admin").is_err());
+    }
+}
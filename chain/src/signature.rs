@@ -10,6 +10,40 @@ use lite_json::JsonValue;
 #[cfg(feature = "std")]
 use serde::Serialize as SerSerialize;
 
+/// Id of the secp256k1 algorithm in the node encoding
+pub const SECP256K1_ID: u8 = 1;
+/// Id of the ed25519 algorithm in the node encoding
+pub const ED25519_ID: u8 = 2;
+
+/// Name of the signature algorithm with the given id
+pub fn algorithm_name(id: u8) -> Option<&'static str> {
+    match id {
+        SECP256K1_ID => Some(algorithm::SECP256K1),
+        ED25519_ID => Some(algorithm::ED25519),
+        _ => None,
+    }
+}
+
+/// Id of the signature algorithm with the given name
+pub fn algorithm_id(name: &str) -> Option<u8> {
+    if name.eq_ignore_ascii_case(algorithm::SECP256K1) {
+        Some(SECP256K1_ID)
+    } else if name.eq_ignore_ascii_case(algorithm::ED25519) {
+        Some(ED25519_ID)
+    } else {
+        None
+    }
+}
+
+/// Fields of a signature in the order the node encodes them
+#[derive(NumberBytes, Write)]
+#[iost_root_path = "crate"]
+struct SignatureBytes {
+    algorithm: u8,
+    signature: Vec<u8>,
+    public_key: Vec<u8>,
+}
+
 #[derive(Clone, Default, Debug, NumberBytes, Write, Read)]
 #[cfg_attr(feature = "std", derive(SerSerialize))]
 #[iost_root_path = "crate"]
@@ -25,7 +59,10 @@ pub struct Signature {
 impl Signature {
     pub fn sign(message: &[u8], sign_algorithm: &str, sec_key: &[u8]) -> crate::Result<Signature> {
         let algorithm = algorithm::new(sign_algorithm);
-        let pub_key = algorithm.get_pub_key(sec_key).unwrap();
+        // checks the secret key, which the signing expects valid
+        let pub_key = algorithm
+            .get_pub_key(sec_key)
+            .map_err(|_| Error::InvalidSecretKey())?;
         let result = algorithm.sign(message, sec_key);
         Ok(Signature {
             algorithm: sign_algorithm.to_string(),
@@ -34,11 +71,36 @@ impl Signature {
         })
     }
 
-    pub fn verify(&self, message: &[u8]) -> bool {
-        let algorithm = algorithm::new(self.algorithm.as_str());
-        let pub_key = base64::decode(self.public_key.as_str()).unwrap();
-        let sig = base64::decode(self.signature.as_str()).unwrap();
-        algorithm.verify(message, pub_key.as_slice(), sig.as_slice())
+    /// Check the signature of `message`, failing as well when the algorithm is unknown or the
+    /// signature or the public key is malformed
+    pub fn verify(&self, message: &[u8]) -> crate::Result<()> {
+        let name = algorithm_id(&self.algorithm)
+            .and_then(algorithm_name)
+            .ok_or(Error::InvalidSignature())?;
+        let pub_key =
+            base64::decode(self.public_key.as_str()).map_err(|_| Error::InvalidSignature())?;
+        let sig = base64::decode(self.signature.as_str()).map_err(|_| Error::InvalidSignature())?;
+        if algorithm::new(name).verify(message, pub_key.as_slice(), sig.as_slice()) {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignature())
+        }
+    }
+
+    /// Bytes of the signature as the node encodes them: algorithm id, raw signature and raw public key
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        let signature = SignatureBytes {
+            algorithm: algorithm_id(&self.algorithm).ok_or(Error::InvalidSignature())?,
+            signature: base64::decode(self.signature.as_str())
+                .map_err(|_| Error::InvalidSignature())?,
+            public_key: base64::decode(self.public_key.as_str())
+                .map_err(|_| Error::InvalidSignature())?,
+        };
+        let mut data = vec![0u8; signature.num_bytes()];
+        signature
+            .write(&mut data, &mut 0)
+            .map_err(Error::BytesWriteError)?;
+        Ok(data)
     }
 
     pub fn no_std_serialize(&self) -> JsonValue {
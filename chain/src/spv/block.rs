@@ -6,8 +6,8 @@ use base64;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
-use keys::algorithm;
-
+#[cfg(feature = "std")]
+use crate::spv::merkle_root;
 use crate::spv::{Head, Sign, Tx};
 use crate::Error::IOSTBlockVerifyError;
 use crate::Result;
@@ -39,17 +39,17 @@ pub struct Block {
 }
 
 impl Block {
+    /// Hash of the block, which is the hash of its head
+    pub fn hash(&self) -> Vec<u8> {
+        self.head.hash()
+    }
+
     #[cfg(feature = "std")]
     pub(crate) fn verify_self(&self) -> Result<()> {
-        let ed25519 = algorithm::new(algorithm::ED25519);
-        let sign = base64::decode(self.sign.sig.as_str()).unwrap();
-        let pub_key = bs58::decode(self.head.witness.as_str()).into_vec().unwrap();
-        let hash = self.head.hash();
-
-        if !ed25519.verify(hash.as_slice(), pub_key.as_slice(), sign.as_slice()) {
+        if let Err(e) = self.head.verify_sign(&self.sign) {
             return Err(IOSTBlockVerifyError(format!(
-                "The signature of block {} is wrong",
-                self.sign.sig
+                "The signature of block {} is wrong: {:?}",
+                self.sign.sig, e
             )));
         }
 
@@ -63,3 +63,258 @@ impl Block {
         Ok(())
     }
 }
+
+#[cfg(feature = "std")]
+fn decode_hashes(hashes: &[String]) -> Result<Vec<Vec<u8>>> {
+    hashes
+        .iter()
+        .map(|hash| {
+            base64::decode(hash.as_str())
+                .map_err(|_| IOSTBlockVerifyError(format!("invalid hash {}", hash)))
+        })
+        .collect()
+}
+
+#[cfg(feature = "std")]
+impl Block {
+    /// Hashes of the transactions of the block: `tx_hashes`, or the hashes referenced by the
+    /// receipts when the node sent none
+    pub fn tx_hashes(&self) -> Result<Vec<Vec<u8>>> {
+        if self.tx_hashes.is_empty() {
+            self.receipts.iter().map(|r| r.tx_hash_bytes()).collect()
+        } else {
+            decode_hashes(&self.tx_hashes)
+        }
+    }
+
+    /// Hashes of the receipts of the block: `receipt_hashes`, or the hashes computed from
+    /// the receipts when the node sent none
+    pub fn receipt_hashes(&self) -> Result<Vec<Vec<u8>>> {
+        if self.receipt_hashes.is_empty() {
+            self.compute_receipt_hashes()
+        } else {
+            decode_hashes(&self.receipt_hashes)
+        }
+    }
+
+    pub fn compute_receipt_hashes(&self) -> Result<Vec<Vec<u8>>> {
+        self.receipts.iter().map(|r| r.hash()).collect()
+    }
+
+    pub fn compute_tx_hashes(&self) -> Result<Vec<Vec<u8>>> {
+        self.txs.iter().map(|tx| tx.hash()).collect()
+    }
+
+    /// Check the signatures of every transaction of the block
+    pub fn verify_txs(&self) -> Result<()> {
+        for tx in self.txs.iter() {
+            tx.to_tx()?.verify()?;
+        }
+        Ok(())
+    }
+
+    /// Check the tx and receipt hashes against the merkle hashes of the head,
+    /// and against the txs and receipts when the block carries them
+    pub fn verify_hashes(&self) -> Result<()> {
+        let number = self.head.number;
+        let tx_hashes = self.tx_hashes()?;
+        let receipt_hashes = self.receipt_hashes()?;
+        if tx_hashes.len() != receipt_hashes.len() {
+            return Err(IOSTBlockVerifyError(format!(
+                "Tx hash len {} unmatch receipt hash len {} at block {}",
+                tx_hashes.len(),
+                receipt_hashes.len(),
+                number
+            )));
+        }
+        if merkle_root(&tx_hashes) != self.head.tx_merkle_hash {
+            return Err(IOSTBlockVerifyError(format!(
+                "Tx merkle hash unmatch at block {}",
+                number
+            )));
+        }
+        if merkle_root(&receipt_hashes) != self.head.tx_receipt_merkle_hash {
+            return Err(IOSTBlockVerifyError(format!(
+                "Receipt merkle hash unmatch at block {}",
+                number
+            )));
+        }
+        if !self.txs.is_empty() && self.compute_tx_hashes()? != tx_hashes {
+            return Err(IOSTBlockVerifyError(format!(
+                "Tx hashes unmatch txs at block {}",
+                number
+            )));
+        }
+        if self.receipts.is_empty() {
+            return Ok(());
+        }
+        if self.receipts.len() != tx_hashes.len() {
+            return Err(IOSTBlockVerifyError(format!(
+                "Receipt len {} unmatch tx hash len {} at block {}",
+                self.receipts.len(),
+                tx_hashes.len(),
+                number
+            )));
+        }
+        for (i, receipt) in self.receipts.iter().enumerate() {
+            if receipt.tx_hash_bytes()? != tx_hashes[i] {
+                return Err(IOSTBlockVerifyError(format!(
+                    "Receipt {} refers to tx {} instead of {} at block {}",
+                    i,
+                    receipt.tx_hash,
+                    base64::encode(&tx_hashes[i]),
+                    number
+                )));
+            }
+            if receipt.hash()? != receipt_hashes[i] {
+                return Err(IOSTBlockVerifyError(format!(
+                    "Receipt hash {} unmatch at block {}",
+                    base64::encode(&receipt_hashes[i]),
+                    number
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the producer signature and the consistency of the whole block
+    pub fn verify(&self) -> Result<()> {
+        self.verify_self()?;
+        self.verify_hashes()?;
+        self.verify_txs()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spv::TxReceiptStatus;
+    use crate::Receipt;
+
+    fn receipt(tx_hash: &[u8], amount: &str) -> TxReceipt {
+        TxReceipt {
+            tx_hash: base64::encode(tx_hash),
+            gas_usage: "3026000".to_string(),
+            ram_usage: vec![("admin".to_string(), "-12".to_string())]
+                .into_iter()
+                .collect(),
+            status: TxReceiptStatus {
+                code: 0,
+                message: "".to_string(),
+            },
+            returns: vec!["[]".to_string()],
+            receipts: vec![Receipt {
+                func_name: "token.iost/transfer".to_string(),
+                content: format!(r#"["iost","admin","lispczz3","{}",""]"#, amount),
+            }],
+        }
+    }
+
+    fn block(receipts: Vec<TxReceipt>) -> Block {
+        let tx_hashes: Vec<Vec<u8>> = receipts
+            .iter()
+            .map(|r| r.tx_hash_bytes().unwrap())
+            .collect();
+        let receipt_hashes: Vec<Vec<u8>> = receipts.iter().map(|r| r.hash().unwrap()).collect();
+        Block {
+            head: Head {
+                version: 1,
+                parent_hash: vec![],
+                tx_merkle_hash: merkle_root(&tx_hashes),
+                tx_receipt_merkle_hash: merkle_root(&receipt_hashes),
+                info: vec![],
+                number: 1200,
+                witness: "".to_string(),
+                time: 1598918258274417000,
+            },
+            sign: Sign {
+                algorithm: 2,
+                sig: "".to_string(),
+                pub_key: "".to_string(),
+            },
+            receipts,
+            txs: vec![],
+            tx_hashes: tx_hashes.iter().map(base64::encode).collect(),
+            receipt_hashes: receipt_hashes.iter().map(base64::encode).collect(),
+            block_type: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn should_block_hashes_be_verified() {
+        let b = block(vec![receipt(&[1; 32], "10"), receipt(&[2; 32], "20")]);
+        assert!(b.verify_hashes().is_ok());
+        assert_eq!(b.hash(), b.head.hash());
+
+        // hashes recomputed from the receipts when the node sends none
+        let mut only_receipts = block(vec![receipt(&[1; 32], "10")]);
+        only_receipts.tx_hashes.clear();
+        only_receipts.receipt_hashes.clear();
+        assert!(only_receipts.verify_hashes().is_ok());
+
+        let empty = block(vec![]);
+        assert!(empty.verify_hashes().is_ok());
+    }
+
+    #[test]
+    fn should_tampered_block_be_rejected() {
+        let mut b = block(vec![receipt(&[1; 32], "10"), receipt(&[2; 32], "20")]);
+        b.receipts[1] = receipt(&[2; 32], "2000");
+        assert!(b.verify_hashes().is_err());
+
+        let mut b = block(vec![receipt(&[1; 32], "10"), receipt(&[2; 32], "20")]);
+        b.tx_hashes.swap(0, 1);
+        assert!(b.verify_hashes().is_err());
+
+        let mut b = block(vec![receipt(&[1; 32], "10")]);
+        b.head.tx_merkle_hash = vec![0; 32];
+        assert!(b.verify_hashes().is_err());
+    }
+
+    /// Hash of the signed `sendTx` example of the IOST API documentation, pinned by the tests of
+    /// `crate::Tx`
+    const DOCUMENTED_TX_HASH: &str = "CihRgNwEPjWnaXgogcFhK31r7EkWZyU9H6ZxzyWF37yK";
+
+    /// The documented tx as blocks carry it
+    fn documented_tx() -> Tx {
+        serde_json::from_value(serde_json::json!({
+            "time": "1544709662543340000",
+            "expiration": "1544709692318715000",
+            "gasRatio": "100",
+            "gasLimit": "50000000",
+            "delay": "0",
+            "chain_id": 1024,
+            "actions": [{
+                "contract": "token.iost",
+                "action_name": "transfer",
+                "data": "[\"iost\", \"testaccount\", \"anothertest\", \"100\", \"this is an example transfer\"]",
+            }],
+            "amountLimit": [{ "token": "*", "value": "unlimited" }],
+            "publisher": "testaccount",
+            "publishSigns": [{
+                "algorithm": 2,
+                "sig": "/K1HM0OEbfJ4+D3BmalpLmb03WS7BeCz4nVHBNbDrx3/A31aN2RJNxyEKhv+VSoWctfevDNRnL1kadRVxSt8CA==",
+                "pub_key": "lDS+SdM+aiVHbDyXapvrsgyKxFg9mJuHWPZb/INBRWY=",
+            }],
+            "signers": [],
+            "signs": [],
+            "referredTx": "",
+            "reserved": null,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn block_of_documented_tx_should_be_verified() {
+        let tx_hash = bs58::decode(DOCUMENTED_TX_HASH).into_vec().unwrap();
+        let mut b = block(vec![receipt(&tx_hash, "100")]);
+        b.txs = vec![documented_tx()];
+        assert_eq!(b.compute_tx_hashes().unwrap(), vec![tx_hash.clone()]);
+        assert!(b.verify_hashes().is_ok());
+        assert!(b.verify_txs().is_ok());
+
+        b.txs[0].publisher = "anothertest".to_string();
+        assert!(b.verify_hashes().is_err());
+        assert!(b.verify_txs().is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use codec::{Decode, Encode};
+use lite_json::{parse_json, JsonValue};
+
+#[cfg(feature = "std")]
+use crate::spv::Block;
+use crate::spv::{check_confirmations, MerkleProof, Verify};
+use crate::verify::BlockHead;
+use crate::Error::{IOSTDepositProofError, JsonParserError};
+use crate::{Result, TokenTransfer, TxReceiptData};
+
+/// Receipt of a token transfer
+pub const TRANSFER_RECEIPT: &str = "token.iost/transfer";
+
+/// Proof that a transaction of a block transferred a token to an account: the signed head of the
+/// block and the heads confirming it, the merkle paths of the tx hash and of its receipt to the
+/// head, and the index of the transfer among the receipts of the transaction
+#[derive(Clone, Debug, Default, PartialEq, Encode, Decode)]
+pub struct DepositProof {
+    pub block: BlockHead,
+    pub witness_blocks: Vec<BlockHead>,
+    pub tx_proof: MerkleProof,
+    pub receipt: TxReceiptData,
+    pub receipt_proof: MerkleProof,
+    pub transfer_index: u32,
+}
+
+/// A transfer proven by a `DepositProof`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Deposit {
+    pub block_number: i64,
+    pub tx_hash: Vec<u8>,
+    pub transfer: TokenTransfer,
+}
+
+impl DepositProof {
+    /// Proof of the transfer to `to` made by the transaction `tx_hash` of `block`, confirmed by
+    /// the blocks `witness_blocks`
+    #[cfg(feature = "std")]
+    pub fn new(
+        block: &Block,
+        witness_blocks: &[Block],
+        tx_hash: &[u8],
+        to: &str,
+    ) -> Result<DepositProof> {
+        let number = block.head.number;
+        let tx_hashes = block.tx_hashes()?;
+        let index = tx_hashes
+            .iter()
+            .position(|hash| hash.as_slice() == tx_hash)
+            .ok_or_else(|| {
+                IOSTDepositProofError(format!(
+                    "tx {} not found at block {}",
+                    base64::encode(tx_hash),
+                    number
+                ))
+            })?;
+        let receipt = block.receipts.get(index).ok_or_else(|| {
+            IOSTDepositProofError(format!("receipt {} not found at block {}", index, number))
+        })?;
+        let transfer_index = receipt
+            .receipts
+            .iter()
+            .position(|r| {
+                r.func_name == TRANSFER_RECEIPT
+                    && parse_transfer(&r.content).map_or(false, |t| t.to == to)
+            })
+            .ok_or_else(|| {
+                IOSTDepositProofError(format!(
+                    "no transfer to {} in tx {}",
+                    to,
+                    base64::encode(tx_hash)
+                ))
+            })?;
+        let proof_error =
+            || IOSTDepositProofError(format!("hashes unmatch txs at block {}", number));
+        Ok(DepositProof {
+            block: BlockHead::from_head(&block.head, &block.sign),
+            witness_blocks: witness_blocks
+                .iter()
+                .map(|b| BlockHead::from_head(&b.head, &b.sign))
+                .collect(),
+            tx_proof: MerkleProof::new(&tx_hashes, index).ok_or_else(proof_error)?,
+            receipt: receipt.to_data()?,
+            receipt_proof: MerkleProof::new(&block.receipt_hashes()?, index)
+                .ok_or_else(proof_error)?,
+            transfer_index: transfer_index as u32,
+        })
+    }
+}
+
+/// Content of a `token.iost/transfer` receipt, `[token, from, to, amount, memo]`
+pub fn parse_transfer(content: &str) -> Result<TokenTransfer> {
+    let args = match parse_json(content).map_err(|_| JsonParserError())? {
+        JsonValue::Array(args) => args
+            .into_iter()
+            .map(|arg| match arg {
+                JsonValue::String(s) => Ok(s.into_iter().collect::<String>()),
+                _ => Err(JsonParserError()),
+            })
+            .collect::<Result<Vec<_>>>()?,
+        _ => return Err(JsonParserError()),
+    };
+    match args.as_slice() {
+        [token, from, to, amount, memo] => Ok(TokenTransfer {
+            token: token.clone(),
+            from: from.clone(),
+            to: to.clone(),
+            amount: amount.clone(),
+            memo: memo.clone(),
+        }),
+        _ => Err(JsonParserError()),
+    }
+}
+
+impl Verify {
+    /// Check `proof` and return the transfer it proves: the block is signed by a producer of its
+    /// epoch and confirmed by the witness blocks, the tx hash and the successful receipt are in
+    /// the block at the same position, and the receipt contains the transfer
+    pub fn verify_deposit(&self, proof: &DepositProof) -> Result<Deposit> {
+        let head = proof.block.verified_head()?;
+        let producers = self.producers_of(head.number)?;
+        if !producers.contains(&head.witness) {
+            return Err(IOSTDepositProofError(format!(
+                "block {} is produced by {} which is not a producer of its epoch",
+                head.number, head.witness
+            )));
+        }
+        let witness_heads = proof
+            .witness_blocks
+            .iter()
+            .map(|b| b.verified_head())
+            .collect::<Result<Vec<_>>>()?;
+        check_confirmations(&head, &witness_heads, producers)?;
+
+        let receipt = &proof.receipt;
+        if proof.tx_proof.index != proof.receipt_proof.index
+            || proof.tx_proof.leaf_count != proof.receipt_proof.leaf_count
+        {
+            return Err(IOSTDepositProofError(format!(
+                "tx and receipt proofs are not at the same position at block {}",
+                head.number
+            )));
+        }
+        if !proof
+            .tx_proof
+            .verify(&receipt.tx_hash, &head.tx_merkle_hash)
+        {
+            return Err(IOSTDepositProofError(format!(
+                "tx {} is not in block {}",
+                base64::encode(&receipt.tx_hash),
+                head.number
+            )));
+        }
+        if !proof
+            .receipt_proof
+            .verify(&receipt.hash()?, &head.tx_receipt_merkle_hash)
+        {
+            return Err(IOSTDepositProofError(format!(
+                "receipt of tx {} is not in block {}",
+                base64::encode(&receipt.tx_hash),
+                head.number
+            )));
+        }
+        if receipt.status_code != 0 {
+            return Err(IOSTDepositProofError(format!(
+                "tx {} failed with status {}: {}",
+                base64::encode(&receipt.tx_hash),
+                receipt.status_code,
+                receipt.message
+            )));
+        }
+        let transfer = match receipt.receipts.get(proof.transfer_index as usize) {
+            Some((func_name, content)) if func_name == TRANSFER_RECEIPT => parse_transfer(content)?,
+            _ => {
+                return Err(IOSTDepositProofError(format!(
+                    "receipt {} of tx {} is not a transfer",
+                    proof.transfer_index,
+                    base64::encode(&receipt.tx_hash)
+                )))
+            }
+        };
+        Ok(Deposit {
+            block_number: head.number,
+            tx_hash: receipt.tx_hash.clone(),
+            transfer,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spv::epoch_proof::test::{following_blocks, producers, receipt, signed_block};
+    use crate::spv::{CONFIRM_WITNESS_NUM, VERIFIER_NUM, VOTE_INTERVAL};
+
+    fn block(producer: usize) -> Block {
+        signed_block(
+            vec![1; 32],
+            VOTE_INTERVAL + 1,
+            producer,
+            vec![
+                receipt(1, "ram.iost/buy", r#"["admin","lispczz3",1024]"#),
+                receipt(
+                    2,
+                    TRANSFER_RECEIPT,
+                    r#"["iost","admin","lispczz3","10.5","deposit"]"#,
+                ),
+                receipt(3, TRANSFER_RECEIPT, r#"["iost","admin","bridge","1",""]"#),
+            ],
+        )
+    }
+
+    fn proof(producer: usize, witnesses: &[usize]) -> DepositProof {
+        let block = block(producer);
+        let witness_blocks = following_blocks(&block.head, witnesses);
+        DepositProof::new(&block, &witness_blocks, &[2; 32], "lispczz3").unwrap()
+    }
+
+    #[test]
+    fn should_deposit_be_verified() {
+        let v = Verify::with_epoch(VOTE_INTERVAL, producers(0..VERIFIER_NUM));
+        let witnesses: Vec<usize> = (1..=CONFIRM_WITNESS_NUM).collect();
+        let proof = proof(0, &witnesses);
+
+        let encoded = proof.encode();
+        let decoded = DepositProof::decode(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, proof);
+
+        assert_eq!(
+            v.verify_deposit(&decoded).unwrap(),
+            Deposit {
+                block_number: VOTE_INTERVAL + 1,
+                tx_hash: vec![2; 32],
+                transfer: TokenTransfer {
+                    token: "iost".to_string(),
+                    from: "admin".to_string(),
+                    to: "lispczz3".to_string(),
+                    amount: "10.5".to_string(),
+                    memo: "deposit".to_string(),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn should_forged_deposit_be_rejected() {
+        let v = Verify::with_epoch(VOTE_INTERVAL, producers(0..VERIFIER_NUM));
+        let witnesses: Vec<usize> = (1..=CONFIRM_WITNESS_NUM).collect();
+
+        let mut amount = proof(0, &witnesses);
+        amount.receipt.receipts[0].1 = r#"["iost","admin","lispczz3","1000",""]"#.to_string();
+        assert!(v.verify_deposit(&amount).is_err());
+
+        let mut other_tx = proof(0, &witnesses);
+        other_tx.tx_proof.index = 2;
+        assert!(v.verify_deposit(&other_tx).is_err());
+
+        let mut failed = proof(0, &witnesses);
+        failed.receipt.status_code = 1;
+        assert!(v.verify_deposit(&failed).is_err());
+
+        let mut not_transfer = proof(0, &witnesses);
+        not_transfer.transfer_index = 1;
+        assert!(v.verify_deposit(&not_transfer).is_err());
+
+        // not enough confirmations, or a block not produced by a producer of the epoch
+        assert!(v.verify_deposit(&proof(0, &witnesses[1..])).is_err());
+        assert!(v.verify_deposit(&proof(VERIFIER_NUM, &witnesses)).is_err());
+
+        // the transaction does not transfer to the account
+        let block = block(0);
+        assert!(DepositProof::new(&block, &[], &[1; 32], "lispczz3").is_err());
+        assert!(DepositProof::new(&block, &[], &[4; 32], "lispczz3").is_err());
+    }
+
+    #[test]
+    fn should_transfer_be_parsed() {
+        let transfer = parse_transfer(r#"["iost","admin","lispczz3","10",""]"#).unwrap();
+        assert_eq!(transfer.amount, "10");
+        assert!(parse_transfer(r#"["iost","admin","lispczz3",10,""]"#).is_err());
+        assert!(parse_transfer(r#"["iost","admin"]"#).is_err());
+    }
+}
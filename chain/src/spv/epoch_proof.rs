@@ -0,0 +1,349 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use codec::{Decode, Encode};
+use lite_json::{parse_json, JsonValue};
+
+#[cfg(feature = "std")]
+use crate::spv::Block;
+use crate::spv::{check_confirmations, MerkleProof, Verify, VERIFIER_NUM, VOTE_INTERVAL};
+use crate::verify::BlockHead;
+use crate::Error::{IOSTUpdateEpochError, JsonParserError};
+use crate::{Result, TxReceiptData};
+
+/// Receipt of the vote block listing the producers of the next epoch
+pub const STAT_RECEIPT: &str = "vote_producer.iost/stat";
+
+/// What a light client needs to move to the next epoch without the full blocks: the signed head
+/// of the vote block, the receipt electing the producers with its merkle path to the head, and
+/// the heads of the following blocks confirming the vote block
+#[derive(Clone, Debug, Default, PartialEq, Encode, Decode)]
+pub struct EpochChangeProof {
+    pub vote_block: BlockHead,
+    pub stat_receipt: TxReceiptData,
+    pub stat_receipt_proof: MerkleProof,
+    pub witness_blocks: Vec<BlockHead>,
+}
+
+impl EpochChangeProof {
+    /// Proof of the epoch elected at `vote_block`, confirmed by the blocks `witness_blocks`
+    #[cfg(feature = "std")]
+    pub fn new(vote_block: &Block, witness_blocks: &[Block]) -> Result<EpochChangeProof> {
+        let number = vote_block.head.number;
+        let index = vote_block
+            .receipts
+            .iter()
+            .position(|r| r.receipts.iter().any(|e| e.func_name == STAT_RECEIPT))
+            .ok_or_else(|| {
+                IOSTUpdateEpochError(format!(
+                    "{} receipt not found at block {}",
+                    STAT_RECEIPT, number
+                ))
+            })?;
+        let stat_receipt_proof = MerkleProof::new(&vote_block.receipt_hashes()?, index)
+            .ok_or_else(|| {
+                IOSTUpdateEpochError(format!(
+                    "receipt hashes unmatch receipts at block {}",
+                    number
+                ))
+            })?;
+        Ok(EpochChangeProof {
+            vote_block: BlockHead::from_head(&vote_block.head, &vote_block.sign),
+            stat_receipt: vote_block.receipts[index].to_data()?,
+            stat_receipt_proof,
+            witness_blocks: witness_blocks
+                .iter()
+                .map(|b| BlockHead::from_head(&b.head, &b.sign))
+                .collect(),
+        })
+    }
+
+    /// Producers of the next epoch listed by the stat receipt, not verified
+    pub fn pending_list(&self) -> Result<Vec<String>> {
+        parse_pending_list(stat_content(&self.stat_receipt, self.vote_block.number)?)
+    }
+}
+
+/// Content of the `vote_producer.iost/stat` receipt `stat_receipt` of the vote block `number`
+pub(crate) fn stat_content(stat_receipt: &TxReceiptData, number: i64) -> Result<&str> {
+    stat_receipt.find_receipt(STAT_RECEIPT).ok_or_else(|| {
+        IOSTUpdateEpochError(format!(
+            "{} receipt not found at block {}",
+            STAT_RECEIPT, number
+        ))
+    })
+}
+
+/// `pendingList` of the content of a `vote_producer.iost/stat` receipt, the producers elected
+pub fn parse_pending_list(content: &str) -> Result<Vec<String>> {
+    parse_producer_list(content, "pendingList")
+}
+
+/// `currentList` of the content of a `vote_producer.iost/stat` receipt, the producers of the
+/// epoch ending at the vote block
+pub fn parse_current_list(content: &str) -> Result<Vec<String>> {
+    parse_producer_list(content, "currentList")
+}
+
+fn parse_producer_list(content: &str, field: &str) -> Result<Vec<String>> {
+    let fields = match parse_json(content).map_err(|_| JsonParserError())? {
+        JsonValue::Object(fields) => fields,
+        _ => return Err(JsonParserError()),
+    };
+    let list = fields
+        .into_iter()
+        .find(|(name, _)| name.iter().copied().eq(field.chars()))
+        .map(|(_, value)| value);
+    match list {
+        Some(JsonValue::Array(items)) => items
+            .into_iter()
+            .map(|item| match item {
+                JsonValue::String(producer) => Ok(producer.into_iter().collect()),
+                _ => Err(JsonParserError()),
+            })
+            .collect(),
+        _ => Err(JsonParserError()),
+    }
+}
+
+impl Verify {
+    /// Check `proof` against the producers of the current epoch and return the producers it elects
+    pub fn verify_epoch_change(&self, proof: &EpochChangeProof) -> Result<Vec<String>> {
+        let vote = proof.vote_block.verified_head()?;
+        if vote.number % VOTE_INTERVAL != 0 {
+            return Err(IOSTUpdateEpochError(format!(
+                "invalid spv start block {}",
+                vote.number
+            )));
+        }
+        let leaf = proof.stat_receipt.hash()?;
+        if !proof
+            .stat_receipt_proof
+            .verify(&leaf, &vote.tx_receipt_merkle_hash)
+        {
+            return Err(IOSTUpdateEpochError(format!(
+                "{} receipt is not in block {}",
+                STAT_RECEIPT, vote.number
+            )));
+        }
+        let pending_list = proof.pending_list()?;
+        if pending_list.len() != VERIFIER_NUM {
+            return Err(IOSTUpdateEpochError(format!(
+                "invalid pending list length {} at block {}",
+                pending_list.len(),
+                vote.number
+            )));
+        }
+        let witness_heads = proof
+            .witness_blocks
+            .iter()
+            .map(|b| b.verified_head())
+            .collect::<Result<Vec<_>>>()?;
+        check_confirmations(&vote, &witness_heads, self.producers_of(vote.number)?)?;
+        Ok(pending_list)
+    }
+
+    /// Verify `proof` and trust the producers it elects for the next epoch
+    pub fn apply_epoch_change(&mut self, proof: &EpochChangeProof) -> Result<()> {
+        let pending_list = self.verify_epoch_change(proof)?;
+        self.insert_epoch(proof.vote_block.number, pending_list);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::spv::{merkle_root, Head, Sign, TxReceiptStatus, CONFIRM_WITNESS_NUM};
+    use crate::{Receipt, TxReceipt, SECP256K1_ID};
+    use keys::algorithm;
+
+    fn producer_key(i: usize) -> Vec<u8> {
+        vec![i as u8 + 1; 32]
+    }
+
+    /// Witness of the producer `i` of the test chains
+    pub(crate) fn producer(i: usize) -> String {
+        let pub_key = algorithm::new(algorithm::SECP256K1)
+            .get_pub_key(&producer_key(i))
+            .unwrap();
+        bs58::encode(pub_key).into_string()
+    }
+
+    pub(crate) fn producers(range: core::ops::Range<usize>) -> Vec<String> {
+        range.map(producer).collect()
+    }
+
+    pub(crate) fn receipt(tx_hash: u8, func_name: &str, content: &str) -> TxReceipt {
+        TxReceipt {
+            tx_hash: base64::encode(&[tx_hash; 32]),
+            gas_usage: "3026000".to_string(),
+            ram_usage: Default::default(),
+            status: TxReceiptStatus {
+                code: 0,
+                message: "".to_string(),
+            },
+            returns: vec!["[]".to_string()],
+            receipts: vec![Receipt {
+                func_name: func_name.to_string(),
+                content: content.to_string(),
+            }],
+        }
+    }
+
+    pub(crate) fn stat_receipt(pending_list: &[String]) -> TxReceipt {
+        let content = serde_json::json!({
+            "pendingList": pending_list,
+            "currentList": pending_list,
+        });
+        receipt(0, STAT_RECEIPT, &content.to_string())
+    }
+
+    /// Block `number` on top of the block `parent_hash`, signed by the producer `i`
+    pub(crate) fn signed_block(
+        parent_hash: Vec<u8>,
+        number: i64,
+        i: usize,
+        receipts: Vec<TxReceipt>,
+    ) -> Block {
+        let tx_hashes: Vec<Vec<u8>> = receipts
+            .iter()
+            .map(|r| r.tx_hash_bytes().unwrap())
+            .collect();
+        let receipt_hashes: Vec<Vec<u8>> = receipts.iter().map(|r| r.hash().unwrap()).collect();
+        let head = Head {
+            version: 1,
+            parent_hash,
+            tx_merkle_hash: merkle_root(&tx_hashes),
+            tx_receipt_merkle_hash: merkle_root(&receipt_hashes),
+            info: vec![],
+            number,
+            witness: producer(i),
+            time: 1598918258274417000 + number * 500_000_000,
+        };
+        let algorithm = algorithm::new(algorithm::SECP256K1);
+        let sig = algorithm.sign(head.hash().as_slice(), &producer_key(i));
+        let sign = Sign {
+            algorithm: SECP256K1_ID,
+            sig: base64::encode(sig),
+            pub_key: head.witness.clone(),
+        };
+        Block {
+            head,
+            sign,
+            receipts,
+            txs: vec![],
+            tx_hashes: tx_hashes.iter().map(base64::encode).collect(),
+            receipt_hashes: receipt_hashes.iter().map(base64::encode).collect(),
+            block_type: "".to_string(),
+        }
+    }
+
+    /// Empty blocks following `parent`, produced in turn by `witnesses`
+    pub(crate) fn following_blocks(parent: &Head, witnesses: &[usize]) -> Vec<Block> {
+        let mut parent_hash = parent.hash();
+        let mut blocks = Vec::new();
+        for (n, i) in witnesses.iter().enumerate() {
+            let b = signed_block(parent_hash, parent.number + n as i64 + 1, *i, vec![]);
+            parent_hash = b.hash();
+            blocks.push(b);
+        }
+        blocks
+    }
+
+    fn vote_block() -> Block {
+        signed_block(
+            vec![1; 32],
+            2 * VOTE_INTERVAL,
+            0,
+            vec![
+                receipt(
+                    1,
+                    "token.iost/transfer",
+                    r#"["iost","admin","lispczz3","10",""]"#,
+                ),
+                stat_receipt(&producers(VERIFIER_NUM..2 * VERIFIER_NUM)),
+                receipt(2, "ram.iost/buy", r#"["admin","lispczz3",1024]"#),
+            ],
+        )
+    }
+
+    fn proof(witnesses: &[usize]) -> EpochChangeProof {
+        let vote_block = vote_block();
+        let witness_blocks = following_blocks(&vote_block.head, witnesses);
+        EpochChangeProof::new(&vote_block, &witness_blocks).unwrap()
+    }
+
+    #[test]
+    fn should_epoch_change_proof_be_verified() {
+        let mut v = Verify::with_epoch(VOTE_INTERVAL, producers(0..VERIFIER_NUM));
+        let witnesses: Vec<usize> = (0..CONFIRM_WITNESS_NUM).collect();
+        let proof = proof(&witnesses);
+
+        let encoded = proof.encode();
+        let decoded = EpochChangeProof::decode(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, proof);
+
+        assert!(v.apply_epoch_change(&decoded).is_ok());
+        assert_eq!(
+            v.epoch_producers(2 * VOTE_INTERVAL),
+            Some(&producers(VERIFIER_NUM..2 * VERIFIER_NUM))
+        );
+        assert_eq!(
+            v.producers_of(2 * VOTE_INTERVAL + 1).unwrap(),
+            &producers(VERIFIER_NUM..2 * VERIFIER_NUM)
+        );
+    }
+
+    #[test]
+    fn should_forged_epoch_change_proof_be_rejected() {
+        let v = Verify::with_epoch(VOTE_INTERVAL, producers(0..VERIFIER_NUM));
+        let witnesses: Vec<usize> = (0..CONFIRM_WITNESS_NUM).collect();
+
+        // producers not elected by the vote block
+        let mut forged = proof(&witnesses);
+        forged.stat_receipt = stat_receipt(&producers(1..VERIFIER_NUM + 1))
+            .to_data()
+            .unwrap();
+        assert!(v.verify_epoch_change(&forged).is_err());
+
+        // a producer repeated does not count twice
+        let mut repeated = witnesses.clone();
+        repeated[CONFIRM_WITNESS_NUM - 1] = 0;
+        assert!(v.verify_epoch_change(&proof(&repeated)).is_err());
+
+        // a witness which is not a producer of the epoch does not count
+        let mut outsider = witnesses.clone();
+        outsider[0] = VERIFIER_NUM;
+        assert!(v.verify_epoch_change(&proof(&outsider)).is_err());
+
+        // witness blocks not chained to the vote block
+        let mut unchained = proof(&witnesses);
+        unchained.witness_blocks.remove(1);
+        assert!(v.verify_epoch_change(&unchained).is_err());
+
+        // vote block head not signed by its witness
+        let mut unsigned = proof(&witnesses);
+        unsigned.vote_block.time += 1;
+        assert!(v.verify_epoch_change(&unsigned).is_err());
+
+        // no producers known for the previous epoch
+        let unknown = Verify::with_epoch(0, producers(0..VERIFIER_NUM));
+        assert!(unknown.verify_epoch_change(&proof(&witnesses)).is_err());
+    }
+
+    #[test]
+    fn should_pending_list_be_parsed() {
+        assert_eq!(
+            parse_pending_list(r#"{"pendingList":["a","b"],"currentList":["c"]}"#).unwrap(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert!(parse_pending_list(r#"{"currentList":["c"]}"#).is_err());
+        assert!(parse_pending_list(r#"["a","b"]"#).is_err());
+        assert_eq!(
+            parse_current_list(r#"{"pendingList":["a","b"],"currentList":["c"]}"#).unwrap(),
+            vec!["c".to_string()]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::spv::{Head, Verify, CONFIRM_WITNESS_NUM};
+use crate::verify::BlockHead;
+use crate::Error::IOSTBlockWitnessError;
+use crate::Result;
+
+/// Change of the branch accepted by a `ForkChoice`
+#[derive(Clone, Debug, PartialEq)]
+pub enum ForkEvent {
+    /// blocks appended to the accepted branch
+    Extended(Vec<Head>),
+    /// blocks of the accepted branch superseded by the blocks of another branch
+    Reorg {
+        removed: Vec<Head>,
+        added: Vec<Head>,
+    },
+    /// blocks which cannot be reverted anymore, oldest first
+    Irreversible(Vec<Head>),
+}
+
+/// Follows the competing branches after the last irreversible block and accepts the one
+/// confirmed by enough distinct producers
+#[derive(Clone, Debug)]
+pub struct ForkChoice {
+    irreversible: Head,
+    accepted: Vec<Head>,
+}
+
+/// A candidate branch and the number of its blocks confirmed by the blocks on top of them
+struct Branch {
+    heads: Vec<Head>,
+    confirmed: usize,
+}
+
+impl ForkChoice {
+    /// Fork choice starting at the trusted block `irreversible`
+    pub fn new(irreversible: Head) -> ForkChoice {
+        ForkChoice {
+            irreversible,
+            accepted: Vec::new(),
+        }
+    }
+
+    /// The last irreversible block
+    pub fn irreversible(&self) -> &Head {
+        &self.irreversible
+    }
+
+    /// The accepted blocks after the last irreversible block, which may still be reverted
+    pub fn accepted(&self) -> &[Head] {
+        self.accepted.as_slice()
+    }
+
+    /// Choose among `branches`, each following the last irreversible block, the branch whose
+    /// blocks are confirmed by at least `CONFIRM_WITNESS_NUM` distinct producers of their epoch.
+    /// Branches with a wrong signature, a broken link or a block produced by a witness which is
+    /// not a producer of its epoch are ignored. Among the branches with the
+    /// most confirmed blocks the longest one is accepted, the accepted branch winning ties.
+    /// The state is left unchanged on error
+    pub fn update(&mut self, v: &Verify, branches: &[Vec<BlockHead>]) -> Result<Vec<ForkEvent>> {
+        let candidates: Vec<Branch> = branches
+            .iter()
+            .filter_map(|b| self.parse_branch(v, b).ok())
+            .map(|heads| Branch {
+                confirmed: confirmed_len(v, &heads),
+                heads,
+            })
+            .filter(|b| b.confirmed > 0)
+            .collect();
+
+        let best = candidates
+            .iter()
+            .max_by_key(|b| {
+                (
+                    b.confirmed,
+                    b.heads.len(),
+                    common_len(&self.accepted, &b.heads),
+                )
+            })
+            .ok_or_else(|| {
+                IOSTBlockWitnessError(format!(
+                    "no branch confirmed after block {}",
+                    self.irreversible.number
+                ))
+            })?;
+        for other in candidates.iter() {
+            let confirmed = other.confirmed.min(best.confirmed);
+            if common_len(&best.heads[..confirmed], &other.heads[..confirmed]) < confirmed {
+                return Err(IOSTBlockWitnessError(format!(
+                    "conflicting branches confirmed after block {}",
+                    self.irreversible.number
+                )));
+            }
+        }
+
+        let mut events = Vec::new();
+        let common = common_len(&self.accepted, &best.heads);
+        if common < self.accepted.len() {
+            events.push(ForkEvent::Reorg {
+                removed: self.accepted[common..].to_vec(),
+                added: best.heads[common..].to_vec(),
+            });
+        } else if common < best.heads.len() {
+            events.push(ForkEvent::Extended(best.heads[common..].to_vec()));
+        }
+        let irreversible = best.heads[..best.confirmed].to_vec();
+        self.irreversible = best.heads[best.confirmed - 1].clone();
+        self.accepted = best.heads[best.confirmed..].to_vec();
+        events.push(ForkEvent::Irreversible(irreversible));
+        Ok(events)
+    }
+
+    /// Heads of the branch, once their signatures, their witnesses and their links to the
+    /// irreversible block are checked
+    fn parse_branch(&self, v: &Verify, branch: &[BlockHead]) -> Result<Vec<Head>> {
+        let mut parent_hash = self.irreversible.hash();
+        let mut parent_block_number = self.irreversible.number;
+        let mut heads = Vec::with_capacity(branch.len());
+        for b in branch.iter() {
+            let head = b.verified_head()?;
+            if head.parent_hash != parent_hash || head.number != parent_block_number + 1 {
+                return Err(IOSTBlockWitnessError(format!(
+                    "block {} does not follow block {}",
+                    head.number, parent_block_number
+                )));
+            }
+            if !v.producers_of(head.number)?.contains(&head.witness) {
+                return Err(IOSTBlockWitnessError(format!(
+                    "witness {} of block {} is not a producer of its epoch",
+                    head.witness, head.number
+                )));
+            }
+            parent_hash = head.hash();
+            parent_block_number = head.number;
+            heads.push(head);
+        }
+        Ok(heads)
+    }
+}
+
+/// Number of blocks of `heads` followed by blocks of at least `CONFIRM_WITNESS_NUM` distinct
+/// producers of their epoch. An ancestor has all the followers of its descendants, so the
+/// confirmed blocks are a prefix of the branch
+fn confirmed_len(v: &Verify, heads: &[Head]) -> usize {
+    let mut followers: BTreeSet<&str> = BTreeSet::new();
+    for (i, head) in heads.iter().enumerate().rev() {
+        if let Ok(producers) = v.producers_of(head.number) {
+            let count = producers
+                .iter()
+                .filter(|p| followers.contains(p.as_str()))
+                .count();
+            if count >= CONFIRM_WITNESS_NUM {
+                return i + 1;
+            }
+        }
+        followers.insert(head.witness.as_str());
+    }
+    0
+}
+
+/// Length of the common prefix of two branches
+fn common_len(a: &[Head], b: &[Head]) -> usize {
+    a.iter()
+        .zip(b.iter())
+        .take_while(|(x, y)| x.hash() == y.hash())
+        .count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spv::epoch_proof::test::{following_blocks, producers, signed_block};
+    use crate::spv::{Block, VERIFIER_NUM, VOTE_INTERVAL};
+
+    fn heads(blocks: &[Block]) -> Vec<BlockHead> {
+        blocks
+            .iter()
+            .map(|b| BlockHead::from_head(&b.head, &b.sign))
+            .collect()
+    }
+
+    fn setup() -> (Verify, ForkChoice, Block) {
+        let v = Verify::with_epoch(VOTE_INTERVAL, producers(0..VERIFIER_NUM));
+        let root = signed_block(vec![1; 32], VOTE_INTERVAL + 1, 0, vec![]);
+        (v, ForkChoice::new(root.head.clone()), root)
+    }
+
+    #[test]
+    fn should_confirmed_branch_be_accepted() {
+        let (v, mut fork_choice, root) = setup();
+        let confirmed = following_blocks(&root.head, &(1..14).collect::<Vec<_>>());
+        let unconfirmed = following_blocks(&root.head, &[3, 4]);
+
+        let events = fork_choice
+            .update(&v, &[heads(&unconfirmed), heads(&confirmed)])
+            .unwrap();
+        let confirmed_heads: Vec<Head> = confirmed.iter().map(|b| b.head.clone()).collect();
+        assert_eq!(
+            events,
+            vec![
+                ForkEvent::Extended(confirmed_heads.clone()),
+                ForkEvent::Irreversible(confirmed_heads[..1].to_vec()),
+            ]
+        );
+        assert_eq!(fork_choice.irreversible(), &confirmed[0].head);
+        assert_eq!(fork_choice.accepted(), &confirmed_heads[1..]);
+
+        // none of the branches is confirmed
+        let mut state = fork_choice.clone();
+        assert!(state.update(&v, &[heads(&unconfirmed)]).is_err());
+        assert_eq!(state.irreversible(), fork_choice.irreversible());
+    }
+
+    #[test]
+    fn should_reorg_be_emitted() {
+        let (v, mut fork_choice, root) = setup();
+        let first = following_blocks(&root.head, &(1..14).collect::<Vec<_>>());
+        fork_choice.update(&v, &[heads(&first)]).unwrap();
+
+        // the accepted blocks after the new irreversible block are superseded by a branch
+        // confirmed by other producers
+        let witnesses = [14, 15, 16, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let second = following_blocks(&first[0].head, &witnesses);
+        let events = fork_choice
+            .update(&v, &[heads(&first[1..]), heads(&second)])
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![
+                ForkEvent::Reorg {
+                    removed: first[1..].iter().map(|b| b.head.clone()).collect(),
+                    added: second.iter().map(|b| b.head.clone()).collect(),
+                },
+                ForkEvent::Irreversible(vec![second[0].head.clone()]),
+            ]
+        );
+        assert_eq!(fork_choice.irreversible(), &second[0].head);
+    }
+
+    #[test]
+    fn should_invalid_and_conflicting_branches_be_rejected() {
+        let (v, mut fork_choice, root) = setup();
+        let valid = following_blocks(&root.head, &(1..14).collect::<Vec<_>>());
+        let mut forged = heads(&following_blocks(&root.head, &(4..17).collect::<Vec<_>>()));
+        forged[3].time += 1;
+
+        // a branch with a wrong signature is ignored
+        let mut state = fork_choice.clone();
+        assert!(state.update(&v, &[forged, heads(&valid)]).is_ok());
+        assert_eq!(state.irreversible(), &valid[0].head);
+
+        // two branches confirmed at the same height
+        let other = following_blocks(&root.head, &(4..17).collect::<Vec<_>>());
+        assert!(fork_choice
+            .update(&v, &[heads(&valid), heads(&other)])
+            .is_err());
+        assert_eq!(fork_choice.irreversible(), &root.head);
+    }
+
+    #[test]
+    fn should_branch_of_non_producer_be_ignored() {
+        let (v, mut fork_choice, root) = setup();
+        let mut witnesses = vec![VERIFIER_NUM];
+        witnesses.extend(1..14);
+        let outsider = following_blocks(&root.head, &witnesses);
+
+        // the blocks after the one of the outsider would confirm it
+        assert!(fork_choice.update(&v, &[heads(&outsider)]).is_err());
+        assert_eq!(fork_choice.irreversible(), &root.head);
+
+        let valid = following_blocks(&root.head, &(1..14).collect::<Vec<_>>());
+        assert!(fork_choice
+            .update(&v, &[heads(&outsider), heads(&valid)])
+            .is_ok());
+        assert_eq!(fork_choice.irreversible(), &valid[0].head);
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
@@ -10,9 +11,11 @@ use keys::algorithm;
 
 use crate::spv::Sign;
 use crate::verify::BlockHead;
-use crate::{NumberBytes, Read, SerializeData, Write};
+use crate::Error::{IOSTBlockVerifyError, IOSTInvalidBlockSignature};
+use crate::{algorithm_name, NumberBytes, Read, Result, SerializeData, Write};
+use crate::{ED25519_ID, SECP256K1_ID};
 
-#[derive(Debug, Clone, NumberBytes, SerializeData, Write, Read)]
+#[derive(Debug, Clone, PartialEq, NumberBytes, SerializeData, Write, Read)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[iost_root_path = "crate"]
 pub struct Head {
@@ -59,13 +62,50 @@ impl Head {
     }
 
     pub fn verify(&self, sign: Sign) -> bool {
-        let ed25519 = algorithm::new(algorithm::ED25519);
-        let sign = base64::decode(sign.sig.as_str()).unwrap();
-        let pub_key = bs58::decode(self.witness.as_str()).into_vec().unwrap();
-        ed25519.verify(self.hash().as_slice(), pub_key.as_slice(), sign.as_slice())
+        self.verify_sign(&sign).is_ok()
+    }
+
+    /// Check that `sign` is the signature of the head by its witness,
+    /// with the algorithm given by `sign.algorithm`
+    pub fn verify_sign(&self, sign: &Sign) -> Result<()> {
+        let witness = bs58::decode(self.witness.as_str())
+            .into_vec()
+            .map_err(|_| IOSTBlockVerifyError(format!("invalid witness {}", self.witness)))?;
+        if !is_witness_key(sign.pub_key.as_str(), self.witness.as_str(), &witness) {
+            return Err(IOSTBlockVerifyError(format!(
+                "block {} is signed by {} instead of its witness {}",
+                self.number, sign.pub_key, self.witness
+            )));
+        }
+        let name = algorithm_name(sign.algorithm).ok_or_else(|| {
+            IOSTBlockVerifyError(format!(
+                "unknown signature algorithm {} at block {}",
+                sign.algorithm, self.number
+            ))
+        })?;
+        let sig = base64::decode(sign.sig.as_str()).map_err(|_| IOSTInvalidBlockSignature())?;
+        let sig = match (sign.algorithm, sig.len()) {
+            (ED25519_ID, 64) if witness.len() == 32 => sig,
+            // the recovery id of a secp256k1 signature is not verified
+            (SECP256K1_ID, 64) | (SECP256K1_ID, 65) if witness.len() == 33 => sig[..64].to_vec(),
+            _ => return Err(IOSTInvalidBlockSignature()),
+        };
+        if algorithm::new(name).verify(self.hash().as_slice(), witness.as_slice(), sig.as_slice()) {
+            Ok(())
+        } else {
+            Err(IOSTInvalidBlockSignature())
+        }
     }
 }
 
+/// The public key of a block signature is either the witness itself or its base64 encoded bytes
+fn is_witness_key(pub_key: &str, witness: &str, witness_bytes: &[u8]) -> bool {
+    pub_key == witness
+        || base64::decode(pub_key)
+            .map(|key| key == witness_bytes)
+            .unwrap_or(false)
+}
+
 pub fn from_block_head(bh: &BlockHead) -> Head {
     let mut head = Head {
         version: bh.version,
@@ -102,3 +142,80 @@ where
     let res = s.parse::<i64>().unwrap();
     Ok(res)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn head(witness: String) -> Head {
+        Head {
+            version: 1,
+            parent_hash: vec![1; 32],
+            tx_merkle_hash: vec![],
+            tx_receipt_merkle_hash: vec![],
+            info: vec![],
+            number: 1201,
+            witness,
+            time: 1598918258274417000,
+        }
+    }
+
+    fn signed_head(algorithm_id: u8, sec_key: &[u8]) -> (Head, Sign) {
+        let algorithm = algorithm::new(algorithm_name(algorithm_id).unwrap());
+        let pub_key = algorithm.get_pub_key(sec_key).unwrap();
+        let head = head(bs58::encode(&pub_key).into_string());
+        let sig = algorithm.sign(head.hash().as_slice(), sec_key);
+        let sign = Sign {
+            algorithm: algorithm_id,
+            sig: base64::encode(sig),
+            pub_key: base64::encode(pub_key),
+        };
+        (head, sign)
+    }
+
+    fn ed25519_key() -> Vec<u8> {
+        base64::decode("gkpobuI3gbFGstgfdymLBQAGR67ulguDzNmLXEJSWaGUNL5J0z5qJUdsPJdqm+uyDIrEWD2Ym4dY9lv8g0FFZg==").unwrap()
+    }
+
+    fn secp256k1_key() -> Vec<u8> {
+        bs58::decode("xjggJ3TrLXz7qEwrGG3Rc4Fz59imjixhXpViq9W7Ncx")
+            .into_vec()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_ed25519_head_be_verified() {
+        let (head, sign) = signed_head(ED25519_ID, &ed25519_key());
+        assert!(head.verify_sign(&sign).is_ok());
+        assert!(head.verify(sign));
+    }
+
+    #[test]
+    fn should_secp256k1_head_be_verified() {
+        let (head, mut sign) = signed_head(SECP256K1_ID, &secp256k1_key());
+        assert!(head.verify_sign(&sign).is_ok());
+
+        // the public key can also be given as the witness itself
+        sign.pub_key = head.witness.clone();
+        assert!(head.verify_sign(&sign).is_ok());
+    }
+
+    #[test]
+    fn should_wrong_head_signature_be_rejected() {
+        let (mut head, sign) = signed_head(SECP256K1_ID, &secp256k1_key());
+        head.number += 1;
+        assert!(head.verify_sign(&sign).is_err());
+
+        // signed with the wrong algorithm id
+        let (head, mut sign) = signed_head(ED25519_ID, &ed25519_key());
+        sign.algorithm = SECP256K1_ID;
+        assert!(head.verify_sign(&sign).is_err());
+        sign.algorithm = 0;
+        assert!(head.verify_sign(&sign).is_err());
+
+        // signed by a key which is not the witness
+        let (_, other) = signed_head(SECP256K1_ID, &secp256k1_key());
+        let (head, _) = signed_head(ED25519_ID, &ed25519_key());
+        assert!(head.verify_sign(&other).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use alloc::vec::Vec;
+
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+fn hash_pair(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha3_256::new();
+    hasher.input(left);
+    hasher.input(right);
+    hasher.result().to_vec()
+}
+
+/// Root of the merkle tree the node builds over the tx or receipt hashes of a block.
+///
+/// A node hashes the concatenation of its two children, or takes the hash of its left child
+/// when it has no right one, as go-iost does over the leaves padded with nil up to a power of
+/// two. The root of an empty tree is empty.
+pub fn merkle_root(leaves: &[Vec<u8>]) -> Vec<u8> {
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hash_pair(left, right),
+                _ => pair[0].clone(),
+            })
+            .collect();
+    }
+    level.pop().unwrap_or_default()
+}
+
+/// Path from a leaf to the root of the tree built by `merkle_root`
+#[derive(Clone, Debug, Default, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct MerkleProof {
+    /// position of the leaf
+    pub index: u32,
+    /// number of leaves of the tree
+    pub leaf_count: u32,
+    /// hashes of the siblings from the leaf level up, lone nodes have none
+    pub siblings: Vec<Vec<u8>>,
+}
+
+impl MerkleProof {
+    /// Proof of the leaf at `index`, `None` if it is out of range
+    pub fn new(leaves: &[Vec<u8>], index: usize) -> Option<MerkleProof> {
+        if index >= leaves.len() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut level = leaves.to_vec();
+        let mut i = index;
+        while level.len() > 1 {
+            let sibling = i ^ 1;
+            if sibling < level.len() {
+                siblings.push(level[sibling].clone());
+            }
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(left, right),
+                    _ => pair[0].clone(),
+                })
+                .collect();
+            i /= 2;
+        }
+        Some(MerkleProof {
+            index: index as u32,
+            leaf_count: leaves.len() as u32,
+            siblings,
+        })
+    }
+
+    /// Root of the tree containing `leaf`, `None` if the proof is malformed
+    pub fn root(&self, leaf: &[u8]) -> Option<Vec<u8>> {
+        if self.index >= self.leaf_count {
+            return None;
+        }
+        let mut siblings = self.siblings.iter();
+        let mut hash = leaf.to_vec();
+        let (mut i, mut count) = (self.index, self.leaf_count);
+        while count > 1 {
+            if i % 2 == 1 {
+                hash = hash_pair(siblings.next()?, &hash);
+            } else if i + 1 < count {
+                hash = hash_pair(&hash, siblings.next()?);
+            }
+            i /= 2;
+            count = (count + 1) / 2;
+        }
+        match siblings.next() {
+            None => Some(hash),
+            Some(_) => None,
+        }
+    }
+
+    /// Whether `leaf` is in the tree whose root is `root`
+    pub fn verify(&self, leaf: &[u8], root: &[u8]) -> bool {
+        self.root(leaf).map_or(false, |r| r.as_slice() == root)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sha3(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha3_256::new();
+        hasher.input(data);
+        hasher.result().to_vec()
+    }
+
+    /// `MerkleTree.Build` of go-iost `core/merkletree`, written the way it is there: the leaves
+    /// are copied at the bottom of a heap of `2n - 1` nodes, `n` being the number of leaves
+    /// rounded up to a power of two, and a node is nil without a left child, its left child
+    /// without a right one, or else the hash of both
+    fn heap_root(leaves: &[Vec<u8>]) -> Vec<u8> {
+        if leaves.is_empty() {
+            return vec![];
+        }
+        let n = leaves.len().next_power_of_two();
+        let mut nodes: Vec<Option<Vec<u8>>> = vec![None; 2 * n - 1];
+        for (i, leaf) in leaves.iter().enumerate() {
+            nodes[n - 1 + i] = Some(leaf.clone());
+        }
+        for i in (0..n - 1).rev() {
+            nodes[i] = match (&nodes[2 * i + 1], &nodes[2 * i + 2]) {
+                (Some(left), Some(right)) => Some(sha3(&[left.clone(), right.clone()].concat())),
+                (left, _) => left.clone(),
+            };
+        }
+        nodes[0].take().unwrap_or_default()
+    }
+
+    #[test]
+    fn should_merkle_root_match_padded_tree() {
+        for count in 0..=33usize {
+            let leaves: Vec<Vec<u8>> = (0..count).map(|i| sha3(&[i as u8])).collect();
+            let root = heap_root(&leaves);
+            assert_eq!(merkle_root(&leaves), root, "{} leaves", count);
+            for (index, leaf) in leaves.iter().enumerate() {
+                assert!(MerkleProof::new(&leaves, index)
+                    .unwrap()
+                    .verify(leaf, &root));
+            }
+        }
+    }
+
+    #[test]
+    fn should_merkle_root_be_ok() {
+        let leaves: Vec<Vec<u8>> = (0u8..5).map(|i| sha3(&[i])).collect();
+        assert!(merkle_root(&[]).is_empty());
+        assert_eq!(merkle_root(&leaves[..1]), leaves[0]);
+        assert_eq!(
+            merkle_root(&leaves[..2]),
+            sha3(&[leaves[0].clone(), leaves[1].clone()].concat())
+        );
+
+        // a lone node is promoted to the next level
+        let ab = sha3(&[leaves[0].clone(), leaves[1].clone()].concat());
+        let cd = sha3(&[leaves[2].clone(), leaves[3].clone()].concat());
+        let abcd = sha3(&[ab.clone(), cd].concat());
+        assert_eq!(
+            merkle_root(&leaves[..3]),
+            sha3(&[ab, leaves[2].clone()].concat())
+        );
+        assert_eq!(
+            merkle_root(&leaves),
+            sha3(&[abcd, leaves[4].clone()].concat())
+        );
+    }
+
+    #[test]
+    fn should_merkle_proof_be_verified() {
+        for count in 1..10usize {
+            let leaves: Vec<Vec<u8>> = (0..count).map(|i| sha3(&[i as u8])).collect();
+            let root = merkle_root(&leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = MerkleProof::new(&leaves, index).unwrap();
+                assert!(proof.verify(leaf, &root));
+                assert!(!proof.verify(&sha3(b"other"), &root));
+                let decoded = MerkleProof::decode(&mut proof.encode().as_slice()).unwrap();
+                assert_eq!(decoded, proof);
+            }
+            assert!(MerkleProof::new(&leaves, count).is_none());
+        }
+    }
+}
@@ -1,9 +1,35 @@
-pub use self::{block::*, head::*, tx::*, verify::*};
+pub use self::{
+    block::*, deposit_proof::*, epoch_proof::*, fork::*, head::*, merkle::*, snapshot::*, tx::*,
+    verify::*,
+};
+
+#[cfg(feature = "std")]
+use alloc::format;
+
+#[cfg(feature = "std")]
+use crate::Error::IOSTBlockVerifyError;
+#[cfg(feature = "std")]
+use crate::Result;
 
 pub mod block;
+pub mod deposit_proof;
+pub mod epoch_proof;
+pub mod fork;
 pub mod head;
+pub mod merkle;
+pub mod snapshot;
 pub mod tx;
 pub mod verify;
 
 pub const VOTE_INTERVAL: i64 = 1200;
 pub const VERIFIER_NUM: usize = 17;
+/// Number of distinct producers of an epoch which have to build on a block to confirm it
+pub const CONFIRM_WITNESS_NUM: usize = 12;
+
+/// Parse an int64 the node sends as a string
+#[cfg(feature = "std")]
+pub(crate) fn parse_i64(field: &str, value: &str) -> Result<i64> {
+    value
+        .parse::<i64>()
+        .map_err(|_| IOSTBlockVerifyError(format!("invalid {} {}", field, value)))
+}
@@ -0,0 +1,253 @@
+use alloc::format;
+
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use crate::spv::{
+    parse_current_list, parse_pending_list, stat_content, EpochChangeProof, MerkleProof, Verify,
+    STAT_RECEIPT, VERIFIER_NUM, VOTE_INTERVAL,
+};
+use crate::verify::BlockHead;
+use crate::Error::IOSTSnapshotError;
+use crate::{Result, TxReceiptData};
+
+/// State of a `Verify` taken at an epoch boundary, with the signed head of the vote block opening
+/// its last epoch and the receipt electing its producers, so that a restarted relayer does not
+/// have to bootstrap again
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct VerifySnapshot {
+    pub verify: Verify,
+    /// head of the vote block of the last epoch, its `hash` is the stored header hash
+    pub vote_block: BlockHead,
+    /// `vote_producer.iost/stat` receipt of the vote block
+    pub stat_receipt: TxReceiptData,
+    /// merkle path of the stat receipt to the receipt merkle hash of the vote block
+    pub stat_receipt_proof: MerkleProof,
+}
+
+impl Verify {
+    /// Snapshot of the verifier at the vote block of `proof`, which has to be the vote block of
+    /// its last epoch. The witness blocks of the proof are not kept
+    pub fn snapshot(&self, proof: &EpochChangeProof) -> Result<VerifySnapshot> {
+        let snapshot = VerifySnapshot {
+            verify: self.clone(),
+            vote_block: proof.vote_block.clone(),
+            stat_receipt: proof.stat_receipt.clone(),
+            stat_receipt_proof: proof.stat_receipt_proof.clone(),
+        };
+        snapshot.check()?;
+        Ok(snapshot)
+    }
+}
+
+impl VerifySnapshot {
+    /// Hash of the head of the vote block the snapshot was taken at
+    pub fn header_hash(&self) -> &[u8] {
+        self.vote_block.hash.as_slice()
+    }
+
+    /// Check that the vote block head is signed by its witness and matches the stored header
+    /// hash, that it opens the last epoch, that every epoch is well formed, and that the stat
+    /// receipt is in the vote block, elects the producers of the last epoch and lists the witness
+    /// of the vote block among the producers of the previous one
+    pub fn check(&self) -> Result<()> {
+        let head = self.vote_block.verified_head()?;
+        if head.hash() != self.vote_block.hash {
+            return Err(IOSTSnapshotError(format!(
+                "header hash {} unmatch the head of block {}",
+                base64::encode(&self.vote_block.hash),
+                head.number
+            )));
+        }
+        if self.verify.latest_epoch() != Some(head.number) {
+            return Err(IOSTSnapshotError(format!(
+                "block {} is not the vote block of the last epoch {:?}",
+                head.number,
+                self.verify.latest_epoch()
+            )));
+        }
+        for (number, producers) in self.verify.epochs() {
+            if number % VOTE_INTERVAL != 0 || producers.len() != VERIFIER_NUM {
+                return Err(IOSTSnapshotError(format!(
+                    "invalid epoch at block {} with {} producers",
+                    number,
+                    producers.len()
+                )));
+            }
+        }
+
+        let leaf = self.stat_receipt.hash()?;
+        if !self
+            .stat_receipt_proof
+            .verify(&leaf, &head.tx_receipt_merkle_hash)
+        {
+            return Err(IOSTSnapshotError(format!(
+                "{} receipt is not in block {}",
+                STAT_RECEIPT, head.number
+            )));
+        }
+        let content = stat_content(&self.stat_receipt, head.number)?;
+        if self.verify.epoch_producers(head.number) != Some(&parse_pending_list(content)?) {
+            return Err(IOSTSnapshotError(format!(
+                "producers of the last epoch unmatch the {} receipt of block {}",
+                STAT_RECEIPT, head.number
+            )));
+        }
+        let current_list = parse_current_list(content)?;
+        if !current_list.contains(&head.witness) {
+            return Err(IOSTSnapshotError(format!(
+                "witness {} of block {} is not a producer of the previous epoch",
+                head.witness, head.number
+            )));
+        }
+        if let Ok(previous) = self.verify.producers_of(head.number) {
+            if previous != &current_list {
+                return Err(IOSTSnapshotError(format!(
+                    "producers of the previous epoch unmatch the {} receipt of block {}",
+                    STAT_RECEIPT, head.number
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The verifier of the snapshot, once checked
+    pub fn restore(self) -> Result<Verify> {
+        self.check()?;
+        Ok(self.verify)
+    }
+
+    /// Same as `restore`, also requiring the stored header hash to be `header_hash`, obtained
+    /// from a trusted source
+    pub fn restore_at(self, header_hash: &[u8]) -> Result<Verify> {
+        if self.header_hash() != header_hash {
+            return Err(IOSTSnapshotError(format!(
+                "header hash {} unmatch the trusted hash {}",
+                base64::encode(self.header_hash()),
+                base64::encode(header_hash)
+            )));
+        }
+        self.restore()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spv::epoch_proof::test::{producers, receipt, signed_block, stat_receipt};
+    use crate::spv::Block;
+
+    /// Vote block of the epoch `VOTE_INTERVAL` produced by `witness`, whose stat receipt lists
+    /// `current_list` as the producers of the previous epoch
+    fn vote_block(witness: usize, current_list: &[String]) -> Block {
+        let content = serde_json::json!({
+            "pendingList": producers(0..VERIFIER_NUM),
+            "currentList": current_list,
+        });
+        signed_block(
+            vec![1; 32],
+            VOTE_INTERVAL,
+            witness,
+            vec![
+                receipt(
+                    1,
+                    "token.iost/transfer",
+                    r#"["iost","admin","lispczz3","10",""]"#,
+                ),
+                receipt(2, STAT_RECEIPT, &content.to_string()),
+            ],
+        )
+    }
+
+    fn proof(vote_block: &Block) -> EpochChangeProof {
+        EpochChangeProof::new(vote_block, &[]).unwrap()
+    }
+
+    fn snapshot() -> VerifySnapshot {
+        let v = Verify::with_epoch(VOTE_INTERVAL, producers(0..VERIFIER_NUM));
+        v.snapshot(&proof(&vote_block(0, &producers(0..VERIFIER_NUM))))
+            .unwrap()
+    }
+
+    #[test]
+    fn should_snapshot_be_restored() {
+        let snapshot = snapshot();
+
+        let encoded = snapshot.encode();
+        let decoded = VerifySnapshot::decode(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, snapshot);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let parsed: VerifySnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, snapshot);
+
+        let hash = snapshot.header_hash().to_vec();
+        let v = parsed.restore_at(&hash).unwrap();
+        assert_eq!(v, snapshot.verify);
+        assert_eq!(v.latest_epoch(), Some(VOTE_INTERVAL));
+        assert!(decoded.restore().is_ok());
+    }
+
+    #[test]
+    fn should_corrupted_snapshot_be_rejected() {
+        let snapshot = snapshot();
+        assert!(snapshot.clone().restore_at(&[0; 32]).is_err());
+
+        let mut tampered = snapshot.clone();
+        tampered.vote_block.hash = vec![0; 32];
+        assert!(tampered.restore_at(&[0; 32]).is_err());
+
+        let mut resigned = snapshot.clone();
+        resigned.vote_block.time += 1;
+        assert!(resigned.restore().is_err());
+
+        // the snapshot is not taken at the last epoch
+        let mut later = snapshot.clone();
+        later
+            .verify
+            .insert_epoch(2 * VOTE_INTERVAL, producers(0..VERIFIER_NUM));
+        assert!(later.restore().is_err());
+
+        let mut short = snapshot.clone();
+        short.verify = Verify::with_epoch(VOTE_INTERVAL, producers(0..3));
+        assert!(short.restore().is_err());
+    }
+
+    #[test]
+    fn should_snapshot_of_unproven_epoch_be_rejected() {
+        let snapshot = snapshot();
+
+        // a stat receipt which is not in the vote block
+        let mut forged = snapshot.clone();
+        forged.stat_receipt = stat_receipt(&producers(1..VERIFIER_NUM + 1))
+            .to_data()
+            .unwrap();
+        assert!(forged.restore().is_err());
+
+        let mut misplaced = snapshot.clone();
+        misplaced.stat_receipt_proof.index = 0;
+        assert!(misplaced.restore().is_err());
+
+        // producers of the last epoch not elected by the stat receipt
+        let mut elected = snapshot.clone();
+        elected.verify = Verify::with_epoch(VOTE_INTERVAL, producers(1..VERIFIER_NUM + 1));
+        assert!(elected.restore().is_err());
+
+        // a vote block produced by a producer of the previous epoch
+        let mut previous = Verify::with_epoch(0, producers(VERIFIER_NUM..2 * VERIFIER_NUM));
+        previous.insert_epoch(VOTE_INTERVAL, producers(0..VERIFIER_NUM));
+        let by_producer = vote_block(VERIFIER_NUM, &producers(VERIFIER_NUM..2 * VERIFIER_NUM));
+        assert!(previous.snapshot(&proof(&by_producer)).is_ok());
+
+        // a vote block produced by a witness which is not
+        let by_outsider = vote_block(0, &producers(VERIFIER_NUM..2 * VERIFIER_NUM));
+        assert!(previous.snapshot(&proof(&by_outsider)).is_err());
+
+        // producers of the previous epoch not those listed by the stat receipt
+        assert!(previous
+            .snapshot(&proof(&vote_block(0, &producers(0..VERIFIER_NUM))))
+            .is_err());
+    }
+}
@@ -1,9 +1,17 @@
+#[cfg(feature = "std")]
+use alloc::format;
 use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::string::ToString;
 use alloc::vec::Vec;
 
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "std")]
+use crate::spv::parse_i64;
+#[cfg(feature = "std")]
+use crate::{algorithm_name, Error::IOSTBlockVerifyError, IostAction, Result, Signature};
 use crate::{Action, AmountLimit};
 
 #[derive(Debug)]
@@ -51,7 +59,9 @@ pub struct Tx {
     /// Signer ID other than publisher. It can be empty.
     pub signers: Vec<String>,
     /// Signature of signers. Each signer can have one or more signatures, so the length is not less than the length of signers
-    // pub signatures: Vec<Signature>,
+    #[cfg(feature = "std")]
+    #[serde(default)]
+    pub signs: Vec<Sign>,
     #[cfg(feature = "std")]
     #[serde(rename = "referredTx")]
     pub referred_tx: Option<String>,
@@ -64,3 +74,153 @@ pub struct TxReceiptStatus {
     pub code: i32,
     pub message: String,
 }
+
+#[cfg(feature = "std")]
+impl Sign {
+    pub fn to_signature(&self) -> Result<Signature> {
+        let algorithm = algorithm_name(self.algorithm).ok_or_else(|| {
+            IOSTBlockVerifyError(format!("unknown signature algorithm {}", self.algorithm))
+        })?;
+        Ok(Signature {
+            algorithm: algorithm.to_string(),
+            signature: self.sig.clone(),
+            public_key: self.pub_key.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Tx {
+    /// Convert to a `chain::Tx`, parsing the numbers the node sends as strings.
+    /// Gas ratio and gas limit are sent multiplied by 100.
+    pub fn to_tx(&self) -> Result<crate::Tx> {
+        Ok(crate::Tx {
+            time: parse_i64("time", &self.time)?,
+            expiration: parse_i64("expiration", &self.expiration)?,
+            gas_ratio: parse_i64("gas ratio", &self.gas_ratio)? as f64 / 100.0,
+            gas_limit: parse_i64("gas limit", &self.gas_limit)? as f64 / 100.0,
+            delay: parse_i64("delay", &self.delay)?,
+            chain_id: self.chain_id,
+            actions: self
+                .actions
+                .iter()
+                .cloned()
+                .map(IostAction::from_shadow_action)
+                .collect(),
+            amount_limit: self.amount_limit.clone(),
+            publisher: self.publisher.clone(),
+            publisher_sigs: self
+                .publisher_sigs
+                .iter()
+                .map(|s| s.to_signature())
+                .collect::<Result<_>>()?,
+            signers: self.signers.clone(),
+            signatures: self
+                .signs
+                .iter()
+                .map(|s| s.to_signature())
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Hash of the transaction as listed in `Block.tx_hashes`
+    pub fn hash(&self) -> Result<Vec<u8>> {
+        let referred_tx = self.referring_tx_hash()?.unwrap_or_default();
+        self.to_tx()?.hash_with_referred_tx(&referred_tx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{TxBuilder, TxBytesLevel, MAINNET_CHAIN_ID};
+    use keys::algorithm;
+    use sha3::{Digest, Sha3_256};
+
+    const SEC_KEY: &str =
+        "gkpobuI3gbFGstgfdymLBQAGR67ulguDzNmLXEJSWaGUNL5J0z5qJUdsPJdqm+uyDIrEWD2Ym4dY9lv8g0FFZg==";
+
+    fn signed_tx() -> crate::Tx {
+        let mut tx = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time(1544709662543340000)
+            .gas_ratio(1.5)
+            .publisher("testaccount")
+            .signer("anothertest@active")
+            .action(IostAction::transfer("testaccount", "anothertest", "100", "").unwrap())
+            .build()
+            .unwrap();
+        let sec_key = base64::decode(SEC_KEY).unwrap();
+        let base = tx.to_bytes(TxBytesLevel::Base).unwrap();
+        tx.signatures =
+            vec![Signature::sign(&Sha3_256::digest(&base), algorithm::ED25519, &sec_key).unwrap()];
+        tx.sign("testaccount".to_string(), algorithm::ED25519, &sec_key)
+            .unwrap();
+        tx
+    }
+
+    fn sign_json(signature: &Signature) -> serde_json::Value {
+        serde_json::json!({
+            "algorithm": 2,
+            "sig": signature.signature,
+            "pub_key": signature.public_key,
+        })
+    }
+
+    fn to_spv(tx: &crate::Tx) -> Tx {
+        let value = serde_json::json!({
+            "time": tx.time.to_string(),
+            "expiration": tx.expiration.to_string(),
+            "gasRatio": "150",
+            "gasLimit": "100000000",
+            "delay": "0",
+            "chain_id": tx.chain_id,
+            "actions": [{
+                "contract": "token.iost",
+                "action_name": "transfer",
+                "data": String::from_utf8(tx.actions[0].data.clone()).unwrap(),
+            }],
+            "amountLimit": tx.amount_limit,
+            "publisher": tx.publisher,
+            "publishSigns": tx.publisher_sigs.iter().map(sign_json).collect::<Vec<_>>(),
+            "signers": tx.signers,
+            "signs": tx.signatures.iter().map(sign_json).collect::<Vec<_>>(),
+            "referredTx": "",
+            "reserved": null,
+        });
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn should_spv_tx_convert_to_tx() {
+        let tx = signed_tx();
+        let spv_tx = to_spv(&tx);
+        let converted = spv_tx.to_tx().unwrap();
+        assert!(converted.verify().is_ok());
+        assert_eq!(converted.gas_ratio, 1.5);
+        assert_eq!(converted.gas_limit, 1000000.0);
+        assert_eq!(
+            converted.to_bytes(TxBytesLevel::Full).unwrap(),
+            tx.to_bytes(TxBytesLevel::Full).unwrap()
+        );
+        assert_eq!(spv_tx.hash().unwrap(), tx.hash().unwrap());
+    }
+
+    #[test]
+    fn should_tx_bytes_grow_with_level() {
+        let tx = signed_tx();
+        let base = tx.to_bytes(TxBytesLevel::Base).unwrap();
+        let publish = tx.to_bytes(TxBytesLevel::Publish).unwrap();
+        let full = tx.to_bytes(TxBytesLevel::Full).unwrap();
+        assert!(publish.starts_with(&base));
+        assert!(full.starts_with(&publish));
+        // signature count, then one algorithm id, raw signature and raw public key
+        assert_eq!(publish.len() - base.len(), 4 + 4 + 1 + 4 + 64 + 4 + 32);
+    }
+
+    #[test]
+    fn should_unknown_sign_algorithm_be_rejected() {
+        let mut spv_tx = to_spv(&signed_tx());
+        spv_tx.publisher_sigs[0].algorithm = 7;
+        assert!(spv_tx.to_tx().is_err());
+    }
+}
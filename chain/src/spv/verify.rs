@@ -1,22 +1,28 @@
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::format;
-use alloc::string::{String, ToString};
+use alloc::string::String;
 use alloc::vec::Vec;
 
+use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
 use crate::Error::*;
 use crate::Result;
+#[cfg(feature = "std")]
+use crate::{ReceiptEvent, VoteProducerEvent};
 
-use super::{Block, Head, VERIFIER_NUM, VOTE_INTERVAL};
+#[cfg(feature = "std")]
+use super::{Block, VERIFIER_NUM};
+use super::{Head, CONFIRM_WITNESS_NUM, VOTE_INTERVAL};
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Verify {
     epoch_producer: BTreeMap<i64, Vec<String>>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct WitnessStatus {
     #[cfg(feature = "std")]
@@ -29,6 +35,44 @@ pub struct WitnessStatus {
 }
 
 impl Verify {
+    /// Verifier trusting `producers` for the epoch elected at the vote block `vote_block_number`
+    pub fn with_epoch(vote_block_number: i64, producers: Vec<String>) -> Verify {
+        let mut v = Verify::default();
+        v.insert_epoch(vote_block_number, producers);
+        v
+    }
+
+    /// Producers elected at the vote block `vote_block_number`
+    pub fn epoch_producers(&self, vote_block_number: i64) -> Option<&Vec<String>> {
+        self.epoch_producer.get(&vote_block_number)
+    }
+
+    /// Vote block of the last epoch known
+    pub fn latest_epoch(&self) -> Option<i64> {
+        self.epoch_producer.keys().next_back().copied()
+    }
+
+    /// Producers of every epoch known, by vote block
+    pub fn epochs(&self) -> impl Iterator<Item = (&i64, &Vec<String>)> {
+        self.epoch_producer.iter()
+    }
+
+    /// Producers of the block `block_number`, elected at the previous vote block
+    pub fn producers_of(&self, block_number: i64) -> Result<&Vec<String>> {
+        self.epoch_producer
+            .get(&epoch_start(block_number))
+            .ok_or_else(|| {
+                IOSTBlockWitnessError(format!(
+                    "cannot update producer list at block {}: cannot find producer info of previous epoch",
+                    block_number
+                ))
+            })
+    }
+
+    pub(crate) fn insert_epoch(&mut self, vote_block_number: i64, producers: Vec<String>) {
+        self.epoch_producer.insert(vote_block_number, producers);
+    }
+
     #[cfg(feature = "std")]
     pub fn check_block(&self, block: &Block, block_list: Vec<Block>) -> Result<()> {
         #[cfg(feature = "std")]
@@ -111,22 +155,24 @@ pub fn init(block: &Block) -> Result<Verify> {
 pub fn get_witness_status_from_block(block: &Block) -> Option<WitnessStatus> {
     for tx_receipt in block.receipts.iter() {
         for receipt in tx_receipt.receipts.iter() {
-            if receipt.func_name == "vote_producer.iost/stat" {
-                #[cfg(feature = "std")]
-                match serde_json::from_str(&receipt.content) {
-                    Ok(ws) => {
-                        return Some(ws);
-                    }
-                    Err(_) => {
-                        continue;
-                    }
-                }
+            if let Ok(ReceiptEvent::VoteProducer(VoteProducerEvent::Stat(ws))) = receipt.decode() {
+                return Some(ws);
             }
         }
     }
     None
 }
 
+/// Vote block electing the producers of the block `block_number`. A vote block itself is
+/// produced by the previous epoch
+fn epoch_start(block_number: i64) -> i64 {
+    if block_number % VOTE_INTERVAL == 0 {
+        block_number - VOTE_INTERVAL
+    } else {
+        block_number / VOTE_INTERVAL * VOTE_INTERVAL
+    }
+}
+
 #[cfg(feature = "std")]
 pub fn check_witness(v: &Verify, block: &Block, witness_blocks: Vec<Block>) -> Result<()> {
     if let Err(_) = block.verify_self() {
@@ -139,62 +185,49 @@ pub fn check_witness(v: &Verify, block: &Block, witness_blocks: Vec<Block>) -> R
         }
     }
 
-    let block_number: i64 = block.head.number;
-    let current_epoch_start_block = if block_number % VOTE_INTERVAL == 0 {
-        block_number - VOTE_INTERVAL
-    } else {
-        block_number / VOTE_INTERVAL * VOTE_INTERVAL
-    };
-
-    match v.epoch_producer.get(&current_epoch_start_block) {
-        Some(pending_list) => {
-            let mut valid_witness_count = 0;
-            let mut valid_witness: BTreeMap<String, bool> = BTreeMap::new();
-
-            let mut parent_hash = block.head.hash();
-            let mut parent_block_number = block.head.number;
-
-            for b in witness_blocks.iter() {
-                // let block_parent_hash = &b.head.parent_hash;
-                if parent_hash.as_slice() != b.head.parent_hash.as_slice() {
-                    return Err(IOSTBlockWitnessError(format!(
-                        "invalid block hash at block {}",
-                        b.head.number
-                    )));
-                }
-                if parent_block_number + 1 != b.head.number {
-                    return Err(IOSTBlockWitnessError(format!(
-                        "invalid block number at block {}",
-                        b.head.number
-                    )));
-                }
+    let pending_list = v.producers_of(block.head.number)?;
+    check_confirmations(
+        &block.head,
+        witness_blocks.iter().map(|b| &b.head),
+        pending_list,
+    )
+}
 
-                match valid_witness.get(&b.head.witness) {
-                    None => {
-                        for produce in pending_list.iter() {
-                            if produce.eq(&b.head.witness) {
-                                valid_witness.insert(produce.to_string(), true);
-                                valid_witness_count = valid_witness_count + 1;
-                                break;
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-                parent_block_number = b.head.number;
-                parent_hash = b.head.hash();
-            }
-            if valid_witness_count < 12 {
-                return Err(IOSTBlockWitnessError(format!(
-                    "valid witness not enough {}",
-                    valid_witness_count
-                )));
-            }
-            Ok(())
+/// Check that `witness_heads` follow `head` one block after the other and that at least
+/// `CONFIRM_WITNESS_NUM` distinct `producers` produced them. The signatures are not checked
+pub fn check_confirmations<'a, I>(head: &Head, witness_heads: I, producers: &[String]) -> Result<()>
+where
+    I: IntoIterator<Item = &'a Head>,
+{
+    let mut valid_witness: BTreeSet<&str> = BTreeSet::new();
+
+    let mut parent_hash = head.hash();
+    let mut parent_block_number = head.number;
+
+    for h in witness_heads {
+        if parent_hash.as_slice() != h.parent_hash.as_slice() {
+            return Err(IOSTBlockWitnessError(format!(
+                "invalid block hash at block {}",
+                h.number
+            )));
+        }
+        if parent_block_number + 1 != h.number {
+            return Err(IOSTBlockWitnessError(format!(
+                "invalid block number at block {}",
+                h.number
+            )));
         }
-        None => Err(IOSTBlockWitnessError(format!(
-            "cannot update producer list at block {}: cannot find producer info of previous epoch",
-            block_number
-        ))),
+        if let Some(produce) = producers.iter().find(|p| **p == h.witness) {
+            valid_witness.insert(produce.as_str());
+        }
+        parent_block_number = h.number;
+        parent_hash = h.hash();
+    }
+    if valid_witness.len() < CONFIRM_WITNESS_NUM {
+        return Err(IOSTBlockWitnessError(format!(
+            "valid witness not enough {}",
+            valid_witness.len()
+        )));
     }
+    Ok(())
 }
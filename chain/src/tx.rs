@@ -8,11 +8,21 @@ use lite_json::{JsonValue, NumberValue, Serialize};
 use serde::{Deserialize, Serialize as SerSerialize};
 use sha3::{Digest, Sha3_256};
 
-use crate::Error::{InvalidPublisherSignature, InvalidSignature};
+use crate::Error::InvalidPublisherSignature;
 use crate::{
-    AmountLimit, IostAction, NumberBytes, Read, SerializeData, Signature, Write, WriteError,
+    validate_account_name, validate_signer, AmountLimit, IostAction, NumberBytes, Read,
+    SerializeData, Signature, Write,
 };
 
+/// Chain id of the IOST mainnet
+pub const MAINNET_CHAIN_ID: u32 = 1024;
+/// The minimum gas limit accepted by the node
+pub const MIN_GAS_LIMIT: f64 = 50000.0;
+/// The lowest gas ratio accepted by the node
+pub const MIN_GAS_RATIO: f64 = 1.0;
+/// The highest gas ratio accepted by the node, which rejects txs with a gas ratio out of [1.0, 100.0]
+pub const MAX_GAS_RATIO: f64 = 100.0;
+
 #[derive(Clone, Default, Debug, Read, Write, NumberBytes, SerializeData)]
 #[cfg_attr(feature = "std", derive(Deserialize, SerSerialize))]
 #[iost_root_path = "crate"]
@@ -43,18 +53,56 @@ pub struct Tx {
     pub signatures: Vec<Signature>,
 }
 
-fn expand<T>(x: &Vec<T>, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError>
-where
-    T: NumberBytes + Write,
-{
-    for item in x.iter() {
-        item.num_bytes().write(bytes, pos)?;
-        item.write(bytes, pos)?;
-    }
-    Ok(())
+/// How much of a transaction goes into its bytes, as in the node
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum TxBytesLevel {
+    /// The bytes signed by the signers
+    Base,
+    /// The bytes signed by the publisher, with the signatures of the signers
+    Publish,
+    /// The bytes of the tx hash, with the referred tx, the publisher and its signatures
+    Full,
+}
+
+/// Fields of a transaction in the order the node encodes them
+#[derive(NumberBytes, Write)]
+#[iost_root_path = "crate"]
+struct TxBaseBytes {
+    time: i64,
+    expiration: i64,
+    gas_ratio: i64,
+    gas_limit: i64,
+    delay: i64,
+    chain_id: i32,
+    reserved: Vec<u8>,
+    signers: Vec<String>,
+    actions: Vec<Vec<u8>>,
+    amount_limit: Vec<Vec<u8>>,
+}
+
+#[derive(NumberBytes, Write)]
+#[iost_root_path = "crate"]
+struct TxPublisherBytes {
+    referred_tx: Vec<u8>,
+    publisher: String,
+    publisher_sigs: Vec<Vec<u8>>,
+}
+
+fn encode<T: NumberBytes + Write>(value: &T) -> crate::Result<Vec<u8>> {
+    let mut data = vec![0u8; value.num_bytes()];
+    value
+        .write(&mut data, &mut 0)
+        .map_err(crate::Error::BytesWriteError)?;
+    Ok(data)
+}
+
+fn encode_signatures(signatures: &[Signature]) -> crate::Result<Vec<Vec<u8>>> {
+    signatures.iter().map(|s| s.to_bytes()).collect()
 }
 
 impl Tx {
+    /// A transaction allowed to spend any amount of any token, with "*: unlimited"
+    #[deprecated(note = "use `TxBuilder`, which derives the amount limits from the actions")]
     pub fn new(time: i64, expiration: i64, chain_id: u32, actions: Vec<IostAction>) -> Self {
         let amount_limit = AmountLimit {
             token: "*".to_string(),
@@ -77,7 +125,9 @@ impl Tx {
         }
     }
 
+    /// A transaction of the mainnet allowed to spend any amount of any token, with "*: unlimited"
     #[cfg(feature = "std")]
+    #[deprecated(note = "use `TxBuilder`, which derives the amount limits from the actions")]
     pub fn from_action(actions: Vec<IostAction>) -> Self {
         let amount_limit = AmountLimit {
             token: "*".to_string(),
@@ -91,7 +141,7 @@ impl Tx {
             gas_ratio: 1.0,
             gas_limit: 1000000.0,
             delay: 0,
-            chain_id: 1024,
+            chain_id: MAINNET_CHAIN_ID,
             actions,
             amount_limit: vec![amount_limit],
             publisher: "".to_string(),
@@ -209,94 +259,109 @@ impl Tx {
         String::from_utf8(self.no_std_serialize_vec()).unwrap()
     }
 
-    #[inline]
-    fn number_bytes(&self, with_sign: bool) -> usize {
-        let res = 48
-            + self.signers.num_bytes()
-            + self.signers.len() * 4
-            + self.actions.num_bytes()
-            + self.actions.len() * 4
-            + self.amount_limit.num_bytes()
-            + self.amount_limit.len() * 4;
-        if with_sign {
-            res + self.signatures.num_bytes() + self.signatures.len() * 4
-        } else {
-            res
-        }
+    /// Bytes of the transaction as the node encodes them at the given level
+    pub fn to_bytes(&self, level: TxBytesLevel) -> crate::Result<Vec<u8>> {
+        self.to_bytes_with_referred_tx(level, &[])
     }
 
-    fn write(&self, bytes: &mut [u8], pos: &mut usize, with_sign: bool) -> Result<(), WriteError> {
-        self.time.clone().write(bytes, pos);
-        self.expiration.clone().write(bytes, pos);
-        let mut ratio = (self.gas_ratio * 100.0) as i64;
-        ratio.write(bytes, pos);
-        let mut limit = (self.gas_limit * 100.0) as i64;
-        limit.write(bytes, pos);
-        self.delay.clone().write(bytes, pos);
-        (self.chain_id.clone() as i32).write(bytes, pos);
-
-        // reserved field
-        0_i32.write(bytes, pos);
-
-        self.signers.len().write(bytes, pos)?;
-        expand::<String>(&self.signers, bytes, pos);
-        self.actions.len().write(bytes, pos);
-        expand::<IostAction>(&self.actions, bytes, pos);
-        self.amount_limit.len().write(bytes, pos);
-        expand::<AmountLimit>(&self.amount_limit, bytes, pos);
-        if with_sign {
-            self.signatures.len().write(bytes, pos);
-            expand::<Signature>(&self.signatures, bytes, pos);
+    pub(crate) fn to_bytes_with_referred_tx(
+        &self,
+        level: TxBytesLevel,
+        referred_tx: &[u8],
+    ) -> crate::Result<Vec<u8>> {
+        let mut data = encode(&TxBaseBytes {
+            time: self.time,
+            expiration: self.expiration,
+            gas_ratio: (self.gas_ratio * 100.0).round() as i64,
+            gas_limit: (self.gas_limit * 100.0).round() as i64,
+            delay: self.delay,
+            chain_id: self.chain_id as i32,
+            reserved: vec![],
+            signers: self.signers.clone(),
+            actions: self
+                .actions
+                .iter()
+                .map(encode)
+                .collect::<crate::Result<_>>()?,
+            amount_limit: self
+                .amount_limit
+                .iter()
+                .map(encode)
+                .collect::<crate::Result<_>>()?,
+        })?;
+        if level >= TxBytesLevel::Publish {
+            data.extend(encode(&encode_signatures(&self.signatures)?)?);
         }
-        Ok(())
+        if level >= TxBytesLevel::Full {
+            data.extend(encode(&TxPublisherBytes {
+                referred_tx: referred_tx.to_vec(),
+                publisher: self.publisher.clone(),
+                publisher_sigs: encode_signatures(&self.publisher_sigs)?,
+            })?);
+        }
+        Ok(data)
+    }
+
+    /// Hash of the transaction, as returned by the node once base58 encoded
+    pub fn hash(&self) -> crate::Result<Vec<u8>> {
+        self.hash_with_referred_tx(&[])
+    }
+
+    pub(crate) fn hash_with_referred_tx(&self, referred_tx: &[u8]) -> crate::Result<Vec<u8>> {
+        let mut hasher = Sha3_256::new();
+        hasher.input(self.to_bytes_with_referred_tx(TxBytesLevel::Full, referred_tx)?);
+        Ok(hasher.result().to_vec())
     }
 
     pub fn customized_to_serialize_data(&self, with_sign: bool) -> crate::Result<Vec<u8>> {
-        let mut data = vec![0u8; self.number_bytes(with_sign)];
-        self.write(&mut data, &mut 0, with_sign)
-            .map_err(crate::Error::BytesWriteError)
-            .unwrap();
-        Ok(data.to_vec())
+        if with_sign {
+            self.to_bytes(TxBytesLevel::Publish)
+        } else {
+            self.to_bytes(TxBytesLevel::Base)
+        }
     }
 
+    /// Sign the transaction as its publisher `account_name`, once the publisher and the signers
+    /// are checked to be valid account names
     pub fn sign(
         &mut self,
         account_name: String,
         sign_algorithm: &str,
         sec_key: &[u8],
-    ) -> Result<(), WriteError> {
+    ) -> crate::Result<()> {
+        validate_account_name(&account_name)?;
+        for signer in self.signers.iter() {
+            validate_signer(signer)?;
+        }
         self.publisher = account_name;
 
         if self.publisher_sigs.len() == 0 {
-            let tx_bytes = self.customized_to_serialize_data(true).unwrap();
+            let tx_bytes = self.customized_to_serialize_data(true)?;
             // create a SHA3-256 object
             let mut hasher = Sha3_256::new();
             hasher.input(tx_bytes);
             let result = hasher.result();
-            self.publisher_sigs =
-                vec![Signature::sign(result.as_ref(), sign_algorithm, sec_key).unwrap()];
+            self.publisher_sigs = vec![Signature::sign(result.as_ref(), sign_algorithm, sec_key)?];
         }
         Ok(())
     }
 
     pub fn verify(&self) -> crate::Result<()> {
         for signature in &self.signatures {
-            let tx_bytes = self.customized_to_serialize_data(false).unwrap();
+            let tx_bytes = self.customized_to_serialize_data(false)?;
             let mut hasher = Sha3_256::new();
             hasher.input(tx_bytes);
             let result = hasher.result();
-            if !signature.verify(result.as_slice()) {
-                return Err(InvalidSignature());
-            }
+            signature.verify(result.as_slice())?;
         }
         for publisher_sig in &self.publisher_sigs {
-            let tx_bytes = self.customized_to_serialize_data(true).unwrap();
+            let tx_bytes = self.customized_to_serialize_data(true)?;
             let mut hasher = Sha3_256::new();
             hasher.input(tx_bytes);
             let result = hasher.result();
-            if !publisher_sig.verify(result.as_slice()) {
-                return Err(InvalidPublisherSignature());
-            }
+            publisher_sig
+                .verify(result.as_slice())
+                .map_err(|_| InvalidPublisherSignature())?;
         }
         Ok(())
     }
@@ -305,14 +370,21 @@ impl Tx {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::TxBuilder;
+    use keys::algorithm;
 
     #[test]
     fn test_bytes_serialization() {
-        let tx = Tx::from_action(vec![IostAction {
-            contract: "token.iost".to_string().into_bytes(),
-            action_name: "transfer".to_string().into_bytes(),
-            data: r#"["iost","admin","lispczz3","100",""]"#.to_string().into_bytes(),
-        }]);
+        let tx = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time_now()
+            .publisher("admin")
+            .action(IostAction {
+                contract: "token.iost".to_string().into_bytes(),
+                action_name: "transfer".to_string().into_bytes(),
+                data: r#"["iost","admin","lispczz3","100",""]"#.to_string().into_bytes(),
+            })
+            .build()
+            .unwrap();
 
         let mut data = tx.to_serialize_data().unwrap();
         // assert!(data.is_ok());
@@ -324,39 +396,25 @@ mod test {
     }
 
     #[test]
-    fn test_send_tx() {
+    fn test_sign_tx() {
         let action = IostAction::transfer("lispczz4", "lispczz5", "10", "").unwrap();
-        // let mut tx = Tx::from_action(vec![Action {
-        //     contract: "token.iost".to_string().into_bytes(),
-        //     action_name: "transfer".to_string().into_bytes(),
-        //     data: r#"["iost","lispczz4","lispczz5","8",""]"#.to_string().into_bytes(),
-        // }]);
-
-        let mut tx = Tx::from_action(vec![action]);
-        // let sec_key = "2yquS3ySrGWPEKywCPzX4RTJugqRh7kJSo5aehsLYPEWkUxBWA39oMrZ7ZxuM4fgyXYs2cPwh5n8aNNpH5x2VyK1".from_base58().unwrap();
+        let mut tx = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time_now()
+            .publisher("lispczz4")
+            .action(action)
+            .build()
+            .unwrap();
         let sec_key = bs58::decode("xjggJ3TrLXz7qEwrGG3Rc4Fz59imjixhXpViq9W7Ncx")
             .into_vec()
             .unwrap();
-        // let sec_key = base64::decode("2yquS3ySrGWPEKywCPzX4RTJugqRh7kJSo5aehsLYPEWkUxBWA39oMrZ7ZxuM4fgyXYs2cPwh5n8aNNpH5x2VyK1").unwrap();
         tx.sign(
             "lispczz4".to_string(),
             algorithm::SECP256K1,
             sec_key.as_slice(),
-        );
+        )
+        .unwrap();
         let result = tx.verify();
         assert!(result.is_ok());
-
-        // let tx_string = serde_json::to_string_pretty(&tx).unwrap();
-        let tx_string = tx.no_std_serialize();
-        // dbg!(tx_string);
-        // dbg!(tx.no_std_serialize());
-        let client = reqwest::blocking::Client::new();
-        let res = client
-            .post("http://127.0.0.1:30001/sendTx")
-            .body(tx_string)
-            .send()
-            .unwrap();
-        dbg!(res.text());
     }
 
     #[test]
@@ -515,4 +573,129 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn should_tx_sign_reject_invalid_publisher() {
+        let mut tx = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time_now()
+            .publisher("lispczz4")
+            .action(IostAction::transfer("lispczz4", "lispczz5", "10", "").unwrap())
+            .build()
+            .unwrap();
+        let sec_key = bs58::decode("xjggJ3TrLXz7qEwrGG3Rc4Fz59imjixhXpViq9W7Ncx")
+            .into_vec()
+            .unwrap();
+        let result = tx.sign("lisp".to_string(), algorithm::SECP256K1, sec_key.as_slice());
+        assert!(matches!(result, Err(crate::Error::ParseIostNameErr(_))));
+        assert!(tx.publisher_sigs.is_empty());
+
+        tx.signers = vec!["lispczz5@active".to_string(), "lisp@active".to_string()];
+        let result = tx.sign(
+            "lispczz4".to_string(),
+            algorithm::SECP256K1,
+            sec_key.as_slice(),
+        );
+        assert!(matches!(result, Err(crate::Error::ParseIostNameErr(_))));
+        assert!(tx.publisher_sigs.is_empty());
+        tx.signers.pop();
+        tx.sign(
+            "lispczz4".to_string(),
+            algorithm::SECP256K1,
+            sec_key.as_slice(),
+        )
+        .unwrap();
+        assert_eq!(tx.publisher_sigs.len(), 1);
+    }
+
+    /// The signed example of `sendTx` in the IOST API documentation. Its publisher signature
+    /// was made by the reference wallet, so it only verifies over the publish bytes of the node
+    const DOCUMENTED_TX: &str = r#"{
+        "time": 1544709662543340000,
+        "expiration": 1544709692318715000,
+        "gas_ratio": 1,
+        "gas_limit": 500000,
+        "delay": 0,
+        "chain_id": 1024,
+        "signers": [],
+        "actions": [{
+            "contract": "token.iost",
+            "action_name": "transfer",
+            "data": "[\"iost\", \"testaccount\", \"anothertest\", \"100\", \"this is an example transfer\"]"
+        }],
+        "amount_limit": [{"token": "*", "value": "unlimited"}],
+        "signatures": [],
+        "publisher": "testaccount",
+        "publisher_sigs": [{
+            "algorithm": "ED25519",
+            "public_key": "lDS+SdM+aiVHbDyXapvrsgyKxFg9mJuHWPZb/INBRWY=",
+            "signature": "/K1HM0OEbfJ4+D3BmalpLmb03WS7BeCz4nVHBNbDrx3/A31aN2RJNxyEKhv+VSoWctfevDNRnL1kadRVxSt8CA=="
+        }]
+    }"#;
+
+    #[test]
+    fn documented_tx_should_match_known_answers() {
+        let tx: Tx = serde_json::from_str(DOCUMENTED_TX).unwrap();
+        assert!(tx.verify().is_ok());
+
+        let base = tx.to_bytes(TxBytesLevel::Base).unwrap();
+        let publish = tx.to_bytes(TxBytesLevel::Publish).unwrap();
+        let full = tx.to_bytes(TxBytesLevel::Full).unwrap();
+        assert_eq!((base.len(), publish.len(), full.len()), (192, 196, 328));
+        assert_eq!(
+            hex::encode(Sha3_256::digest(&publish)),
+            "fe007c4c94226c623b29e9b5bf8bc93dc27bbc73f8f06b928587ddffb361677c"
+        );
+        // the publisher signature is the last part of the full bytes
+        let signature = base64::decode(&tx.publisher_sigs[0].signature).unwrap();
+        let public_key = base64::decode(&tx.publisher_sigs[0].public_key).unwrap();
+        assert_eq!(full[full.len() - 32..], public_key[..]);
+        assert_eq!(
+            full[full.len() - 32 - 4 - 64..full.len() - 36],
+            signature[..]
+        );
+        assert_eq!(
+            bs58::encode(tx.hash().unwrap()).into_string(),
+            "CihRgNwEPjWnaXgogcFhK31r7EkWZyU9H6ZxzyWF37yK"
+        );
+    }
+    #[test]
+    fn malformed_signatures_should_be_rejected() {
+        let tx: Tx = serde_json::from_str(DOCUMENTED_TX).unwrap();
+
+        let mut not_base64 = tx.clone();
+        not_base64.publisher_sigs[0].signature = "not base64!".to_string();
+        assert!(not_base64.verify().is_err());
+
+        let mut short_key = tx.clone();
+        short_key.publisher_sigs[0].public_key = "AAAA".to_string();
+        assert!(short_key.verify().is_err());
+
+        let mut short_signature = tx.clone();
+        short_signature.publisher_sigs[0].signature = "AAAA".to_string();
+        assert!(short_signature.verify().is_err());
+
+        let mut unknown = tx.clone();
+        unknown.publisher_sigs[0].algorithm = "RSA".to_string();
+        assert!(unknown.verify().is_err());
+
+        let mut signer = tx.clone();
+        signer.signatures = vec![tx.publisher_sigs[0].clone()];
+        signer.signatures[0].public_key = "not base64!".to_string();
+        assert!(signer.verify().is_err());
+
+        // secret keys out of range fail to sign instead of panicking
+        let mut unsigned = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time(tx.time)
+            .publisher("admin")
+            .actions(tx.actions.clone())
+            .build()
+            .unwrap();
+        assert!(unsigned
+            .sign("admin".to_string(), algorithm::SECP256K1, &[0; 32])
+            .is_err());
+        assert!(unsigned
+            .sign("admin".to_string(), algorithm::ED25519, &[1; 3])
+            .is_err());
+        assert!(unsigned.publisher_sigs.is_empty());
+    }
 }
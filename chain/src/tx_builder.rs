@@ -0,0 +1,499 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use chrono::Utc;
+
+use crate::{
+    parse_amount, validate_account_name, validate_signer, AmountLimit, ContractCall, IostAction,
+    ParseIostNameError, Tx, MAX_GAS_RATIO, MIN_GAS_LIMIT, MIN_GAS_RATIO,
+};
+
+/// Number of decimals of the iost token
+pub const IOST_DECIMALS: u32 = 8;
+
+/// Default time between the transaction time and its expiration, in nanoseconds
+pub const DEFAULT_EXPIRATION_WINDOW: i64 = 90 * 1_000_000_000;
+
+/// Amount limit value allowing a token to be spent without limit
+pub const UNLIMITED: &str = "unlimited";
+
+/// An error which can be returned when building a transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TxBuildError {
+    /// The transaction time is not set.
+    MissingTime,
+    /// The transaction expires before its time.
+    InvalidExpiration { time: i64, expiration: i64 },
+    /// The delay is negative.
+    NegativeDelay(i64),
+    /// The gas ratio is out of [1.0, 100.0].
+    GasRatioOutOfRange(f64),
+    /// The gas limit is lower than 50000.
+    GasLimitTooLow(f64),
+    /// The transaction has no action.
+    NoAction,
+    /// The transaction has no publisher.
+    MissingPublisher,
+    /// The publisher or a signer is not a valid account name.
+    InvalidAccountName {
+        name: String,
+        error: ParseIostNameError,
+    },
+    /// The amount limit value is neither a non negative decimal number nor "unlimited".
+    MalformedAmountLimit { token: String, value: String },
+    /// The amount limit has more decimals than the token.
+    TooManyDecimals {
+        token: String,
+        value: String,
+        decimals: u32,
+    },
+    /// An unlimited amount limit was requested without `allow_unlimited`.
+    UnlimitedNotAllowed(String),
+}
+
+impl fmt::Display for TxBuildError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingTime => write!(f, "transaction time is not set"),
+            Self::InvalidExpiration { time, expiration } => write!(
+                f,
+                "transaction expiration {} is not after its time {}",
+                expiration, time
+            ),
+            Self::NegativeDelay(delay) => write!(f, "transaction delay {} is negative", delay),
+            Self::GasRatioOutOfRange(ratio) => write!(
+                f,
+                "gas ratio {} is out of [{}, {}]",
+                ratio, MIN_GAS_RATIO, MAX_GAS_RATIO
+            ),
+            Self::GasLimitTooLow(limit) => {
+                write!(f, "gas limit {} is lower than {}", limit, MIN_GAS_LIMIT)
+            }
+            Self::NoAction => write!(f, "transaction has no action"),
+            Self::MissingPublisher => write!(f, "transaction publisher is not set"),
+            Self::InvalidAccountName { name, error } => {
+                write!(f, "invalid account name {}: {}", name, error)
+            }
+            Self::MalformedAmountLimit { token, value } => write!(
+                f,
+                "amount limit {} of token {} is not a non negative number",
+                value, token
+            ),
+            Self::TooManyDecimals {
+                token,
+                value,
+                decimals,
+            } => write!(
+                f,
+                "amount limit {} has more than the {} decimals of token {}",
+                value, decimals, token
+            ),
+            Self::UnlimitedNotAllowed(token) => write!(
+                f,
+                "unlimited amount of token {} requires allow_unlimited",
+                token
+            ),
+        }
+    }
+}
+
+impl From<TxBuildError> for crate::error::Error {
+    fn from(e: TxBuildError) -> crate::error::Error {
+        crate::Error::TxBuildErr(e)
+    }
+}
+
+/// Builds a `Tx` and validates it against the constraints documented on its fields.
+///
+/// Token transfers, destructions and pledges of the publisher and signers get an amount limit
+/// of the exact spent amount. Other spending has to be declared with `amount_limit` or by the
+/// ABI of a `call`, and "*: unlimited" is only added with `allow_unlimited`.
+#[derive(Clone, Debug)]
+pub struct TxBuilder {
+    time: i64,
+    expiration: Option<i64>,
+    expiration_window: i64,
+    delay: i64,
+    chain_id: u32,
+    gas_ratio: f64,
+    gas_limit: f64,
+    actions: Vec<IostAction>,
+    amount_limit: Vec<AmountLimit>,
+    call_amount_limit: Vec<AmountLimit>,
+    publisher: String,
+    signers: Vec<String>,
+    allow_unlimited: bool,
+    token_decimals: BTreeMap<String, u32>,
+}
+
+impl TxBuilder {
+    pub fn new(chain_id: u32) -> Self {
+        let mut token_decimals = BTreeMap::new();
+        token_decimals.insert("iost".to_string(), IOST_DECIMALS);
+        TxBuilder {
+            time: 0,
+            expiration: None,
+            expiration_window: DEFAULT_EXPIRATION_WINDOW,
+            delay: 0,
+            chain_id,
+            gas_ratio: 1.0,
+            gas_limit: 1000000.0,
+            actions: vec![],
+            amount_limit: vec![],
+            call_amount_limit: vec![],
+            publisher: "".to_string(),
+            signers: vec![],
+            allow_unlimited: false,
+            token_decimals,
+        }
+    }
+
+    /// Time of transaction. Unixepoch in nanoseconds
+    pub fn time(mut self, time: i64) -> Self {
+        self.time = time;
+        self
+    }
+
+    #[cfg(feature = "std")]
+    pub fn time_now(self) -> Self {
+        self.time(Utc::now().timestamp_nanos())
+    }
+
+    /// Absolute expiration time. Unixepoch in nanoseconds
+    pub fn expiration(mut self, expiration: i64) -> Self {
+        self.expiration = Some(expiration);
+        self
+    }
+
+    /// Expiration relative to the transaction time, in nanoseconds. Ignored if `expiration` is set
+    pub fn expiration_window(mut self, window: i64) -> Self {
+        self.expiration_window = window;
+        self
+    }
+
+    /// Delay of execution in nanoseconds
+    pub fn delay(mut self, delay: i64) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn gas_ratio(mut self, gas_ratio: f64) -> Self {
+        self.gas_ratio = gas_ratio;
+        self
+    }
+
+    pub fn gas_limit(mut self, gas_limit: f64) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    pub fn action(mut self, action: IostAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    pub fn actions(mut self, actions: Vec<IostAction>) -> Self {
+        self.actions.extend(actions);
+        self
+    }
+
+    /// Add the action of a contract call with the amount limits declared by its ABI. The limits of
+    /// all the calls are summed per token, and replaced by an explicit `amount_limit`
+    pub fn call(mut self, call: ContractCall) -> Self {
+        self.actions.push(call.action);
+        self.call_amount_limit.extend(call.amount_limit);
+        self
+    }
+
+    pub fn publisher<T: AsRef<str>>(mut self, publisher: T) -> Self {
+        self.publisher = publisher.as_ref().to_string();
+        self
+    }
+
+    /// Add a signer other than the publisher, such as "account@active"
+    pub fn signer<T: AsRef<str>>(mut self, signer: T) -> Self {
+        self.signers.push(signer.as_ref().to_string());
+        self
+    }
+
+    /// Declare an amount limit explicitly. It replaces the limit computed from the actions for this token.
+    /// Its decimals are only checked for iost and for the tokens given to `token_decimals`, the node
+    /// rejects a limit with more decimals than any other token has
+    pub fn amount_limit<T: AsRef<str>>(mut self, token: T, value: T) -> Self {
+        self.amount_limit.push(AmountLimit::new(
+            token.as_ref().to_string(),
+            value.as_ref().to_string(),
+        ));
+        self
+    }
+
+    /// Allow unlimited amount limits and add "*: unlimited"
+    pub fn allow_unlimited(mut self) -> Self {
+        self.allow_unlimited = true;
+        self
+    }
+
+    /// Number of decimals of a token, used to validate its amount limit. The decimals of iost are known
+    pub fn token_decimals<T: AsRef<str>>(mut self, token: T, decimals: u32) -> Self {
+        self.token_decimals
+            .insert(token.as_ref().to_string(), decimals);
+        self
+    }
+
+    fn validate_amount_limit(&self, limit: &AmountLimit) -> Result<(), TxBuildError> {
+        if limit.value == UNLIMITED {
+            if self.allow_unlimited {
+                return Ok(());
+            }
+            return Err(TxBuildError::UnlimitedNotAllowed(limit.token.clone()));
+        }
+        let malformed = || TxBuildError::MalformedAmountLimit {
+            token: limit.token.clone(),
+            value: limit.value.clone(),
+        };
+        let (_, decimals) = parse_amount(&limit.value).map_err(|_| malformed())?;
+        match self.token_decimals.get(&limit.token) {
+            Some(max) if decimals > *max => Err(TxBuildError::TooManyDecimals {
+                token: limit.token.clone(),
+                value: limit.value.clone(),
+                decimals: *max,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn build_amount_limit(&self) -> crate::Result<Vec<AmountLimit>> {
+        // signers are written as "account@permission"
+        let spenders: Vec<&str> = core::iter::once(self.publisher.as_str())
+            .chain(self.signers.iter().filter_map(|s| s.split('@').next()))
+            .collect();
+        let mut limits = AmountLimit::from_actions(&spenders, &self.actions)?;
+        limits.extend(self.call_amount_limit.iter().cloned());
+        let mut limits: Vec<AmountLimit> = AmountLimit::sum(&limits)?
+            .into_iter()
+            .filter(|l| !self.amount_limit.iter().any(|e| e.token == l.token))
+            .collect();
+        limits.extend(self.amount_limit.iter().cloned());
+        if self.allow_unlimited && !limits.iter().any(|l| l.token == "*") {
+            limits.push(AmountLimit::new("*".to_string(), UNLIMITED.to_string()));
+        }
+        for limit in limits.iter() {
+            self.validate_amount_limit(limit)?;
+        }
+        Ok(limits)
+    }
+
+    fn validate(&self) -> Result<i64, TxBuildError> {
+        if self.time <= 0 {
+            return Err(TxBuildError::MissingTime);
+        }
+        let expiration = self
+            .expiration
+            .unwrap_or_else(|| self.time.saturating_add(self.expiration_window));
+        if expiration <= self.time {
+            return Err(TxBuildError::InvalidExpiration {
+                time: self.time,
+                expiration,
+            });
+        }
+        if self.delay < 0 {
+            return Err(TxBuildError::NegativeDelay(self.delay));
+        }
+        if !(MIN_GAS_RATIO..=MAX_GAS_RATIO).contains(&self.gas_ratio) {
+            return Err(TxBuildError::GasRatioOutOfRange(self.gas_ratio));
+        }
+        if !(self.gas_limit >= MIN_GAS_LIMIT) {
+            return Err(TxBuildError::GasLimitTooLow(self.gas_limit));
+        }
+        if self.actions.is_empty() {
+            return Err(TxBuildError::NoAction);
+        }
+        if self.publisher.is_empty() {
+            return Err(TxBuildError::MissingPublisher);
+        }
+        validate_account_name(&self.publisher).map_err(|error| {
+            TxBuildError::InvalidAccountName {
+                name: self.publisher.clone(),
+                error,
+            }
+        })?;
+        for signer in self.signers.iter() {
+            validate_signer(signer).map_err(|error| TxBuildError::InvalidAccountName {
+                name: signer.clone(),
+                error,
+            })?;
+        }
+        Ok(expiration)
+    }
+
+    pub fn build(self) -> crate::Result<Tx> {
+        let expiration = self.validate()?;
+        let amount_limit = self.build_amount_limit()?;
+        Ok(Tx {
+            time: self.time,
+            expiration,
+            gas_ratio: self.gas_ratio,
+            gas_limit: self.gas_limit,
+            delay: self.delay,
+            chain_id: self.chain_id,
+            actions: self.actions,
+            amount_limit,
+            publisher: self.publisher,
+            publisher_sigs: vec![],
+            signers: self.signers,
+            signatures: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Error, MAINNET_CHAIN_ID};
+
+    const TIME: i64 = 1598918258274417000;
+
+    fn builder() -> TxBuilder {
+        TxBuilder::new(MAINNET_CHAIN_ID)
+            .time(TIME)
+            .publisher("admin")
+            .action(IostAction::transfer("admin", "lispczz3", "10.5", "").unwrap())
+    }
+
+    #[test]
+    fn amount_limit_should_default_to_actions() {
+        let tx = builder().build().unwrap();
+        assert_eq!(tx.amount_limit.len(), 1);
+        assert_eq!(tx.amount_limit[0].token, "iost");
+        assert_eq!(tx.amount_limit[0].value, "10.5");
+
+        let tx = builder().amount_limit("iost", "20").build().unwrap();
+        assert_eq!(tx.amount_limit.len(), 1);
+        assert_eq!(tx.amount_limit[0].value, "20");
+    }
+
+    #[test]
+    fn unlimited_should_be_opt_in() {
+        let result = builder().amount_limit("*", "unlimited").build();
+        assert!(matches!(
+            result,
+            Err(Error::TxBuildErr(TxBuildError::UnlimitedNotAllowed(_)))
+        ));
+
+        let tx = builder().allow_unlimited().build().unwrap();
+        assert!(tx
+            .amount_limit
+            .iter()
+            .any(|l| l.token == "*" && l.value == UNLIMITED));
+    }
+
+    #[test]
+    fn malformed_amount_limit_should_be_rejected() {
+        for value in ["abc", "-1", "1.2.3", ""].iter() {
+            let result = builder().amount_limit("iost", *value).build();
+            assert!(matches!(
+                result,
+                Err(Error::TxBuildErr(TxBuildError::MalformedAmountLimit { .. }))
+            ));
+        }
+        let result = builder().amount_limit("iost", "0.000000001").build();
+        assert!(matches!(
+            result,
+            Err(Error::TxBuildErr(TxBuildError::TooManyDecimals { .. }))
+        ));
+        let result = builder()
+            .token_decimals("bnb", 2)
+            .amount_limit("bnb", "0.001")
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn tx_fields_should_be_set() {
+        let tx = builder()
+            .expiration_window(30_000_000_000)
+            .delay(10)
+            .gas_ratio(2.0)
+            .gas_limit(MIN_GAS_LIMIT)
+            .signer("lispczz3@active")
+            .build()
+            .unwrap();
+        assert_eq!(tx.time, TIME);
+        assert_eq!(tx.expiration, TIME + 30_000_000_000);
+        assert_eq!(tx.delay, 10);
+        assert_eq!(tx.gas_ratio, 2.0);
+        assert_eq!(tx.gas_limit, MIN_GAS_LIMIT);
+        assert_eq!(tx.chain_id, MAINNET_CHAIN_ID);
+        assert_eq!(tx.signers, vec!["lispczz3@active".to_string()]);
+
+        let tx = builder().expiration(TIME + 1).build().unwrap();
+        assert_eq!(tx.expiration, TIME + 1);
+    }
+
+    #[test]
+    fn protocol_constraints_should_be_validated() {
+        let cases = vec![
+            (
+                TxBuilder::new(MAINNET_CHAIN_ID).publisher("admin"),
+                TxBuildError::MissingTime,
+            ),
+            (
+                builder().expiration(TIME),
+                TxBuildError::InvalidExpiration {
+                    time: TIME,
+                    expiration: TIME,
+                },
+            ),
+            (builder().delay(-1), TxBuildError::NegativeDelay(-1)),
+            (
+                builder().gas_ratio(0.5),
+                TxBuildError::GasRatioOutOfRange(0.5),
+            ),
+            (
+                builder().gas_ratio(101.0),
+                TxBuildError::GasRatioOutOfRange(101.0),
+            ),
+            (
+                builder().gas_limit(49999.0),
+                TxBuildError::GasLimitTooLow(49999.0),
+            ),
+            (
+                TxBuilder::new(MAINNET_CHAIN_ID)
+                    .time(TIME)
+                    .publisher("admin"),
+                TxBuildError::NoAction,
+            ),
+            (
+                TxBuilder::new(MAINNET_CHAIN_ID)
+                    .time(TIME)
+                    .action(IostAction::transfer("admin", "lispczz3", "1", "").unwrap()),
+                TxBuildError::MissingPublisher,
+            ),
+            (
+                builder().publisher("Admin"),
+                TxBuildError::InvalidAccountName {
+                    name: "Admin".to_string(),
+                    error: ParseIostNameError::BadChar('A'),
+                },
+            ),
+            (
+                builder().signer("lisp@active"),
+                TxBuildError::InvalidAccountName {
+                    name: "lisp@active".to_string(),
+                    error: ParseIostNameError::TooShort(4),
+                },
+            ),
+        ];
+        for (builder, expected) in cases {
+            match builder.build() {
+                Err(Error::TxBuildErr(e)) => assert_eq!(e, expected),
+                r => panic!("unexpected result {:?}", r),
+            }
+        }
+    }
+}
@@ -7,7 +7,14 @@ use alloc::collections::btree_map::BTreeMap;
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use crate::{spv::tx::TxReceiptStatus, Receipt};
+#[cfg(feature = "std")]
+use alloc::format;
+use codec::{Decode, Encode};
+use sha3::{Digest, Sha3_256};
+
+#[cfg(feature = "std")]
+use crate::{spv::parse_i64, Error::IOSTBlockVerifyError};
+use crate::{spv::tx::TxReceiptStatus, NumberBytes, Receipt, Result, SerializeData, Write};
 
 #[derive(Debug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -45,3 +52,123 @@ pub struct TxReceipt {
 //     /// for event functions
 //     pub receipts: Vec<Receipt>,
 // }
+
+/// A receipt with the fields decoded as the node hashes them, for proofs which have to be
+/// checked without `std`
+#[derive(Clone, Debug, Default, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TxReceiptData {
+    pub tx_hash: Vec<u8>,
+    /// gas usage multiplied by 100
+    pub gas_usage: i64,
+    /// ram usage by account, sorted by account
+    pub ram_usage: Vec<(String, i64)>,
+    pub status_code: i32,
+    pub message: String,
+    pub returns: Vec<String>,
+    /// `(func_name, content)` of every receipt
+    pub receipts: Vec<(String, String)>,
+}
+
+/// Fields of a receipt in the order the node hashes them
+#[derive(NumberBytes, Write, SerializeData)]
+#[iost_root_path = "crate"]
+struct TxReceiptBytes {
+    tx_hash: Vec<u8>,
+    gas_usage: i64,
+    ram_usage: Vec<(String, i64)>,
+    status_code: i32,
+    message: String,
+    returns: Vec<String>,
+    receipts: Vec<Vec<u8>>,
+}
+
+#[derive(NumberBytes, Write, SerializeData)]
+#[iost_root_path = "crate"]
+struct ReceiptBytes {
+    func_name: String,
+    content: String,
+}
+
+impl TxReceiptData {
+    /// Bytes of the receipt as hashed by the node
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let receipts = self
+            .receipts
+            .iter()
+            .map(|(func_name, content)| {
+                ReceiptBytes {
+                    func_name: func_name.clone(),
+                    content: content.clone(),
+                }
+                .to_serialize_data()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut ram_usage = self.ram_usage.clone();
+        ram_usage.sort();
+        TxReceiptBytes {
+            tx_hash: self.tx_hash.clone(),
+            gas_usage: self.gas_usage,
+            ram_usage,
+            status_code: self.status_code,
+            message: self.message.clone(),
+            returns: self.returns.clone(),
+            receipts,
+        }
+        .to_serialize_data()
+    }
+
+    pub fn hash(&self) -> Result<Vec<u8>> {
+        let mut hasher = Sha3_256::new();
+        hasher.input(self.to_bytes()?);
+        Ok(hasher.result().to_vec())
+    }
+
+    /// Content of the first receipt emitted by `func_name`
+    pub fn find_receipt(&self, func_name: &str) -> Option<&str> {
+        self.receipts
+            .iter()
+            .find(|(name, _)| name == func_name)
+            .map(|(_, content)| content.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl TxReceipt {
+    /// Hash of the transaction, base64 decoded
+    pub fn tx_hash_bytes(&self) -> Result<Vec<u8>> {
+        base64::decode(self.tx_hash.as_str())
+            .map_err(|_| IOSTBlockVerifyError(format!("invalid tx hash {}", self.tx_hash)))
+    }
+
+    /// Parse the fields the node sends as strings
+    pub fn to_data(&self) -> Result<TxReceiptData> {
+        let ram_usage = self
+            .ram_usage
+            .iter()
+            .map(|(account, usage)| Ok((account.clone(), parse_i64("ram usage", usage)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(TxReceiptData {
+            tx_hash: self.tx_hash_bytes()?,
+            gas_usage: parse_i64("gas usage", &self.gas_usage)?,
+            ram_usage,
+            status_code: self.status.code,
+            message: self.status.message.clone(),
+            returns: self.returns.clone(),
+            receipts: self
+                .receipts
+                .iter()
+                .map(|r| (r.func_name.clone(), r.content.clone()))
+                .collect(),
+        })
+    }
+
+    /// Bytes of the receipt as hashed by the node
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.to_data()?.to_bytes()
+    }
+
+    pub fn hash(&self) -> Result<Vec<u8>> {
+        self.to_data()?.hash()
+    }
+}
@@ -1,13 +1,20 @@
+use alloc::format;
+use alloc::string::String;
 use alloc::vec::Vec;
 use core::str::from_utf8;
 
 use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 
 use crate::spv::{Head, Sign};
+use crate::Error::IOSTBlockVerifyError;
+use crate::Result;
 
 use super::super::NumberBytes;
 
 #[derive(Clone, Debug, NumberBytes, PartialEq, Default, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[iost_root_path = "crate"]
 pub struct BlockHead {
     pub version: i64,
@@ -34,6 +41,55 @@ pub struct BlockHead {
 }
 
 impl BlockHead {
+    pub fn from_head(head: &Head, sign: &Sign) -> BlockHead {
+        BlockHead {
+            version: head.version,
+            parent_hash: head.parent_hash.clone(),
+            tx_merkle_hash: head.tx_merkle_hash.clone(),
+            tx_receipt_merkle_hash: head.tx_receipt_merkle_hash.clone(),
+            info: head.info.clone(),
+            number: head.number,
+            witness: head.witness.as_bytes().to_vec(),
+            time: head.time,
+            hash: head.hash(),
+            algorithm: sign.algorithm,
+            sig: sign.sig.as_bytes().to_vec(),
+            pub_key: sign.pub_key.as_bytes().to_vec(),
+        }
+    }
+
+    /// Same as `parse_head` and `parse_sign`, with an error instead of a panic on invalid utf8
+    pub fn try_parse(&self) -> Result<(Head, Sign)> {
+        let utf8 = |bytes: &[u8]| {
+            String::from_utf8(bytes.to_vec()).map_err(|_| {
+                IOSTBlockVerifyError(format!("invalid utf8 in the head of block {}", self.number))
+            })
+        };
+        let head = Head {
+            version: self.version,
+            parent_hash: self.parent_hash.clone(),
+            tx_merkle_hash: self.tx_merkle_hash.clone(),
+            tx_receipt_merkle_hash: self.tx_receipt_merkle_hash.clone(),
+            info: self.info.clone(),
+            number: self.number,
+            witness: utf8(&self.witness)?,
+            time: self.time,
+        };
+        let sign = Sign {
+            algorithm: self.algorithm,
+            sig: utf8(&self.sig)?,
+            pub_key: utf8(&self.pub_key)?,
+        };
+        Ok((head, sign))
+    }
+
+    /// The head, once its signature by the witness is checked
+    pub fn verified_head(&self) -> Result<Head> {
+        let (head, sign) = self.try_parse()?;
+        head.verify_sign(&sign)?;
+        Ok(head)
+    }
+
     pub fn parse_head(&self) -> Head {
         let mut head = Head {
             version: self.version,
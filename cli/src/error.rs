@@ -0,0 +1,61 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Error reading or writing a file
+    Io(io::Error),
+    /// Malformed JSON input
+    Json(serde_json::Error),
+    /// Error from iost-chain
+    Chain(iost_chain::Error),
+    /// Error from the node or the RPC client
+    Rpc(iost_rpc::Error),
+    /// The secret key does not match the algorithm, or the algorithm is not supported
+    InvalidKey(String),
+    /// The keystore has no key for this account
+    AccountNotFound(String),
+    /// The keystore already has a key for this account
+    AccountExists(String),
+    /// The transaction cannot be signed or sent as it is
+    InvalidTx(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Json(e) => write!(f, "invalid JSON: {}", e),
+            Error::Chain(e) => write!(f, "{:?}", e),
+            Error::Rpc(e) => write!(f, "{:?}", e),
+            Error::InvalidKey(message) => write!(f, "invalid key: {}", message),
+            Error::AccountNotFound(name) => write!(f, "no key for account {}", name),
+            Error::AccountExists(name) => write!(f, "a key for account {} already exists", name),
+            Error::InvalidTx(message) => write!(f, "invalid transaction: {}", message),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Error {
+        Error::Json(e)
+    }
+}
+
+impl From<iost_chain::Error> for Error {
+    fn from(e: iost_chain::Error) -> Error {
+        Error::Chain(e)
+    }
+}
+
+impl From<iost_rpc::Error> for Error {
+    fn from(e: iost_rpc::Error) -> Error {
+        Error::Rpc(e)
+    }
+}
@@ -0,0 +1,207 @@
+use std::io::Write;
+
+use iost_chain::time_point::TimePoint;
+use iost_chain::{IostAction, Read, Signature, Tx};
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::tx;
+
+const NANOS_PER_SECOND: f64 = 1_000_000_000.0;
+
+/// Transaction in JSON, in the binary form of `Read`/`Write`, or in that form hex encoded.
+/// The input is taken as JSON when it starts with `{`, unless `binary` is set
+pub fn decode_tx(input: &[u8], binary: bool) -> Result<Tx, Error> {
+    let text = String::from_utf8_lossy(input);
+    let text = text.trim();
+    if !binary && text.starts_with('{') {
+        return Ok(serde_json::from_str(text)?);
+    }
+    let bytes = match hex::decode(text) {
+        Ok(bytes) if !text.is_empty() => bytes,
+        _ => input.to_vec(),
+    };
+    let mut pos = 0;
+    let tx = Tx::read(&bytes, &mut pos).map_err(iost_chain::Error::BytesReadError)?;
+    if pos != bytes.len() {
+        return Err(Error::InvalidTx(format!(
+            "{} trailing bytes after the transaction",
+            bytes.len() - pos
+        )));
+    }
+    Ok(tx)
+}
+
+fn time(nanos: i64) -> String {
+    TimePoint::from_unix_nano_seconds(nanos).to_string()
+}
+
+fn signature(s: &Signature) -> String {
+    let public_key = base64::decode(&s.public_key)
+        .map(|k| bs58::encode(k).into_string())
+        .unwrap_or_else(|_| format!("{} (malformed)", s.public_key));
+    format!("{} {}", s.algorithm, public_key)
+}
+
+/// Arguments of the action, one per line
+fn args(action: &IostAction) -> Vec<String> {
+    match serde_json::from_slice(&action.data) {
+        Ok(Value::Array(args)) => args.iter().map(|a| a.to_string()).collect(),
+        _ => vec![format!(
+            "{} (malformed data)",
+            String::from_utf8_lossy(&action.data)
+        )],
+    }
+}
+
+/// Print what the transaction does, check its signatures and print its hash.
+/// Fails once printed if the signatures are wrong; an unsigned transaction is only reported
+pub fn inspect<W: Write>(tx: &Tx, now: i64, out: &mut W) -> Result<(), Error> {
+    let hash = tx.hash().map(|h| bs58::encode(h).into_string());
+    writeln!(
+        out,
+        "hash:          {}",
+        hash.as_ref()
+            .map(String::as_str)
+            .unwrap_or("(malformed signatures)")
+    )?;
+    writeln!(out, "chain id:      {}", tx.chain_id)?;
+    writeln!(out, "publisher:     {}", tx.publisher)?;
+    writeln!(out, "time:          {}", time(tx.time))?;
+    let expired = if tx.expiration < now {
+        " (expired)"
+    } else {
+        ""
+    };
+    writeln!(out, "expiration:    {}{}", time(tx.expiration), expired)?;
+    writeln!(
+        out,
+        "delay:         {}s",
+        tx.delay as f64 / NANOS_PER_SECOND
+    )?;
+    writeln!(
+        out,
+        "gas:           ratio {}, limit {}",
+        tx.gas_ratio, tx.gas_limit
+    )?;
+    writeln!(out, "signers:       {}", tx.signers.join(", "))?;
+
+    writeln!(out, "actions:")?;
+    for (i, action) in tx.actions.iter().enumerate() {
+        writeln!(
+            out,
+            "  {}. {}/{}",
+            i,
+            String::from_utf8_lossy(&action.contract),
+            String::from_utf8_lossy(&action.action_name)
+        )?;
+        for arg in args(action) {
+            writeln!(out, "       {}", arg)?;
+        }
+    }
+    writeln!(out, "amount limits:")?;
+    for limit in tx.amount_limit.iter() {
+        writeln!(out, "  {}: {}", limit.token, limit.value)?;
+    }
+    writeln!(out, "signatures:")?;
+    for s in tx.signatures.iter() {
+        writeln!(out, "  signer    {}", signature(s))?;
+    }
+    for s in tx.publisher_sigs.iter() {
+        writeln!(out, "  publisher {}", signature(s))?;
+    }
+
+    if tx.publisher_sigs.is_empty() && tx.signatures.is_empty() {
+        writeln!(out, "verify:        not signed")?;
+        return Ok(());
+    }
+    match hash.map_err(Error::from).and_then(|_| tx::verify(tx)) {
+        Ok(()) => {
+            writeln!(out, "verify:        ok")?;
+            Ok(())
+        }
+        Err(e) => {
+            writeln!(out, "verify:        failed, {}", e)?;
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::keystore::test::admin;
+    use iost_chain::{SerializeData, TxBuilder};
+
+    fn signed_tx() -> Tx {
+        let action = IostAction::transfer("admin", "alice", "10", "").unwrap();
+        let mut tx = TxBuilder::new(1024)
+            .time(1_600_000_000_000_000_000)
+            .publisher("admin")
+            .action(action)
+            .build()
+            .unwrap();
+        tx::sign(&mut tx, &admin()).unwrap();
+        tx
+    }
+
+    fn run_inspect(tx: &Tx) -> (String, bool) {
+        let mut out = Vec::new();
+        let ok = inspect(tx, 1_600_000_100_000_000_000, &mut out).is_ok();
+        (String::from_utf8(out).unwrap(), ok)
+    }
+
+    #[test]
+    fn should_tx_be_decoded_from_json_and_binary() {
+        let tx = signed_tx();
+        let hash = tx.hash().unwrap();
+        let json = serde_json::to_vec(&tx).unwrap();
+        let binary = tx.to_serialize_data().unwrap();
+        let hex = hex::encode(&binary);
+
+        assert_eq!(decode_tx(&json, false).unwrap().hash().unwrap(), hash);
+        assert_eq!(decode_tx(&binary, false).unwrap().hash().unwrap(), hash);
+        assert_eq!(decode_tx(&binary, true).unwrap().hash().unwrap(), hash);
+        assert_eq!(
+            decode_tx(format!("{}\n", hex).as_bytes(), false)
+                .unwrap()
+                .hash()
+                .unwrap(),
+            hash
+        );
+
+        assert!(decode_tx(&json, true).is_err());
+        assert!(decode_tx(&binary[..binary.len() - 1], true).is_err());
+        let mut trailing = binary.clone();
+        trailing.push(0);
+        assert!(decode_tx(&trailing, true).is_err());
+    }
+
+    #[test]
+    fn should_tx_be_reported() {
+        let tx = signed_tx();
+        let (report, ok) = run_inspect(&tx);
+        assert!(ok);
+        let hash = bs58::encode(tx.hash().unwrap()).into_string();
+        assert!(report.contains(&format!("hash:          {}", hash)));
+        assert!(report.contains("time:          2020-09-13T12:26:40.000Z"));
+        assert!(report.contains("expiration:    2020-09-13T12:28:10.000Z (expired)"));
+        assert!(report.contains("  0. token.iost/transfer\n       \"iost\"\n       \"admin\""));
+        assert!(report.contains("  iost: 10\n"));
+        assert!(report.contains(&format!("  publisher SECP256K1 {}", admin().public_key)));
+        assert!(report.ends_with("verify:        ok\n"));
+
+        let mut tampered = tx.clone();
+        tampered.actions[0].data = br#"["iost","admin","alice","1000",""]"#.to_vec();
+        let (report, ok) = run_inspect(&tampered);
+        assert!(!ok);
+        assert!(report.contains("       \"1000\""));
+        assert!(report.contains("verify:        failed"));
+
+        let mut unsigned = tx.clone();
+        unsigned.publisher_sigs.clear();
+        let (report, ok) = run_inspect(&unsigned);
+        assert!(ok);
+        assert!(report.ends_with("verify:        not signed\n"));
+    }
+}
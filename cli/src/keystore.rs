@@ -0,0 +1,213 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use iost_chain::validate_account_name;
+use keys::algorithm::{self, ED25519, SECP256K1};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// Key of an account. Like the key files of iwallet, it is stored unencrypted
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    pub name: String,
+    /// "SECP256K1" or "ED25519"
+    pub algorithm: String,
+    /// Base58 encoding
+    pub public_key: String,
+    /// Base58 encoding
+    pub secret_key: String,
+}
+
+impl Account {
+    /// Account of `name` signing with the base58 `secret_key`, once the key is checked
+    pub fn new(name: &str, algorithm: &str, secret_key: &str) -> Result<Account, Error> {
+        validate_account_name(name).map_err(iost_chain::Error::from)?;
+        let sec_key = decode_secret_key(secret_key)?;
+        let public_key = public_key(algorithm, &sec_key)?;
+        Ok(Account {
+            name: name.to_string(),
+            algorithm: algorithm.to_string(),
+            public_key: bs58::encode(public_key).into_string(),
+            secret_key: secret_key.to_string(),
+        })
+    }
+
+    /// Raw secret key
+    pub fn sec_key(&self) -> Result<Vec<u8>, Error> {
+        decode_secret_key(&self.secret_key)
+    }
+}
+
+fn decode_secret_key(secret_key: &str) -> Result<Vec<u8>, Error> {
+    bs58::decode(secret_key)
+        .into_vec()
+        .map_err(|_| Error::InvalidKey("the secret key is not base58 encoded".to_string()))
+}
+
+/// Generate a secret key. Only SECP256K1 keys can be generated
+pub fn generate(algorithm_name: &str) -> Result<Vec<u8>, Error> {
+    match algorithm_name {
+        SECP256K1 => Ok(algorithm::new(SECP256K1).gen_sec_key()),
+        _ => Err(Error::InvalidKey(format!(
+            "cannot generate {} keys",
+            algorithm_name
+        ))),
+    }
+}
+
+/// Public key of a secret key: 32 bytes for SECP256K1, the 64 bytes key pair for ED25519
+pub fn public_key(algorithm_name: &str, sec_key: &[u8]) -> Result<Vec<u8>, Error> {
+    let len = match algorithm_name {
+        SECP256K1 => 32,
+        ED25519 => 64,
+        _ => {
+            return Err(Error::InvalidKey(format!(
+                "unknown algorithm {}",
+                algorithm_name
+            )))
+        }
+    };
+    if sec_key.len() != len {
+        return Err(Error::InvalidKey(format!(
+            "a {} secret key has {} bytes, not {}",
+            algorithm_name,
+            len,
+            sec_key.len()
+        )));
+    }
+    algorithm::new(algorithm_name)
+        .get_pub_key(sec_key)
+        .map_err(|_| Error::InvalidKey("the secret key is out of range".to_string()))
+}
+
+/// Directory of the account keys, one `<name>.json` file per account
+#[derive(Clone, Debug)]
+pub struct Keystore {
+    dir: PathBuf,
+}
+
+impl Keystore {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Keystore {
+        Keystore { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// `~/.iost/accounts`
+    pub fn default_dir() -> PathBuf {
+        let home = env::var_os("HOME").unwrap_or_default();
+        PathBuf::from(home).join(".iost").join("accounts")
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", name))
+    }
+
+    /// Store the key of the account, which must not be in the keystore yet
+    pub fn save(&self, account: &Account) -> Result<PathBuf, Error> {
+        let path = self.path(&account.name);
+        fs::create_dir_all(&self.dir)?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        // readable by the owner only from its creation
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path).map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => Error::AccountExists(account.name.clone()),
+            _ => Error::Io(e),
+        })?;
+        file.write_all(serde_json::to_string_pretty(account)?.as_bytes())?;
+        Ok(path)
+    }
+
+    pub fn load(&self, name: &str) -> Result<Account, Error> {
+        let json = fs::read_to_string(self.path(name)).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => Error::AccountNotFound(name.to_string()),
+            _ => Error::Io(e),
+        })?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Accounts of the keystore, sorted by name
+    pub fn list(&self) -> Result<Vec<Account>, Error> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut accounts = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |e| e == "json") {
+                accounts.push(serde_json::from_str(&fs::read_to_string(path)?)?);
+            }
+        }
+        accounts.sort_by(|a: &Account, b| a.name.cmp(&b.name));
+        Ok(accounts)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    /// Empty keystore in a directory of its own
+    pub(crate) fn keystore(name: &str) -> Keystore {
+        let dir = env::temp_dir().join(format!("iost-cli-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        Keystore::new(dir)
+    }
+
+    pub(crate) fn admin() -> Account {
+        Account::new("admin", SECP256K1, &bs58::encode([1u8; 32]).into_string()).unwrap()
+    }
+
+    #[test]
+    fn should_account_be_saved_and_loaded() {
+        let keystore = keystore("save");
+        let account = admin();
+        assert_eq!(
+            account.public_key,
+            bs58::encode(public_key(SECP256K1, &[1; 32]).unwrap()).into_string()
+        );
+        let path = keystore.save(&account).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert_eq!(keystore.load("admin").unwrap(), account);
+        assert_eq!(keystore.list().unwrap(), vec![account.clone()]);
+
+        match keystore.save(&account) {
+            Err(Error::AccountExists(name)) => assert_eq!(name, "admin"),
+            r => panic!("unexpected {:?}", r),
+        }
+        match keystore.load("unknown") {
+            Err(Error::AccountNotFound(name)) => assert_eq!(name, "unknown"),
+            r => panic!("unexpected {:?}", r),
+        }
+    }
+
+    #[test]
+    fn should_invalid_keys_be_rejected() {
+        let key = bs58::encode([1u8; 32]).into_string();
+        assert!(Account::new("a", SECP256K1, &key).is_err());
+        assert!(Account::new("admin", ED25519, &key).is_err());
+        assert!(Account::new("admin", "RSA", &key).is_err());
+        assert!(Account::new("admin", SECP256K1, "0OIl").is_err());
+        let zero = bs58::encode([0u8; 32]).into_string();
+        assert!(Account::new("admin", SECP256K1, &zero).is_err());
+
+        let generated = generate(SECP256K1).unwrap();
+        assert!(Account::new("admin", SECP256K1, &bs58::encode(generated).into_string()).is_ok());
+        assert!(generate(ED25519).is_err());
+    }
+}
@@ -0,0 +1,365 @@
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use iost_chain::time_point::TimePoint;
+use iost_chain::{IostAction, Tx};
+use iost_rpc::{Client, TxTracker, IOST};
+use serde_json::json;
+use structopt::StructOpt;
+
+use crate::error::Error;
+use crate::keystore::{Account, Keystore};
+use crate::tx::TxOpts;
+
+mod error;
+mod inspect;
+mod keystore;
+mod tx;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "iost", about = "Command-line wallet for IOST")]
+struct Opt {
+    /// URL of the HTTP API of the node
+    #[structopt(
+        long,
+        env = "IOST_URL",
+        default_value = "http://127.0.0.1:30001",
+        global = true
+    )]
+    url: String,
+    /// Directory of the account keys, ~/.iost/accounts by default
+    #[structopt(long, env = "IOST_KEYSTORE", global = true)]
+    keystore: Option<PathBuf>,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Generate a key pair
+    Keygen {
+        #[structopt(long, default_value = "SECP256K1")]
+        algorithm: String,
+    },
+    /// Manage the keys of the accounts
+    Account(AccountCommand),
+    /// Transfer tokens
+    Transfer {
+        /// Account paying, its key has to be in the keystore unless --unsigned
+        from: String,
+        to: String,
+        amount: String,
+        #[structopt(long, default_value = "iost")]
+        token: String,
+        #[structopt(long, default_value = "")]
+        memo: String,
+        #[structopt(flatten)]
+        tx: TxOpts,
+    },
+    /// Call contracts with the actions of a JSON file, "-" for the standard input
+    Call {
+        /// Publisher, its key has to be in the keystore unless --unsigned
+        account: String,
+        /// An object or an array of objects with the fields contract, action_name and data
+        actions: String,
+        #[structopt(flatten)]
+        tx: TxOpts,
+    },
+    /// Sign a transaction written by --unsigned, without connecting to the node
+    Sign {
+        account: String,
+        /// Unsigned transaction, "-" for the standard input
+        input: String,
+        #[structopt(long, short)]
+        output: String,
+    },
+    /// Send a signed transaction
+    Send {
+        /// Signed transaction, "-" for the standard input
+        input: String,
+        /// Wait until the block of the transaction is irreversible
+        #[structopt(long)]
+        wait: bool,
+    },
+    /// Decode a transaction, check its signatures and print what it does, without connecting
+    /// to the node
+    Inspect {
+        /// Transaction in JSON or in binary form, raw or hex encoded; "-" for the standard input
+        input: String,
+        /// Decode the input as binary even if it starts with "{"
+        #[structopt(long)]
+        binary: bool,
+    },
+    /// Query the balance of an account
+    Balance {
+        account: String,
+        #[structopt(long, default_value = "iost")]
+        token: String,
+    },
+    /// Query the receipt of a transaction
+    Receipt { hash: String },
+}
+
+#[derive(Debug, StructOpt)]
+enum AccountCommand {
+    /// Import the base58 secret key of an account
+    Import {
+        name: String,
+        secret_key: String,
+        #[structopt(long, default_value = "SECP256K1")]
+        algorithm: String,
+    },
+    /// List the accounts of the keystore
+    List,
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run(Opt::from_args(), &mut io::stdout()).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run<W: Write>(opt: Opt, out: &mut W) -> Result<(), Error> {
+    let keystore = Keystore::new(opt.keystore.unwrap_or_else(Keystore::default_dir));
+    let client = IOST::new(&opt.url);
+    match opt.command {
+        Command::Keygen { algorithm } => {
+            let sec_key = keystore::generate(&algorithm)?;
+            let public_key = keystore::public_key(&algorithm, &sec_key)?;
+            print_json(
+                out,
+                &json!({
+                    "algorithm": algorithm,
+                    "public_key": bs58::encode(public_key).into_string(),
+                    "secret_key": bs58::encode(sec_key).into_string(),
+                }),
+            )
+        }
+        Command::Account(AccountCommand::Import {
+            name,
+            secret_key,
+            algorithm,
+        }) => {
+            let account = Account::new(&name, &algorithm, &secret_key)?;
+            let path = keystore.save(&account)?;
+            writeln!(
+                out,
+                "{} {} saved to {}",
+                name,
+                account.public_key,
+                path.display()
+            )?;
+            Ok(())
+        }
+        Command::Account(AccountCommand::List) => {
+            for account in keystore.list()? {
+                writeln!(
+                    out,
+                    "{} {} {}",
+                    account.name, account.algorithm, account.public_key
+                )?;
+            }
+            Ok(())
+        }
+        Command::Transfer {
+            from,
+            to,
+            amount,
+            token,
+            memo,
+            tx,
+        } => {
+            let data = serde_json::to_string(&[&token, &from, &to, &amount, &memo])?;
+            let action = IostAction::new("token.iost".to_string(), "transfer".to_string(), data);
+            submit(&client, &keystore, &from, vec![action], &tx, out).await
+        }
+        Command::Call {
+            account,
+            actions,
+            tx,
+        } => {
+            let actions = tx::parse_actions(&tx::read_input(&actions)?)?;
+            submit(&client, &keystore, &account, actions, &tx, out).await
+        }
+        Command::Sign {
+            account,
+            input,
+            output,
+        } => {
+            let mut tx = tx::read_tx(&input)?;
+            tx::sign(&mut tx, &keystore.load(&account)?)?;
+            tx::write_tx(&output, &tx)?;
+            writeln!(out, "{}", output)?;
+            Ok(())
+        }
+        Command::Send { input, wait } => {
+            let tx = tx::read_tx(&input)?;
+            tx::verify(&tx)?;
+            send(&client, &tx, wait, out).await
+        }
+        Command::Inspect { input, binary } => {
+            let tx = inspect::decode_tx(&tx::read_bytes(&input)?, binary)?;
+            inspect::inspect(&tx, TimePoint::now().time_since_epoch(), out)
+        }
+        Command::Balance { account, token } => {
+            let balance = client.get_token_balance(&account, &token, true).await?;
+            print_json(out, &balance)
+        }
+        Command::Receipt { hash } => {
+            let receipt = client.get_tx_receipt_by_tx_hash(&hash).await?;
+            print_json(out, &receipt)
+        }
+    }
+}
+
+/// Build the transaction of `publisher`, then write it to the output file or sign and send it
+async fn submit<W: Write>(
+    client: &IOST,
+    keystore: &Keystore,
+    publisher: &str,
+    actions: Vec<IostAction>,
+    opts: &TxOpts,
+    out: &mut W,
+) -> Result<(), Error> {
+    let mut tx = opts.build(publisher, actions)?;
+    if !opts.unsigned {
+        tx::sign(&mut tx, &keystore.load(publisher)?)?;
+    }
+    match opts.output {
+        Some(ref output) => {
+            tx::write_tx(output, &tx)?;
+            writeln!(out, "{}", output)?;
+            Ok(())
+        }
+        None => send(client, &tx, opts.wait, out).await,
+    }
+}
+
+async fn send<W: Write>(client: &IOST, tx: &Tx, wait: bool, out: &mut W) -> Result<(), Error> {
+    let tracker = TxTracker::new(client);
+    if wait {
+        let confirmation = tracker.send_and_confirm(tx).await?;
+        print_json(
+            out,
+            &json!({
+                "hash": confirmation.hash,
+                "status": format!("{:?}", confirmation.status),
+                "block_number": confirmation.block_number,
+                "receipt": confirmation.receipt,
+            }),
+        )
+    } else {
+        writeln!(out, "{}", tracker.send(tx).await?)?;
+        Ok(())
+    }
+}
+
+fn print_json<W: Write, T: serde::Serialize>(out: &mut W, value: &T) -> Result<(), Error> {
+    writeln!(out, "{}", serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::keystore::test::keystore;
+    use iost_mock_node::{Fixtures, MockNode};
+
+    async fn iost(node: &MockNode, keystore: &Keystore, args: &[&str]) -> Result<String, Error> {
+        let url = node.url();
+        let dir = keystore.dir().to_str().unwrap();
+        let mut argv = vec!["iost", "--url", url.as_str(), "--keystore", dir];
+        argv.extend_from_slice(args);
+        let mut out = Vec::new();
+        run(Opt::from_iter(argv), &mut out).await?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    async fn import_admin(node: &MockNode, keystore: &Keystore) {
+        let secret_key = bs58::encode([1u8; 32]).into_string();
+        iost(node, keystore, &["account", "import", "admin", &secret_key])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_transfer_be_confirmed() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let keystore = keystore("transfer");
+        import_admin(&node, &keystore).await;
+        let listed = iost(&node, &keystore, &["account", "list"]).await.unwrap();
+        assert!(listed.starts_with("admin SECP256K1 "));
+
+        let out = iost(
+            &node,
+            &keystore,
+            &["transfer", "admin", "alice", "10", "--wait"],
+        )
+        .await
+        .unwrap();
+        let confirmation: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(confirmation["status"], "IRREVERSIBLE");
+        assert_eq!(confirmation["block_number"], 3);
+        let sent = node.sent_txs();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(confirmation["hash"], sent[0].0.as_str());
+        assert_eq!(sent[0].1.publisher, "admin");
+
+        // the key of the publisher is required
+        assert!(
+            iost(&node, &keystore, &["transfer", "alice", "admin", "10"])
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_tx_be_signed_offline_then_sent() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let keystore = keystore("offline");
+        import_admin(&node, &keystore).await;
+        let dir = std::env::temp_dir();
+        let unsigned = dir.join(format!("iost-cli-unsigned-{}.json", std::process::id()));
+        let signed = dir.join(format!("iost-cli-signed-{}.json", std::process::id()));
+        let (unsigned, signed) = (unsigned.to_str().unwrap(), signed.to_str().unwrap());
+
+        let args = [
+            "transfer",
+            "admin",
+            "alice",
+            "10",
+            "--unsigned",
+            "-o",
+            unsigned,
+        ];
+        iost(&node, &keystore, &args).await.unwrap();
+        // an unsigned transaction is not sent
+        assert!(iost(&node, &keystore, &["send", unsigned]).await.is_err());
+
+        let args = ["sign", "admin", unsigned, "--output", signed];
+        iost(&node, &keystore, &args).await.unwrap();
+        let hash = iost(&node, &keystore, &["send", signed]).await.unwrap();
+        assert_eq!(hash.trim(), node.sent_txs()[0].0);
+        let receipt = iost(&node, &keystore, &["receipt", hash.trim()])
+            .await
+            .unwrap();
+        assert!(receipt.contains("SUCCESS"));
+
+        let _ = std::fs::remove_file(unsigned);
+        let _ = std::fs::remove_file(signed);
+    }
+
+    #[tokio::test]
+    async fn should_balance_be_queried() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let keystore = keystore("balance");
+        let out = iost(&node, &keystore, &["balance", "admin"]).await.unwrap();
+        let balance: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(balance["balance"], 1000000.0);
+        assert!(iost(&node, &keystore, &["balance", "nobody"])
+            .await
+            .is_err());
+    }
+}
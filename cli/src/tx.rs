@@ -0,0 +1,239 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use iost_chain::{validate_contract_id, IostAction, Tx, TxBuilder};
+use serde_json::Value;
+use structopt::StructOpt;
+
+use crate::error::Error;
+use crate::keystore::Account;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+/// Options of the commands building a transaction
+#[derive(Debug, StructOpt)]
+pub struct TxOpts {
+    /// Chain id, 1024 for the mainnet
+    #[structopt(long, default_value = "1024")]
+    pub chain_id: u32,
+    #[structopt(long, default_value = "1.0")]
+    pub gas_ratio: f64,
+    #[structopt(long, default_value = "1000000")]
+    pub gas_limit: f64,
+    /// Seconds before the transaction expires
+    #[structopt(long, default_value = "90")]
+    pub expiration: i64,
+    /// Seconds before the transaction is executed
+    #[structopt(long, default_value = "0")]
+    pub delay: i64,
+    /// Amount limit as TOKEN:VALUE, such as iost:10; transfers of the publisher get one already
+    #[structopt(long = "amount-limit", parse(try_from_str = parse_amount_limit))]
+    pub amount_limits: Vec<(String, String)>,
+    /// Allow "unlimited" amount limits and add "*:unlimited"
+    #[structopt(long)]
+    pub allow_unlimited: bool,
+    /// Write the transaction to this file instead of sending it
+    #[structopt(long, short)]
+    pub output: Option<String>,
+    /// Do not sign the transaction, to sign it offline with `iost sign`
+    #[structopt(long, requires = "output")]
+    pub unsigned: bool,
+    /// Wait until the block of the transaction is irreversible
+    #[structopt(long)]
+    pub wait: bool,
+}
+
+fn parse_amount_limit(s: &str) -> Result<(String, String), String> {
+    match s.find(':') {
+        Some(i) => Ok((s[..i].to_string(), s[i + 1..].to_string())),
+        None => Err(format!("{} is not TOKEN:VALUE", s)),
+    }
+}
+
+impl TxOpts {
+    /// Unsigned transaction of `publisher`, starting now
+    pub fn build(&self, publisher: &str, actions: Vec<IostAction>) -> Result<Tx, Error> {
+        let mut builder = TxBuilder::new(self.chain_id)
+            .time_now()
+            .expiration_window(self.expiration * NANOS_PER_SECOND)
+            .delay(self.delay * NANOS_PER_SECOND)
+            .gas_ratio(self.gas_ratio)
+            .gas_limit(self.gas_limit)
+            .publisher(publisher)
+            .actions(actions);
+        for (token, value) in self.amount_limits.iter() {
+            builder = builder.amount_limit(token.as_str(), value.as_str());
+        }
+        if self.allow_unlimited {
+            builder = builder.allow_unlimited();
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// Actions of a JSON object or array of objects with the fields of `IostAction`.
+/// `data` is the JSON array of the arguments, either as a string or as is
+pub fn parse_actions(json: &str) -> Result<Vec<IostAction>, Error> {
+    let values = match serde_json::from_str(json)? {
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+    values
+        .into_iter()
+        .map(|mut value| {
+            if let Some(data) = value.get_mut("data") {
+                if !data.is_string() {
+                    *data = Value::String(data.to_string());
+                }
+            }
+            let action: IostAction = serde_json::from_value(value)?;
+            validate_contract_id(&String::from_utf8_lossy(&action.contract))
+                .map_err(iost_chain::Error::from)?;
+            action.args()?;
+            Ok(action)
+        })
+        .collect()
+}
+
+/// Sign the transaction as its publisher
+pub fn sign(tx: &mut Tx, account: &Account) -> Result<(), Error> {
+    if !tx.publisher_sigs.is_empty() {
+        return Err(Error::InvalidTx("it is signed already".to_string()));
+    }
+    if !tx.publisher.is_empty() && tx.publisher != account.name {
+        return Err(Error::InvalidTx(format!(
+            "it is published by {}, not {}",
+            tx.publisher, account.name
+        )));
+    }
+    tx.sign(
+        account.name.clone(),
+        &account.algorithm,
+        &account.sec_key()?,
+    )?;
+    Ok(())
+}
+
+/// Check the signatures of the transaction, as the node does
+pub fn verify(tx: &Tx) -> Result<(), Error> {
+    if tx.publisher_sigs.is_empty() {
+        return Err(Error::InvalidTx("it is not signed".to_string()));
+    }
+    Ok(tx.verify()?)
+}
+
+/// Read a file, or the standard input for "-"
+pub fn read_input(path: &str) -> Result<String, Error> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        Ok(fs::read_to_string(path)?)
+    }
+}
+
+/// Bytes of a file, or of the standard input for "-"
+pub fn read_bytes(path: &str) -> Result<Vec<u8>, Error> {
+    if path == "-" {
+        let mut input = Vec::new();
+        io::stdin().read_to_end(&mut input)?;
+        Ok(input)
+    } else {
+        Ok(fs::read(path)?)
+    }
+}
+
+/// Transaction in the JSON format of `sendTx`
+pub fn read_tx(path: &str) -> Result<Tx, Error> {
+    Ok(serde_json::from_str(&read_input(path)?)?)
+}
+
+pub fn write_tx<P: AsRef<Path>>(path: P, tx: &Tx) -> Result<(), Error> {
+    Ok(fs::write(path, serde_json::to_string_pretty(tx)?)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::keystore::test::admin;
+
+    fn opts() -> TxOpts {
+        TxOpts::from_iter(&["tx", "--amount-limit", "abct:2"])
+    }
+
+    #[test]
+    fn should_actions_be_parsed() {
+        let actions = parse_actions(
+            r#"{"contract": "token.iost", "action_name": "transfer",
+                "data": "[\"iost\",\"admin\",\"alice\",\"10\",\"\"]"}"#,
+        )
+        .unwrap();
+        let data = r#"["iost","admin","alice","10",""]"#;
+        assert_eq!(
+            actions,
+            vec![IostAction::new(
+                "token.iost".to_string(),
+                "transfer".to_string(),
+                data.to_string()
+            )]
+        );
+
+        let actions = parse_actions(
+            r#"[{"contract": "vote_producer.iost", "action_name": "vote",
+                 "data": ["admin", "producer", "100"]},
+                {"contract": "gas.iost", "action_name": "pledge",
+                 "data": "[\"admin\",\"admin\",\"10\"]"}]"#,
+        )
+        .unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0].data, br#"["admin","producer","100"]"#.to_vec());
+
+        assert!(
+            parse_actions(r#"{"contract": "token", "action_name": "a", "data": "[]"}"#).is_err()
+        );
+        assert!(
+            parse_actions(r#"{"contract": "token.iost", "action_name": "a", "data": "{"}"#)
+                .is_err()
+        );
+        assert!(parse_actions("[").is_err());
+    }
+
+    #[test]
+    fn should_tx_be_built_and_signed() {
+        let action = IostAction::transfer("admin", "alice", "10", "").unwrap();
+        let mut tx = opts().build("admin", vec![action]).unwrap();
+        assert_eq!(tx.publisher, "admin");
+        assert_eq!(tx.expiration - tx.time, 90 * NANOS_PER_SECOND);
+        assert_eq!(tx.amount_limit.len(), 2);
+        assert!(verify(&tx).is_err());
+
+        sign(&mut tx, &admin()).unwrap();
+        assert!(verify(&tx).is_ok());
+        assert!(sign(&mut tx, &admin()).is_err());
+
+        assert!(opts().build("admin", vec![]).is_err());
+        let mut tampered = tx.clone();
+        tampered.gas_limit += 1.0;
+        assert!(verify(&tampered).is_err());
+    }
+
+    #[test]
+    fn should_unsigned_tx_be_signed_offline() {
+        let path = std::env::temp_dir().join(format!("iost-cli-tx-{}.json", std::process::id()));
+        let action = IostAction::transfer("admin", "alice", "10", "").unwrap();
+        let tx = opts().build("admin", vec![action]).unwrap();
+        write_tx(&path, &tx).unwrap();
+
+        let mut read = read_tx(path.to_str().unwrap()).unwrap();
+        assert_eq!(read.hash().unwrap(), tx.hash().unwrap());
+        sign(&mut read, &admin()).unwrap();
+        assert!(verify(&read).is_ok());
+
+        let mut alice = admin();
+        alice.name = "alice".to_string();
+        assert!(sign(&mut tx.clone(), &alice).is_err());
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,398 @@
+//! Expand `contract_abi!`.
+use crate::proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use serde_json::Value;
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Attribute, Error, Ident, LitStr, Path, Token, Visibility};
+
+/// `#[iost_root_path = "..."] pub struct Name = "path/to/contract.abi";`
+struct Input {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    path: LitStr,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let path = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![;]>()?;
+        }
+        Ok(Input {
+            attrs,
+            vis,
+            name,
+            path,
+        })
+    }
+}
+
+/// An entry of the ABI
+#[derive(Debug, PartialEq)]
+struct Abi {
+    name: String,
+    args: Vec<String>,
+    amount_limit: Vec<(String, String)>,
+}
+
+fn string(value: &Value, what: &str) -> Result<String, String> {
+    value
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| format!("{} must be a string", what))
+}
+
+/// The entries of an ABI file as it is deployed, `{"lang": .., "abi": [..]}` with `amountLimit`
+/// of `{token, val}`, or as the node returns them, with `amount_limit` of `{token, value}`
+fn parse_abis(json: &str) -> Result<Vec<Abi>, String> {
+    let json: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let entries = match json.get("abi").unwrap_or(&json) {
+        Value::Array(entries) => entries,
+        _ => return Err("expected an array of ABIs or an object with an \"abi\" array".into()),
+    };
+    entries
+        .iter()
+        .map(|entry| {
+            let name = string(&entry["name"], "the name of an ABI")?;
+            let args = match &entry["args"] {
+                Value::Array(args) => args
+                    .iter()
+                    .map(|arg| string(arg, &format!("an argument of {}", name)))
+                    .collect::<Result<_, _>>()?,
+                Value::Null => vec![],
+                _ => return Err(format!("the arguments of {} must be an array", name)),
+            };
+            let limits = match entry
+                .get("amountLimit")
+                .or_else(|| entry.get("amount_limit"))
+            {
+                Some(Value::Array(limits)) => limits.as_slice(),
+                Some(Value::Null) | None => &[],
+                Some(_) => return Err(format!("the amount limit of {} must be an array", name)),
+            };
+            let amount_limit = limits
+                .iter()
+                .map(|limit| {
+                    let what = format!("an amount limit of {}", name);
+                    let value = limit.get("val").unwrap_or(&limit["value"]);
+                    Ok((string(&limit["token"], &what)?, string(value, &what)?))
+                })
+                .collect::<Result<_, String>>()?;
+            Ok(Abi {
+                name,
+                args,
+                amount_limit,
+            })
+        })
+        .collect()
+}
+
+/// "transferFreeze" to "transfer_freeze" and "getURL" to "get_url"
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = matches!(chars.get(i + 1), Some(n) if n.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower) {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+/// Methods of the binding which are not calls
+const RESERVED: &[&str] = &["self", "super", "crate", "new", "contract", "abis"];
+
+fn method_ident(name: &str) -> Result<Ident, String> {
+    let snake = snake_case(name);
+    let valid = matches!(snake.chars().next(), Some(c) if c.is_alphabetic() || c == '_')
+        && snake.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !valid || RESERVED.contains(&snake.as_str()) {
+        return Err(format!("the ABI {} can not be a method name", name));
+    }
+    let ident = if KEYWORDS.contains(&snake.as_str()) {
+        format!("r#{}", snake)
+    } else {
+        snake
+    };
+    syn::parse_str(&ident).map_err(|e| format!("the ABI {} can not be a method name: {}", name, e))
+}
+
+/// The method names of the ABIs, which must be distinct, as `getURL` and `get_url` are not
+fn method_idents(abis: &[Abi]) -> Result<Vec<Ident>, String> {
+    let mut idents: Vec<Ident> = Vec::with_capacity(abis.len());
+    for abi in abis {
+        let ident = method_ident(&abi.name)?;
+        if let Some(i) = idents.iter().position(|other| *other == ident) {
+            return Err(format!(
+                "the ABIs {} and {} are both the method {}",
+                abis[i].name, abi.name, ident
+            ));
+        }
+        idents.push(ident);
+    }
+    Ok(idents)
+}
+
+/// The parameter and its conversion to a JSON value, for an argument of the ABI. Numbers are
+/// those of javascript, a non finite one being rejected by the call
+fn arg(root: &Path, abi: &Abi, index: usize) -> Result<(TokenStream2, TokenStream2), String> {
+    let ident = Ident::new(&format!("arg{}", index), Span::call_site());
+    match abi.args[index].as_str() {
+        "string" => Ok((
+            quote!(#ident: &str),
+            quote!(#root::serde_json::Value::from(#ident)),
+        )),
+        "number" => Ok((
+            quote!(#ident: f64),
+            quote!(#root::serde_json::Value::from(#ident)),
+        )),
+        "bool" => Ok((
+            quote!(#ident: bool),
+            quote!(#root::serde_json::Value::from(#ident)),
+        )),
+        "json" => Ok((
+            quote!(#ident: &#root::serde_json::Value),
+            quote!(#ident.clone()),
+        )),
+        arg_type => Err(format!(
+            "argument {} of {} has the unknown type {}",
+            index, abi.name, arg_type
+        )),
+    }
+}
+
+fn abi_value(root: &Path, abi: &Abi) -> TokenStream2 {
+    let name = &abi.name;
+    let args = &abi.args;
+    let tokens = abi.amount_limit.iter().map(|(token, _)| token);
+    let values = abi.amount_limit.iter().map(|(_, value)| value);
+    quote! {
+        #root::ABI {
+            name: #name.to_string(),
+            args: vec![#(#args.to_string()),*],
+            amount_limit: vec![#(#root::AmountLimit::new(#tokens.to_string(), #values.to_string())),*],
+        }
+    }
+}
+
+fn method(root: &Path, abi: &Abi, ident: &Ident) -> Result<TokenStream2, String> {
+    let (params, values): (Vec<_>, Vec<_>) = (0..abi.args.len())
+        .map(|index| arg(root, abi, index))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    let abi_value = abi_value(root, abi);
+    let doc = format!(
+        "Call of `{}({})`, with its declared amount limits",
+        abi.name,
+        abi.args.join(", ")
+    );
+    Ok(quote! {
+        #[doc = #doc]
+        #[allow(clippy::too_many_arguments)]
+        pub fn #ident(&self, #(#params),*) -> #root::Result<#root::ContractCall> {
+            #abi_value.call(&self.contract, &[#(#values),*])
+        }
+    })
+}
+
+fn binding(input: Input) -> Result<TokenStream2, Error> {
+    let root = crate::attrs_root_path(&input.attrs, crate::DEFAULT_CHAIN_ROOT_PATH);
+    let error = |message: String| Error::new(input.path.span(), message);
+    let dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|e| error(e.to_string()))?;
+    let path = std::path::Path::new(&dir).join(input.path.value());
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| error(format!("can not read {}: {}", path.display(), e)))?;
+    let abis = parse_abis(&json).map_err(|e| error(format!("{}: {}", path.display(), e)))?;
+    let idents = method_idents(&abis).map_err(error)?;
+    let methods = abis
+        .iter()
+        .zip(idents.iter())
+        .map(|(abi, ident)| method(&root, abi, ident))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(error)?;
+    let abi_values = abis.iter().map(|abi| abi_value(&root, abi));
+
+    let attrs = input
+        .attrs
+        .iter()
+        .filter(|attr| !attr.path.is_ident("iost_root_path"));
+    let vis = &input.vis;
+    let name = &input.name;
+    let path = path.to_string_lossy().into_owned();
+    Ok(quote! {
+        // rebuilds the binding when the ABI changes
+        const _: &[u8] = include_bytes!(#path);
+
+        #(#attrs)*
+        #[derive(Clone, Debug)]
+        #vis struct #name {
+            contract: String,
+        }
+
+        impl #name {
+            /// Binding of the deployed contract `contract`
+            pub fn new<T: AsRef<str>>(contract: T) -> Self {
+                #name {
+                    contract: contract.as_ref().to_string(),
+                }
+            }
+
+            pub fn contract(&self) -> &str {
+                &self.contract
+            }
+
+            /// The ABIs the binding is generated from
+            pub fn abis() -> Vec<#root::ABI> {
+                vec![#(#abi_values),*]
+            }
+
+            #(#methods)*
+        }
+    })
+}
+
+/// Expand input
+pub(crate) fn expand(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+    match binding(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn abi(name: &str, args: &[&str]) -> Abi {
+        Abi {
+            name: name.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            amount_limit: vec![],
+        }
+    }
+
+    #[test]
+    fn names_should_be_snake_cased() {
+        assert_eq!(snake_case("transfer"), "transfer");
+        assert_eq!(snake_case("transferFreeze"), "transfer_freeze");
+        assert_eq!(snake_case("getURL"), "get_url");
+        assert_eq!(snake_case("URLOf"), "url_of");
+        assert_eq!(snake_case("setV2Config"), "set_v2_config");
+        assert_eq!(snake_case("get_url"), "get_url");
+        assert_eq!(snake_case("Init"), "init");
+    }
+
+    #[test]
+    fn method_names_should_be_checked() {
+        assert_eq!(method_ident("transferFreeze").unwrap(), "transfer_freeze");
+        assert_eq!(method_ident("type").unwrap().to_string(), "r#type");
+        assert_eq!(method_ident("Match").unwrap().to_string(), "r#match");
+        for name in &[
+            "new",
+            "abis",
+            "contract",
+            "self",
+            "Self",
+            "super",
+            "2fa",
+            "set-config",
+            "",
+        ] {
+            assert!(method_ident(name).is_err(), "{}", name);
+        }
+
+        let idents = method_idents(&[abi("getURL", &[]), abi("setURL", &[])]).unwrap();
+        assert_eq!(idents[1], "set_url");
+        let collision = method_idents(&[abi("getURL", &[]), abi("get_url", &[])]).unwrap_err();
+        assert_eq!(
+            collision,
+            "the ABIs getURL and get_url are both the method get_url"
+        );
+        assert!(method_idents(&[abi("init", &[]), abi("init", &[])]).is_err());
+    }
+
+    #[test]
+    fn args_should_be_typed() {
+        let root: Path = syn::parse_str("crate").unwrap();
+        let set_config = abi("setConfig", &["number", "bool", "int"]);
+        let (param, _) = arg(&root, &set_config, 0).unwrap();
+        assert_eq!(param.to_string(), quote!(arg0: f64).to_string());
+        let (param, _) = arg(&root, &set_config, 1).unwrap();
+        assert_eq!(param.to_string(), quote!(arg1: bool).to_string());
+        assert_eq!(
+            arg(&root, &set_config, 2).unwrap_err(),
+            "argument 2 of setConfig has the unknown type int"
+        );
+    }
+
+    #[test]
+    fn abis_should_be_parsed() {
+        let deployed = r#"{"lang": "javascript", "abi": [
+            {"name": "deposit", "args": ["string"], "amountLimit": [{"token": "iost", "val": "100"}]},
+            {"name": "init"}
+        ]}"#;
+        let returned = r#"[
+            {"name": "deposit", "args": ["string"], "amount_limit": [{"token": "iost", "value": "100"}]},
+            {"name": "init", "args": [], "amount_limit": null}
+        ]"#;
+        let abis = parse_abis(deployed).unwrap();
+        assert_eq!(abis, parse_abis(returned).unwrap());
+        assert_eq!(
+            abis[0].amount_limit,
+            vec![("iost".to_string(), "100".to_string())]
+        );
+        assert!(abis[1].args.is_empty());
+    }
+
+    #[test]
+    fn invalid_abis_should_be_reported() {
+        let error = |json: &str| parse_abis(json).unwrap_err();
+        assert!(parse_abis("{").is_err());
+        assert_eq!(
+            error(r#"{"abi": 1}"#),
+            "expected an array of ABIs or an object with an \"abi\" array"
+        );
+        assert_eq!(
+            error(r#"[{"args": []}]"#),
+            "the name of an ABI must be a string"
+        );
+        assert_eq!(
+            error(r#"[{"name": "f", "args": [1]}]"#),
+            "an argument of f must be a string"
+        );
+        assert_eq!(
+            error(r#"[{"name": "f", "args": "string"}]"#),
+            "the arguments of f must be an array"
+        );
+        assert_eq!(
+            error(r#"[{"name": "f", "amountLimit": {}}]"#),
+            "the amount limit of f must be an array"
+        );
+        assert_eq!(
+            error(r#"[{"name": "f", "amountLimit": [{"token": "iost"}]}]"#),
+            "an amount limit of f must be a string"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::unimplemented)]
 extern crate proc_macro;
 
+mod contract_abi;
 mod derive_num_bytes;
 mod derive_read;
 // mod derive_table;
@@ -10,7 +11,7 @@ mod derive_serialize_data;
 
 use crate::proc_macro::TokenStream;
 use proc_macro2::Span;
-use syn::{DeriveInput, Lit, LitStr, Meta, Path};
+use syn::{Attribute, DeriveInput, Lit, LitStr, Meta, Path};
 
 /// Derive the `Digest` trait
 // #[inline]
@@ -47,6 +48,27 @@ pub fn derive_num_bytes(input: TokenStream) -> TokenStream {
     crate::derive_num_bytes::expand(input)
 }
 
+/// Generate a binding of a contract from its ABI file, a struct with one method per ABI
+/// returning the call of the contract with the amount limits the ABI declares.
+///
+/// The path of the ABI file is relative to the manifest of the crate:
+///
+/// ```ignore
+/// iost_chain::contract_abi! {
+///     pub struct Exchange = "abi/exchange.abi";
+/// }
+///
+/// let exchange = Exchange::new("Contract6uHwbaqtHjnf3fpBGd5nsxHKbHGQR2JBzNUxH9XkBCYm");
+/// let tx = TxBuilder::new(MAINNET_CHAIN_ID)
+///     .call(exchange.deposit("admin", "100")?)
+///     .publisher("admin");
+/// ```
+#[inline]
+#[proc_macro]
+pub fn contract_abi(input: TokenStream) -> TokenStream {
+    crate::contract_abi::expand(input)
+}
+
 /// TODO docs
 // #[inline]
 // #[proc_macro_derive(
@@ -65,10 +87,17 @@ const DEFAULT_ROOT_PATH: &str = "::eosio";
 #[cfg(not(feature = "internal-use-only-root-path-is-eosio"))]
 const DEFAULT_ROOT_PATH: &str = "::eosio_core";
 
+/// The default root path of the contract bindings, the `iost_chain` crate.
+const DEFAULT_CHAIN_ROOT_PATH: &str = "::iost_chain";
+
 /// Get the root path for types/traits.
 pub(crate) fn root_path(input: &DeriveInput) -> Path {
-    let litstr = input
-        .attrs
+    attrs_root_path(&input.attrs, DEFAULT_ROOT_PATH)
+}
+
+/// Get the root path from `#[iost_root_path = "..."]` among `attrs`, or else `default`.
+pub(crate) fn attrs_root_path(attrs: &[Attribute], default: &str) -> Path {
+    let litstr = attrs
         .iter()
         .fold(None, |acc, attr| match attr.parse_meta() {
             Ok(meta) => {
@@ -92,7 +121,7 @@ pub(crate) fn root_path(input: &DeriveInput) -> Path {
             }
             Err(_) => acc,
         })
-        .unwrap_or_else(|| LitStr::new(DEFAULT_ROOT_PATH, Span::call_site()));
+        .unwrap_or_else(|| LitStr::new(default, Span::call_site()));
     litstr
         .parse_with(Path::parse_mod_style)
         .expect("bad path for iost_root_path")
@@ -42,9 +42,13 @@ impl Algorithm for AlgorithmEd25519 {
     }
 
     fn verify(&self, message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
-        let public_key = ed25519_dalek::PublicKey::from_bytes(pub_key).unwrap();
-        let sig = Signature::try_from(signature).unwrap();
-        public_key.verify(message, &sig).is_ok()
+        match (
+            ed25519_dalek::PublicKey::from_bytes(pub_key),
+            Signature::try_from(signature),
+        ) {
+            (Ok(public_key), Ok(sig)) => public_key.verify(message, &sig).is_ok(),
+            _ => false,
+        }
     }
 
     #[cfg(feature = "std")]
@@ -56,7 +60,8 @@ impl Algorithm for AlgorithmEd25519 {
     }
 
     fn get_pub_key(&self, sec_key: &[u8]) -> crate::Result<Vec<u8>> {
-        let key_pair = ed25519_dalek::Keypair::from_bytes(sec_key).unwrap();
+        let key_pair =
+            ed25519_dalek::Keypair::from_bytes(sec_key).map_err(|_| crate::Error::ErrorEd25519)?;
         Ok(Vec::from(key_pair.public.as_ref()))
     }
 
@@ -75,10 +80,14 @@ impl Algorithm for AlgorithmSecp256k1 {
     }
 
     fn verify(&self, message: &[u8], pub_key: &[u8], signature: &[u8]) -> bool {
-        let msg = secp256k1::Message::parse_slice(message).unwrap();
-        let sig = secp256k1::Signature::parse_slice(signature).unwrap();
-        let public_key = secp256k1::PublicKey::parse_slice(pub_key, None).unwrap();
-        secp256k1::verify(&msg, &sig, &public_key)
+        match (
+            secp256k1::Message::parse_slice(message),
+            secp256k1::Signature::parse_slice(signature),
+            secp256k1::PublicKey::parse_slice(pub_key, None),
+        ) {
+            (Ok(msg), Ok(sig), Ok(public_key)) => secp256k1::verify(&msg, &sig, &public_key),
+            _ => false,
+        }
     }
 
     #[cfg(feature = "std")]
@@ -89,7 +98,7 @@ impl Algorithm for AlgorithmSecp256k1 {
     }
 
     fn get_pub_key(&self, sec_key: &[u8]) -> Result<Vec<u8>> {
-        let secret_key = secp256k1::SecretKey::parse_slice(sec_key).unwrap();
+        let secret_key = secp256k1::SecretKey::parse_slice(sec_key)?;
         let public_key = secp256k1::PublicKey::from_secret_key(&secret_key);
         Ok(public_key.serialize_compressed().to_vec())
     }
@@ -4,6 +4,7 @@ extern crate alloc;
 
 pub mod algorithm;
 pub mod error;
+pub mod public;
 pub mod secret;
 pub mod signature;
 
@@ -0,0 +1,103 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::algorithm::{ED25519, SECP256K1};
+use crate::base58;
+use crate::error::{Error, Result};
+
+/// Public key of an account, as `auth.iost` stores it: 32 bytes for ED25519 and
+/// 33 compressed bytes for SECP256K1, encoded in base58
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey {
+    algorithm: &'static str,
+    bytes: Vec<u8>,
+}
+
+impl PublicKey {
+    /// Key of its raw bytes, the algorithm being told by their length
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let algorithm = match bytes.len() {
+            32 => {
+                ed25519_dalek::PublicKey::from_bytes(bytes).map_err(|_| Error::ErrorEd25519)?;
+                ED25519
+            }
+            33 => {
+                secp256k1::PublicKey::parse_slice(bytes, None)?;
+                SECP256K1
+            }
+            len => return Err(base58::Error::InvalidLength(len).into()),
+        };
+        Ok(PublicKey {
+            algorithm,
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    /// "ED25519" or "SECP256K1"
+    pub fn algorithm(&self) -> &'static str {
+        self.algorithm
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn to_base58(&self) -> String {
+        base58::encode_slice(&self.bytes)
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        PublicKey::from_bytes(&base58::from(s)?)
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_base58())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm;
+
+    #[test]
+    fn public_key_should_be_parsed() {
+        let key: PublicKey = "Gcv8c2tH8qZrUYnKdEEdTtASsxivic2834MQW6mgxqto"
+            .parse()
+            .unwrap();
+        assert_eq!(key.algorithm(), ED25519);
+        assert_eq!(key.as_bytes().len(), 32);
+        assert_eq!(
+            key.to_string(),
+            "Gcv8c2tH8qZrUYnKdEEdTtASsxivic2834MQW6mgxqto"
+        );
+
+        let key: PublicKey = "iWgLQj3VTPN4dZnomuJMMCggv22LFw4nAkA6bmrVsmCo"
+            .parse()
+            .unwrap();
+        assert_eq!(key.algorithm(), SECP256K1);
+        let sk = bs58::decode("3BZ3HWs2nWucCCvLp7FRFv1K7RR3fAjjEQccf9EJrTv4")
+            .into_vec()
+            .unwrap();
+        let pub_key = algorithm::new(SECP256K1).get_pub_key(&sk).unwrap();
+        assert_eq!(PublicKey::from_bytes(&pub_key).unwrap(), key);
+    }
+
+    #[test]
+    fn malformed_public_key_should_be_rejected() {
+        assert!("0OIl".parse::<PublicKey>().is_err());
+        assert_eq!(
+            PublicKey::from_bytes(&[1; 20]),
+            Err(Error::Base58(base58::Error::InvalidLength(20)))
+        );
+        assert!(PublicKey::from_bytes(&[1; 33]).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+//! Run a mock IOST node for the tests which need one.
+//!
+//! `iost-mock-node [ADDRESS] [FIXTURES]` listens on `127.0.0.1:30001` by default and serves the
+//! fixtures shipped with the crate unless a fixture file is given.
+use std::env;
+use std::net::SocketAddr;
+
+use iost_mock_node::{Fixtures, MockNode};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:30001";
+
+#[tokio::main]
+async fn main() {
+    let mut args = env::args().skip(1);
+    let addr: SocketAddr = args
+        .next()
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string())
+        .parse()
+        .expect("invalid listening address");
+    let fixtures = match args.next() {
+        Some(path) => Fixtures::load(&path).expect("cannot read the fixtures"),
+        None => Fixtures::builtin(),
+    };
+    let node = MockNode::bind(addr, fixtures).expect("cannot start the mock node");
+    println!("mock IOST node listening on {}", node.url());
+    tokio::signal::ctrl_c().await.ok();
+}
@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Answers of the mock node, in the JSON format of the IOST HTTP API
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Fixtures {
+    /// `getNodeInfo`
+    #[serde(default)]
+    pub node_info: Value,
+    /// `getChainInfo`, its `chain_id` is also required from the submitted transactions
+    #[serde(default)]
+    pub chain_info: Value,
+    /// `getGasRatio`
+    #[serde(default)]
+    pub gas_ratio: Value,
+    /// `getRAMInfo`
+    #[serde(default)]
+    pub ram_info: Value,
+    /// `getAccount`, by account name
+    #[serde(default)]
+    pub accounts: BTreeMap<String, Value>,
+    /// `getTokenBalance`, by account name then token symbol
+    #[serde(default)]
+    pub token_balances: BTreeMap<String, BTreeMap<String, Value>>,
+    /// `getBlockByNumber` and `getBlockByHash`, found by their `number` and `hash` fields
+    #[serde(default)]
+    pub blocks: Vec<Value>,
+    /// `getTxByHash`, by tx hash
+    #[serde(default)]
+    pub txs: BTreeMap<String, Value>,
+    /// `getTxReceiptByTxHash`, by tx hash
+    #[serde(default)]
+    pub tx_receipts: BTreeMap<String, Value>,
+    /// `execTx`, the receipt of any pre executed transaction, whose `tx_hash` is set to its hash.
+    /// A SUCCESS receipt without resource usage when null
+    #[serde(default)]
+    pub exec_receipt: Value,
+    /// `getContract`, by contract id
+    #[serde(default)]
+    pub contracts: BTreeMap<String, Value>,
+    /// `getTokenInfo`, by token symbol
+    #[serde(default)]
+    pub token_infos: BTreeMap<String, Value>,
+    /// `getProducerVoteInfo`, by producer account
+    #[serde(default)]
+    pub producer_vote_infos: BTreeMap<String, Value>,
+    /// `getCandidateBonus`, by candidate account
+    #[serde(default)]
+    pub candidate_bonuses: BTreeMap<String, Value>,
+    /// `getVoterBonus`, by voter account
+    #[serde(default)]
+    pub voter_bonuses: BTreeMap<String, Value>,
+    /// `getContractStorage`, `getContractStorageFields` and `getBatchContractStorage`, by contract
+    /// then key: a string for a plain value, an object of strings for a map
+    #[serde(default)]
+    pub storage: BTreeMap<String, BTreeMap<String, Value>>,
+    /// `subscribe`, the `event` of the node with the `contract_id` it is filtered by, such as
+    /// `{"contract_id": "token.iost", "topic": "CONTRACT_RECEIPT", "data": "[]", "time": "0"}`
+    #[serde(default)]
+    pub events: Vec<Value>,
+}
+
+impl Fixtures {
+    pub fn from_json(json: &str) -> serde_json::Result<Fixtures> {
+        serde_json::from_str(json)
+    }
+
+    /// Read the fixtures from a JSON file with the layout of `fixtures/default.json`
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Fixtures> {
+        let json = fs::read_to_string(path)?;
+        Fixtures::from_json(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// The fixtures shipped with the crate: a mainnet chain id, an `admin` account and producer,
+    /// the `iost` token, the `base.iost` contract, blocks 0 to 3, whose hashes chain up, block 2 carrying the transfer of
+    /// the API documentation, and some storage of `token.iost` and `vote_producer.iost`
+    pub fn builtin() -> Fixtures {
+        Fixtures::from_json(include_str!("../fixtures/default.json"))
+            .expect("fixtures/default.json is valid")
+    }
+
+    /// Chain id required from the submitted transactions, if the chain info has one
+    pub fn chain_id(&self) -> Option<u64> {
+        self.chain_info["chain_id"].as_u64()
+    }
+
+    pub fn block_by_number(&self, number: &str) -> Option<&Value> {
+        self.blocks.iter().find(|b| b["number"] == number)
+    }
+
+    /// Stored string of `contract`, `"null"` when missing like the node answers. An empty field
+    /// reads a plain value, otherwise a field of a map
+    pub fn storage_data(&self, contract: &str, key: &str, field: &str) -> String {
+        let value = self.storage.get(contract).and_then(|keys| keys.get(key));
+        let value = match (value, field) {
+            (Some(Value::Object(map)), field) if !field.is_empty() => map.get(field),
+            (value, "") => value,
+            _ => None,
+        };
+        match value {
+            Some(Value::String(data)) => data.clone(),
+            Some(Value::Null) | None => "null".to_string(),
+            Some(value) => value.to_string(),
+        }
+    }
+
+    /// Fields of the map stored at `key`, sorted
+    pub fn storage_fields(&self, contract: &str, key: &str) -> Vec<String> {
+        match self.storage.get(contract).and_then(|keys| keys.get(key)) {
+            Some(Value::Object(map)) => map.keys().cloned().collect(),
+            _ => vec![],
+        }
+    }
+
+    pub fn block_by_hash(&self, hash: &str) -> Option<&Value> {
+        self.blocks.iter().find(|b| b["hash"] == hash)
+    }
+}
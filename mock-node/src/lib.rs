@@ -0,0 +1,624 @@
+//! A mock IOST node serving the HTTP API from fixture data, so that the client and the signing
+//! paths can be tested without network.
+//!
+//! Transactions submitted to `sendTx` are decoded as `iost_chain::Tx` and their signatures are
+//! checked with `Tx::verify`. The accepted ones get a SUCCESS receipt, served afterwards by
+//! `getTxReceiptByTxHash` and `getTxByHash`. `execTx` pre executes unsigned transactions too, and
+//! answers with the receipt of the fixtures.
+//!
+//! `subscribe` streams the fixture events matching the topics and the contract of the request,
+//! then closes the stream, so that the clients reconnect.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use iost_chain::Tx;
+use serde_json::{json, Value};
+use tokio::sync::oneshot;
+
+pub use crate::fixtures::Fixtures;
+
+mod fixtures;
+
+/// Error code of the answers of the node when a request fails
+pub const ERROR_CODE: i32 = 2;
+
+/// A running mock node, stopped when dropped
+pub struct MockNode {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+struct State {
+    fixtures: Fixtures,
+    sent_txs: Vec<(String, Tx)>,
+    subscriptions: usize,
+}
+
+impl MockNode {
+    /// Start a node on a free local port. Must be called from a tokio runtime
+    pub fn start(fixtures: Fixtures) -> Result<MockNode, hyper::Error> {
+        MockNode::bind(([127, 0, 0, 1], 0).into(), fixtures)
+    }
+
+    /// Start a node listening on `addr`. Must be called from a tokio runtime
+    pub fn bind(addr: SocketAddr, fixtures: Fixtures) -> Result<MockNode, hyper::Error> {
+        let state = Arc::new(Mutex::new(State {
+            fixtures,
+            sent_txs: Vec::new(),
+            subscriptions: 0,
+        }));
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_conn| {
+            let state = service_state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), req))) }
+        });
+        let server = Server::try_bind(&addr)?.serve(make_service);
+        let addr = server.local_addr();
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            stopped.await.ok();
+        }));
+        Ok(MockNode {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Host to give to the clients, such as `http://127.0.0.1:30001`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Hashes and transactions accepted by `sendTx`, in order
+    pub fn sent_txs(&self) -> Vec<(String, Tx)> {
+        self.state.lock().unwrap().sent_txs.clone()
+    }
+
+    /// Number of the `subscribe` requests answered so far
+    pub fn subscriptions(&self) -> usize {
+        self.state.lock().unwrap().subscriptions
+    }
+}
+
+impl Drop for MockNode {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<State>>,
+    req: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let method = req.method().clone();
+    let path = req.uri().path().trim_matches('/').to_string();
+    let result = match hyper::body::to_bytes(req.into_body()).await {
+        Ok(body) if method == Method::POST && path == "subscribe" => {
+            state.lock().unwrap().subscribe(&body)
+        }
+        Ok(body) => state
+            .lock()
+            .unwrap()
+            .route(&method, &path, &body)
+            .map(|value| value.to_string()),
+        Err(e) => Err(format!("cannot read request: {}", e)),
+    };
+    let (status, body) = match result {
+        Ok(body) => (StatusCode::OK, body),
+        Err(message) => (
+            StatusCode::BAD_REQUEST,
+            json!({ "code": ERROR_CODE, "message": message }).to_string(),
+        ),
+    };
+    let response = Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body))
+        .expect("valid response");
+    Ok(response)
+}
+
+fn parse_request(body: &[u8]) -> Result<Value, String> {
+    serde_json::from_slice(body).map_err(|e| format!("invalid request: {}", e))
+}
+
+fn found(value: Option<&Value>, what: &str) -> Result<Value, String> {
+    match value {
+        Some(Value::Null) | None => Err(format!("{} not found", what)),
+        Some(value) => Ok(value.clone()),
+    }
+}
+
+impl State {
+    fn route(&mut self, method: &Method, path: &str, body: &[u8]) -> Result<Value, String> {
+        let fixtures = &self.fixtures;
+        let segments: Vec<&str> = path.split('/').collect();
+        match (method, segments.as_slice()) {
+            (&Method::GET, ["getNodeInfo"]) => found(Some(&fixtures.node_info), "node info"),
+            (&Method::GET, ["getChainInfo"]) => found(Some(&fixtures.chain_info), "chain info"),
+            (&Method::GET, ["getGasRatio"]) => found(Some(&fixtures.gas_ratio), "gas ratio"),
+            (&Method::GET, ["getRAMInfo"]) => found(Some(&fixtures.ram_info), "ram info"),
+            (&Method::GET, ["getAccount", name, _]) => {
+                found(fixtures.accounts.get(*name), &format!("account {}", name))
+            }
+            (&Method::GET, ["getContract", id, _]) => {
+                found(fixtures.contracts.get(*id), &format!("contract {}", id))
+            }
+            (&Method::GET, ["getTokenInfo", symbol, _]) => found(
+                fixtures.token_infos.get(*symbol),
+                &format!("token {}", symbol),
+            ),
+            (&Method::GET, ["getProducerVoteInfo", id, _]) => found(
+                fixtures.producer_vote_infos.get(*id),
+                &format!("producer {}", id),
+            ),
+            (&Method::GET, ["getCandidateBonus", name, _]) => found(
+                fixtures.candidate_bonuses.get(*name),
+                &format!("bonus of candidate {}", name),
+            ),
+            (&Method::GET, ["getVoterBonus", name, _]) => found(
+                fixtures.voter_bonuses.get(*name),
+                &format!("bonus of voter {}", name),
+            ),
+            (&Method::GET, ["getTokenBalance", account, token, _]) => found(
+                fixtures
+                    .token_balances
+                    .get(*account)
+                    .and_then(|balances| balances.get(*token)),
+                &format!("{} balance of {}", token, account),
+            ),
+            (&Method::GET, ["getBlockByNumber", number, _]) => found(
+                fixtures.block_by_number(number),
+                &format!("block {}", number),
+            )
+            .map(|block| json!({ "status": "IRREVERSIBLE", "block": block })),
+            (&Method::GET, ["getBlockByHash", hash, _]) => {
+                found(fixtures.block_by_hash(hash), &format!("block {}", hash))
+                    .map(|block| json!({ "status": "IRREVERSIBLE", "block": block }))
+            }
+            (&Method::GET, ["getTxByHash", hash]) => {
+                found(fixtures.txs.get(*hash), &format!("tx {}", hash))
+            }
+            (&Method::GET, ["getTxReceiptByTxHash", hash]) => found(
+                fixtures.tx_receipts.get(*hash),
+                &format!("receipt of tx {}", hash),
+            ),
+            (&Method::POST, ["getContractStorage"]) => {
+                let request = parse_request(body)?;
+                let data = fixtures.storage_data(
+                    request["id"].as_str().unwrap_or_default(),
+                    request["key"].as_str().unwrap_or_default(),
+                    request["field"].as_str().unwrap_or_default(),
+                );
+                Ok(
+                    json!({ "data": data, "block_hash": fixtures.chain_info["lib_block_hash"], "block_number": fixtures.chain_info["lib_block"] }),
+                )
+            }
+            (&Method::POST, ["getContractStorageFields"]) => {
+                let request = parse_request(body)?;
+                let fields = fixtures.storage_fields(
+                    request["id"].as_str().unwrap_or_default(),
+                    request["key"].as_str().unwrap_or_default(),
+                );
+                Ok(
+                    json!({ "fields": fields, "block_hash": fixtures.chain_info["lib_block_hash"], "block_number": fixtures.chain_info["lib_block"] }),
+                )
+            }
+            (&Method::POST, ["getBatchContractStorage"]) => {
+                let request = parse_request(body)?;
+                let id = request["id"].as_str().unwrap_or_default();
+                let key_fields = request["key_fields"]
+                    .as_array()
+                    .ok_or_else(|| "no key_fields".to_string())?;
+                let datas: Vec<String> = key_fields
+                    .iter()
+                    .map(|kf| {
+                        fixtures.storage_data(
+                            id,
+                            kf["key"].as_str().unwrap_or_default(),
+                            kf["field"].as_str().unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+                Ok(
+                    json!({ "datas": datas, "block_hash": fixtures.chain_info["lib_block_hash"], "block_number": fixtures.chain_info["lib_block"] }),
+                )
+            }
+            (&Method::POST, ["sendTx"]) => {
+                let (hash, tx) = self.check_tx(body)?;
+                let receipt = success_receipt(&hash, &tx);
+                let packed = packed_tx(&hash, &tx, &receipt, &self.fixtures.chain_info);
+                self.fixtures.tx_receipts.insert(hash.clone(), receipt);
+                self.fixtures.txs.insert(hash.clone(), packed);
+                self.sent_txs.push((hash.clone(), tx));
+                Ok(json!({ "hash": hash, "pre_tx_receipt": null }))
+            }
+            (&Method::POST, ["execTx"]) => {
+                let (hash, tx) = self.decode_tx(body)?;
+                match &self.fixtures.exec_receipt {
+                    Value::Null => Ok(success_receipt(&hash, &tx)),
+                    receipt => {
+                        let mut receipt = receipt.clone();
+                        receipt["tx_hash"] = Value::from(hash);
+                        Ok(receipt)
+                    }
+                }
+            }
+            _ => Err(format!("unknown api {} /{}", method, path)),
+        }
+    }
+
+    /// Events of the fixtures for a `{"topics": [...], "filter": {"contract_id": ...}}` request,
+    /// one `{"result": {"event": ...}}` line each as the gateway of the node streams them
+    fn subscribe(&mut self, body: &[u8]) -> Result<String, String> {
+        let request = parse_request(body)?;
+        let topics = request["topics"]
+            .as_array()
+            .ok_or_else(|| "no topics".to_string())?;
+        let contract_id = &request["filter"]["contract_id"];
+        self.subscriptions += 1;
+        let mut lines = String::new();
+        for event in self.fixtures.events.iter() {
+            if !topics.contains(&event["topic"])
+                || (contract_id.is_string() && *contract_id != event["contract_id"])
+            {
+                continue;
+            }
+            let event = json!({
+                "topic": event["topic"],
+                "data": event["data"],
+                "time": event["time"],
+            });
+            lines.push_str(&json!({ "result": { "event": event } }).to_string());
+            lines.push('\n');
+        }
+        Ok(lines)
+    }
+
+    /// Decode a submitted transaction, check its chain id, and return it with its base58 hash
+    fn decode_tx(&self, body: &[u8]) -> Result<(String, Tx), String> {
+        let tx: Tx = serde_json::from_slice(body).map_err(|e| format!("invalid tx: {}", e))?;
+        if let Some(chain_id) = self.fixtures.chain_id() {
+            if u64::from(tx.chain_id) != chain_id {
+                return Err(format!(
+                    "invalid chain id {}, expected {}",
+                    tx.chain_id, chain_id
+                ));
+            }
+        }
+        let hash = tx.hash().map_err(|e| format!("cannot hash tx: {:?}", e))?;
+        Ok((bs58::encode(hash).into_string(), tx))
+    }
+
+    /// Decode a submitted transaction like `decode_tx`, and check its signatures
+    fn check_tx(&self, body: &[u8]) -> Result<(String, Tx), String> {
+        let (hash, tx) = self.decode_tx(body)?;
+        if tx.publisher_sigs.is_empty() {
+            return Err(format!(
+                "tx is not signed by its publisher {}",
+                tx.publisher
+            ));
+        }
+        tx.verify()
+            .map_err(|e| format!("invalid tx signature: {:?}", e))?;
+        Ok((hash, tx))
+    }
+}
+
+fn success_receipt(hash: &str, tx: &Tx) -> Value {
+    json!({
+        "tx_hash": hash,
+        "gas_usage": 0.0,
+        "ram_usage": {},
+        "status_code": "SUCCESS",
+        "message": "",
+        "returns": tx.actions.iter().map(|_| "[]").collect::<Vec<_>>(),
+        "receipts": [],
+    })
+}
+
+/// Answer of `getTxByHash` for an accepted transaction, packed in the last irreversible block
+fn packed_tx(hash: &str, tx: &Tx, receipt: &Value, chain_info: &Value) -> Value {
+    let actions: Vec<Value> = tx
+        .actions
+        .iter()
+        .map(|action| {
+            json!({
+                "contract": String::from_utf8_lossy(&action.contract),
+                "action_name": String::from_utf8_lossy(&action.action_name),
+                "data": String::from_utf8_lossy(&action.data),
+            })
+        })
+        .collect();
+    json!({
+        "status": "PACKED",
+        "transaction": {
+            "hash": hash,
+            "time": tx.time.to_string(),
+            "expiration": tx.expiration.to_string(),
+            "gas_ratio": (tx.gas_ratio * 100.0) as i64,
+            "gas_limit": (tx.gas_limit * 100.0) as i64,
+            "delay": tx.delay.to_string(),
+            "chain_id": tx.chain_id,
+            "actions": actions,
+            "signers": tx.signers,
+            "publisher": tx.publisher,
+            "referred_tx": "",
+            "amount_limit": tx.amount_limit,
+            "tx_receipt": receipt,
+        },
+        "block_number": chain_info["lib_block"].as_str().unwrap_or("0"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use iost_chain::{IostAction, TxBuilder, MAINNET_CHAIN_ID};
+    use keys::algorithm;
+    use lite_json::{parse_json, JsonValue};
+
+    const TIME: i64 = 1598918258274417000;
+
+    fn sec_key() -> Vec<u8> {
+        bs58::decode("xjggJ3TrLXz7qEwrGG3Rc4Fz59imjixhXpViq9W7Ncx")
+            .into_vec()
+            .unwrap()
+    }
+
+    fn signed_tx(chain_id: u32) -> Tx {
+        let mut tx = TxBuilder::new(chain_id)
+            .time(TIME)
+            .publisher("admin")
+            .action(IostAction::transfer("admin", "lispczz3", "10", "").unwrap())
+            .build()
+            .unwrap();
+        tx.sign("admin".to_string(), algorithm::SECP256K1, &sec_key())
+            .unwrap();
+        tx
+    }
+
+    async fn post(node: &MockNode, tx: &Tx) -> (u16, Value) {
+        let response = reqwest::Client::new()
+            .post(&format!("{}/sendTx", node.url()))
+            .json(tx)
+            .send()
+            .await
+            .unwrap();
+        let status = response.status().as_u16();
+        (status, response.json().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn should_serve_fixtures() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let get = |path: &str| reqwest::get(format!("{}/{}", node.url(), path));
+
+        let chain_info: Value = get("getChainInfo").await.unwrap().json().await.unwrap();
+        assert_eq!(chain_info["chain_id"], 1024);
+
+        let account: Value = get("getAccount/admin/true")
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(account["name"], "admin");
+
+        let block: Value = get("getBlockByNumber/3/false")
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(block["block"]["number"], "3");
+
+        let missing = get("getAccount/nobody/true").await.unwrap();
+        assert_eq!(missing.status().as_u16(), 400);
+        let error: Value = missing.json().await.unwrap();
+        assert_eq!(error["code"], ERROR_CODE);
+    }
+
+    #[tokio::test]
+    async fn should_serve_chain_info_to_no_std_parser() {
+        // the chain info as read by the JSON parser iost-chain uses without std
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let body = reqwest::get(format!("{}/getChainInfo", node.url()))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        let mut chain_id = None;
+        let mut head_block_hash = None;
+        if let JsonValue::Object(fields) = parse_json(&body).unwrap() {
+            for (key, value) in fields {
+                match (key.iter().collect::<String>().as_str(), value) {
+                    ("chain_id", JsonValue::Number(number)) => chain_id = Some(number.to_f64()),
+                    ("head_block_hash", JsonValue::String(chars)) => {
+                        head_block_hash = Some(chars.iter().collect::<String>())
+                    }
+                    _ => {}
+                }
+            }
+        }
+        assert_eq!(chain_id, Some(1024.0));
+        assert_eq!(
+            head_block_hash.as_deref(),
+            Some("57wbsUCTjWanYNWNUAH5vk3VoQJnvkWgfV84sR7Hcsj4")
+        );
+    }
+
+    #[tokio::test]
+    async fn should_serve_storage() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let post = |path: &str, request: Value| {
+            reqwest::Client::new()
+                .post(&format!("{}/{}", node.url(), path))
+                .json(&request)
+                .send()
+        };
+
+        let request = json!({ "id": "token.iost", "key": "TIiost", "field": "decimal" });
+        let storage: Value = post("getContractStorage", request)
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(storage["data"], "8");
+
+        let request = json!({ "id": "token.iost", "key": "TIiost", "field": "unknown" });
+        let storage: Value = post("getContractStorage", request)
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(storage["data"], "null");
+
+        let request = json!({ "id": "token.iost", "key": "TIiost" });
+        let fields: Value = post("getContractStorageFields", request)
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(fields["fields"].as_array().unwrap().len(), 5);
+
+        let request = json!({
+            "id": "vote_producer.iost",
+            "key_fields": [
+                { "key": "currentProducerList", "field": "" },
+                { "key": "producerKeyToId", "field": "unknown" },
+            ],
+        });
+        let batch: Value = post("getBatchContractStorage", request)
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            batch["datas"],
+            json!(["[\"xjggJ3TrLXz7qEwrGG3Rc4Fz59imjixhXpViq9W7Ncx\"]", "null"])
+        );
+    }
+
+    #[tokio::test]
+    async fn should_accept_signed_tx() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let tx = signed_tx(MAINNET_CHAIN_ID);
+        let (status, response) = post(&node, &tx).await;
+        assert_eq!(status, 200);
+        let hash = bs58::encode(tx.hash().unwrap()).into_string();
+        assert_eq!(response["hash"], hash.as_str());
+        assert_eq!(node.sent_txs().len(), 1);
+
+        let receipt: Value = reqwest::get(format!("{}/getTxReceiptByTxHash/{}", node.url(), hash))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(receipt["status_code"], "SUCCESS");
+    }
+
+    #[tokio::test]
+    async fn should_accept_no_std_serialized_tx() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let tx = signed_tx(MAINNET_CHAIN_ID);
+        let response = reqwest::Client::new()
+            .post(&format!("{}/sendTx", node.url()))
+            .body(tx.clone().no_std_serialize())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        let response: Value = response.json().await.unwrap();
+        let hash = bs58::encode(tx.hash().unwrap()).into_string();
+        assert_eq!(response["hash"], hash.as_str());
+        assert_eq!(node.sent_txs().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_tx() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+
+        let mut tampered = signed_tx(MAINNET_CHAIN_ID);
+        tampered.actions[0] = IostAction::transfer("admin", "lispczz3", "1000", "").unwrap();
+        assert_eq!(post(&node, &tampered).await.0, 400);
+
+        let mut unsigned = signed_tx(MAINNET_CHAIN_ID);
+        unsigned.publisher_sigs.clear();
+        assert_eq!(post(&node, &unsigned).await.0, 400);
+
+        let mut malformed = signed_tx(MAINNET_CHAIN_ID);
+        malformed.publisher_sigs[0].public_key = "AAAA".to_string();
+        assert_eq!(post(&node, &malformed).await.0, 400);
+
+        assert_eq!(post(&node, &signed_tx(MAINNET_CHAIN_ID + 1)).await.0, 400);
+        assert!(node.sent_txs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_stream_events() {
+        let mut fixtures = Fixtures::builtin();
+        fixtures.events = vec![
+            json!({
+                "contract_id": "token.iost",
+                "topic": "CONTRACT_RECEIPT",
+                "data": "[\"iost\",\"admin\",\"lispczz3\",\"10\",\"\"]",
+                "time": TIME.to_string(),
+            }),
+            json!({
+                "contract_id": "vote_producer.iost",
+                "topic": "CONTRACT_RECEIPT",
+                "data": "[]",
+                "time": TIME.to_string(),
+            }),
+            json!({
+                "contract_id": "token.iost",
+                "topic": "CONTRACT_EVENT",
+                "data": "{}",
+                "time": TIME.to_string(),
+            }),
+        ];
+        let node = MockNode::start(fixtures).unwrap();
+        let request = json!({
+            "topics": ["CONTRACT_RECEIPT"],
+            "filter": { "contract_id": "token.iost" },
+        });
+        let body = reqwest::Client::new()
+            .post(&format!("{}/subscribe", node.url()))
+            .json(&request)
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        let lines: Vec<Value> = body
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 1);
+        let event = &lines[0]["result"]["event"];
+        assert_eq!(event["topic"], "CONTRACT_RECEIPT");
+        assert_eq!(event["time"], TIME.to_string());
+        assert!(event.get("contract_id").is_none());
+        assert_eq!(node.subscriptions(), 1);
+    }
+}
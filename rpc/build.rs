@@ -0,0 +1,5 @@
+fn main() {
+    // the messages and the service of the gRPC API are generated only with the `grpc` feature
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/rpc.proto").unwrap();
+}
@@ -1,5 +1,8 @@
+use crate::error::Error;
 use crate::info::Info;
 use crate::transaction::Transaction;
+use crate::tx_tracker::parse_number;
+use iost_chain::spv;
 use serde::{Serialize, Deserialize};
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -30,3 +33,71 @@ pub struct Block {
     pub transactions: Vec<Transaction>
 }
 
+pub(crate) fn decode_hash(hash: &str) -> Result<Vec<u8>, Error> {
+    bs58::decode(hash).into_vec().map_err(|_| Error::InvalidBlock(format!("invalid hash {}", hash)))
+}
+
+/// The `info` of a head as the node may have hashed it: the JSON of the info, an empty batch
+/// index being marshalled as null or as an empty array, or nothing at all
+pub(crate) fn head_infos(mode: i32, thread: i32, batch_index: &[i32]) -> Vec<Vec<u8>> {
+    let json = |batch_index: &str| format!(
+        r#"{{"mode":{},"thread":{},"batch_index":{}}}"#, mode, thread, batch_index
+    ).into_bytes();
+    if batch_index.is_empty() {
+        vec![json("null"), json("[]"), vec![]]
+    } else {
+        vec![json(&serde_json::to_string(batch_index).unwrap_or_default())]
+    }
+}
+
+/// The unsigned block of `head`, failing unless the head hashes to `hash` with one of `infos`
+pub(crate) fn unsigned_block(hash: &str, mut head: spv::Head, infos: Vec<Vec<u8>>, tx_hashes: Vec<String>, receipts: Vec<iost_chain::TxReceipt>) -> Result<spv::Block, Error> {
+    let expected = decode_hash(hash)?;
+    let hashed = infos.into_iter().any(|info| {
+        head.info = info;
+        head.hash() == expected
+    });
+    if !hashed {
+        return Err(Error::InvalidBlock(format!("block {} does not hash to {}", head.number, hash)));
+    }
+    Ok(spv::Block {
+        head,
+        sign: spv::Sign {
+            algorithm: 0,
+            sig: String::new(),
+            pub_key: String::new(),
+        },
+        receipts,
+        txs: vec![],
+        tx_hashes,
+        receipt_hashes: vec![],
+        block_type: String::new(),
+    })
+}
+
+impl Block {
+    /// The block as a light client checks it, failing unless its head hashes to `hash`.
+    /// The JSON API returns neither the signature of the witness nor those of the transactions,
+    /// so `sign` and `txs` are left empty. The tx hashes and the receipts are those of the
+    /// transactions of a block fetched complete
+    pub fn to_spv_block(&self) -> Result<spv::Block, Error> {
+        let head = spv::Head {
+            version: parse_number(&self.version)?,
+            parent_hash: decode_hash(&self.parent_hash)?,
+            tx_merkle_hash: decode_hash(&self.tx_merkle_hash)?,
+            tx_receipt_merkle_hash: decode_hash(&self.tx_receipt_merkle_hash)?,
+            info: vec![],
+            number: parse_number(&self.number)?,
+            witness: self.witness.clone(),
+            time: parse_number(&self.time)?,
+        };
+        let tx_hashes = self.transactions.iter()
+            .map(|tx| decode_hash(&tx.hash).map(base64::encode))
+            .collect::<Result<Vec<_>, _>>()?;
+        let receipts = self.transactions.iter()
+            .map(|tx| tx.tx_receipt.to_spv_receipt())
+            .collect::<Result<Vec<_>, _>>()?;
+        let infos = head_infos(self.info.mode, self.info.thread, &self.info.batch_index);
+        unsigned_block(&self.hash, head, infos, tx_hashes, receipts)
+    }
+}
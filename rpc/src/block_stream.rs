@@ -0,0 +1,434 @@
+use std::future::Future;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::{self, Stream};
+use iost_chain::spv::{Block, Head};
+
+use crate::epoch_proof::BlockSource;
+use crate::error::Error;
+use crate::tx_tracker::{parse_number, DEFAULT_POLL_INTERVAL};
+use crate::{Client, IOST};
+
+/// Default number of retries of a failed request before the error is yielded
+pub const DEFAULT_RETRIES: usize = 3;
+/// Default interval before the first retry, doubled at each retry
+pub const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+/// Number of the last yielded heads kept to find where a fork starts
+pub const MAX_FORK_DEPTH: usize = 64;
+
+/// Which end of the chain a `BlockStream` follows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StreamMode {
+    /// every block up to the head block, which may be reverted
+    Head,
+    /// every block up to the last irreversible block
+    Irreversible,
+}
+
+#[derive(Debug)]
+pub enum BlockEvent {
+    /// the next block of the followed chain
+    Block(Block),
+    /// the yielded blocks after `ancestor` are not on the chain anymore, oldest first.
+    /// The blocks of the new branch are yielded next
+    Fork { ancestor: i64, removed: Vec<Head> },
+}
+
+/// Height of the chain, as in `getChainInfo`
+#[async_trait]
+pub trait ChainHeight {
+    /// Numbers of the head block and of the last irreversible block
+    async fn get_heights(&self) -> Result<(i64, i64), Error>;
+}
+
+#[async_trait]
+impl ChainHeight for IOST {
+    async fn get_heights(&self) -> Result<(i64, i64), Error> {
+        let chain_info = self.get_chain_info().await?;
+        Ok((parse_number(&chain_info.head_block)?, parse_number(&chain_info.lib_block)?))
+    }
+}
+
+#[async_trait]
+impl<'a, T: ChainHeight + Sync + ?Sized> ChainHeight for &'a T {
+    async fn get_heights(&self) -> Result<(i64, i64), Error> {
+        (**self).get_heights().await
+    }
+}
+
+/// Follows the chain block after block, waiting for new blocks, retrying failed requests and
+/// checking that each block links to the previous one with its `parent_hash`.
+/// The transactions and receipts of the blocks are dropped unless `with_txs` is set.
+/// `IOST` is both the height and the source of a stream following a node, whose blocks are
+/// unsigned.
+pub struct BlockStream<H, S> {
+    height: H,
+    source: S,
+    mode: StreamMode,
+    next: i64,
+    target: i64,
+    recent: Vec<Head>,
+    with_txs: bool,
+    poll_interval: Duration,
+    retries: usize,
+    retry_interval: Duration,
+}
+
+impl<H, S> BlockStream<H, S>
+    where H: ChainHeight + Sync,
+          S: BlockSource + Sync
+{
+    /// Stream of the blocks from the block `start`, heights taken from `height` and blocks
+    /// fetched from `source`
+    pub fn new(height: H, source: S, mode: StreamMode, start: i64) -> Self {
+        Self {
+            height,
+            source,
+            mode,
+            next: start,
+            target: start - 1,
+            recent: Vec::new(),
+            with_txs: false,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            retries: DEFAULT_RETRIES,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+        }
+    }
+
+    /// Keep the decoded transactions and receipts of the yielded blocks
+    pub fn with_txs(mut self, with_txs: bool) -> Self {
+        self.with_txs = with_txs;
+        self
+    }
+
+    /// Interval between two polls of the height while no new block is available
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// Number of the next block to be yielded
+    pub fn next_number(&self) -> i64 {
+        self.next
+    }
+
+    async fn retry<T, F, R>(&self, request: F) -> Result<T, Error>
+        where F: Fn() -> R,
+              R: Future<Output = Result<T, Error>>
+    {
+        let mut interval = self.retry_interval;
+        let mut retries = 0;
+        loop {
+            match request().await {
+                Err(_) if retries < self.retries => {
+                    tokio::time::delay_for(interval).await;
+                    interval *= 2;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn fetch(&self, number: i64) -> Result<Block, Error> {
+        self.retry(|| self.source.get_spv_block(number)).await
+    }
+
+    /// Wait for the next block, or report a fork. The stream is left unchanged on error,
+    /// so that it can be polled again
+    pub async fn next_event(&mut self) -> Result<BlockEvent, Error> {
+        while self.next > self.target {
+            let (head, lib) = self.retry(|| self.height.get_heights()).await?;
+            self.target = match self.mode {
+                StreamMode::Head => head,
+                StreamMode::Irreversible => lib,
+            };
+            if self.next > self.target {
+                tokio::time::delay_for(self.poll_interval).await;
+            }
+        }
+
+        let mut block = self.fetch(self.next).await?;
+        if let Some(last) = self.recent.last() {
+            if block.head.parent_hash != last.hash() {
+                return self.rewind().await;
+            }
+        }
+        self.recent.push(block.head.clone());
+        if self.recent.len() > MAX_FORK_DEPTH {
+            self.recent.remove(0);
+        }
+        self.next += 1;
+        if !self.with_txs {
+            block.txs.clear();
+            block.receipts.clear();
+        }
+        Ok(BlockEvent::Block(block))
+    }
+
+    /// Drop the yielded blocks which were replaced, back to the last one the chain still links
+    /// to. A fork deeper than `MAX_FORK_DEPTH` blocks is reported from the oldest kept block
+    async fn rewind(&mut self) -> Result<BlockEvent, Error> {
+        let mut kept = self.recent.clone();
+        let mut removed = Vec::new();
+        while let Some(head) = kept.pop() {
+            let number = head.number;
+            removed.insert(0, head);
+            let parent_hash = match kept.last() {
+                Some(parent) => parent.hash(),
+                None => break,
+            };
+            if self.fetch(number).await?.head.parent_hash == parent_hash {
+                break;
+            }
+        }
+        let ancestor = removed[0].number - 1;
+        self.recent = kept;
+        self.next = ancestor + 1;
+        Ok(BlockEvent::Fork { ancestor, removed })
+    }
+
+    /// Endless stream of the events of `next_event`
+    pub fn into_stream(self) -> impl Stream<Item = Result<BlockEvent, Error>> {
+        stream::unfold(self, |mut blocks| async move {
+            let event = blocks.next_event().await;
+            Some((event, blocks))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Mutex;
+    use futures::StreamExt;
+    use iost_chain::spv::Sign;
+    use iost_mock_node::{Fixtures, MockNode};
+
+    struct ChainState {
+        heads: Vec<Head>,
+        head: i64,
+        lib: i64,
+        failures: usize,
+    }
+
+    /// Chain of unsigned blocks from block 1, failing the next `failures` requests
+    struct FakeChain {
+        state: Mutex<ChainState>,
+    }
+
+    fn head(parent: Option<&Head>, number: i64, witness: &str) -> Head {
+        Head {
+            version: 1,
+            parent_hash: parent.map(|p| p.hash()).unwrap_or_default(),
+            tx_merkle_hash: vec![],
+            tx_receipt_merkle_hash: vec![],
+            info: vec![],
+            number,
+            witness: witness.to_string(),
+            time: 1598918258274417000 + number * 500_000_000,
+        }
+    }
+
+    impl FakeChain {
+        fn new(len: i64, lib: i64) -> Self {
+            let chain = FakeChain {
+                state: Mutex::new(ChainState { heads: vec![], head: 0, lib, failures: 0 }),
+            };
+            chain.fork(1, len, "producer");
+            chain
+        }
+
+        /// Replace the blocks from `number` by `len` blocks of `witness`
+        fn fork(&self, number: i64, len: i64, witness: &str) {
+            let mut state = self.state.lock().unwrap();
+            state.heads.truncate(number as usize - 1);
+            for n in number..number + len {
+                let h = head(state.heads.last(), n, witness);
+                state.heads.push(h);
+            }
+            state.head = number + len - 1;
+        }
+
+        fn set_lib(&self, lib: i64) {
+            self.state.lock().unwrap().lib = lib;
+        }
+
+        fn fail(&self, failures: usize) {
+            self.state.lock().unwrap().failures = failures;
+        }
+
+        fn check(&self) -> Result<(), Error> {
+            let mut state = self.state.lock().unwrap();
+            if state.failures > 0 {
+                state.failures -= 1;
+                return Err(Error::InvalidNumber("unavailable".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ChainHeight for FakeChain {
+        async fn get_heights(&self) -> Result<(i64, i64), Error> {
+            self.check()?;
+            let state = self.state.lock().unwrap();
+            Ok((state.head, state.lib))
+        }
+    }
+
+    #[async_trait]
+    impl BlockSource for FakeChain {
+        async fn get_spv_block(&self, number: i64) -> Result<Block, Error> {
+            self.check()?;
+            let state = self.state.lock().unwrap();
+            let head = state.heads.get(number as usize - 1)
+                .ok_or_else(|| Error::InvalidNumber(number.to_string()))?;
+            Ok(Block {
+                head: head.clone(),
+                sign: Sign {
+                    algorithm: 2,
+                    sig: "".to_string(),
+                    pub_key: "".to_string(),
+                },
+                receipts: vec![],
+                txs: vec![],
+                tx_hashes: vec![],
+                receipt_hashes: vec![],
+                block_type: "".to_string(),
+            })
+        }
+    }
+
+    fn stream(chain: &FakeChain, mode: StreamMode) -> BlockStream<&FakeChain, &FakeChain> {
+        BlockStream::new(chain, chain, mode, 1)
+            .poll_interval(Duration::from_millis(1))
+            .retry_interval(Duration::from_millis(1))
+    }
+
+    async fn next(blocks: &mut BlockStream<&FakeChain, &FakeChain>) -> Result<BlockEvent, Error> {
+        tokio::time::timeout(Duration::from_secs(5), blocks.next_event()).await.unwrap()
+    }
+
+    async fn next_number(blocks: &mut BlockStream<&FakeChain, &FakeChain>) -> i64 {
+        match next(blocks).await.unwrap() {
+            BlockEvent::Block(block) => block.head.number,
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_blocks_be_streamed_up_to_the_mode_height() {
+        let chain = FakeChain::new(5, 3);
+        let heads: Vec<i64> = stream(&chain, StreamMode::Head)
+            .into_stream()
+            .take(5)
+            .map(|e| match e.unwrap() {
+                BlockEvent::Block(block) => block.head.number,
+                event => panic!("unexpected {:?}", event),
+            })
+            .collect()
+            .await;
+        assert_eq!(heads, vec![1, 2, 3, 4, 5]);
+
+        let mut irreversible = stream(&chain, StreamMode::Irreversible);
+        for number in 1..=3 {
+            assert_eq!(next_number(&mut irreversible).await, number);
+        }
+        // no block until the next one is irreversible
+        let pending = tokio::time::timeout(Duration::from_millis(50), irreversible.next_event()).await;
+        assert!(pending.is_err());
+        chain.set_lib(5);
+        assert_eq!(next_number(&mut irreversible).await, 4);
+        assert_eq!(next_number(&mut irreversible).await, 5);
+    }
+
+    #[tokio::test]
+    async fn should_fork_be_reported() {
+        let chain = FakeChain::new(5, 1);
+        let mut blocks = stream(&chain, StreamMode::Head);
+        for number in 1..=5 {
+            assert_eq!(next_number(&mut blocks).await, number);
+        }
+        let replaced: Vec<Head> = chain.state.lock().unwrap().heads[3..].to_vec();
+
+        chain.fork(4, 3, "other");
+        match next(&mut blocks).await.unwrap() {
+            BlockEvent::Fork { ancestor, removed } => {
+                assert_eq!(ancestor, 3);
+                assert_eq!(removed, replaced);
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+        for number in 4..=6 {
+            match next(&mut blocks).await.unwrap() {
+                BlockEvent::Block(block) => {
+                    assert_eq!(block.head.number, number);
+                    assert_eq!(block.head.witness, "other");
+                }
+                event => panic!("unexpected {:?}", event),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn should_failed_requests_be_retried() {
+        let chain = FakeChain::new(3, 3);
+        let mut blocks = stream(&chain, StreamMode::Head).retries(2);
+        chain.fail(2);
+        assert_eq!(next_number(&mut blocks).await, 1);
+
+        chain.fail(3);
+        assert!(next(&mut blocks).await.is_err());
+        assert_eq!(blocks.next_number(), 2);
+        assert_eq!(next_number(&mut blocks).await, 2);
+    }
+
+    #[tokio::test]
+    async fn should_blocks_of_node_be_streamed() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let client = IOST::new(&node.url());
+        let mut blocks = BlockStream::new(&client, &client, StreamMode::Irreversible, 0)
+            .with_txs(true)
+            .poll_interval(Duration::from_millis(1))
+            .retry_interval(Duration::from_millis(1));
+        let mut parent: Option<Head> = None;
+        for number in 0..=3 {
+            let event = tokio::time::timeout(Duration::from_secs(5), blocks.next_event()).await.unwrap();
+            let block = match event.unwrap() {
+                BlockEvent::Block(block) => block,
+                event => panic!("unexpected {:?}", event),
+            };
+            assert_eq!(block.head.number, number);
+            if let Some(parent) = parent {
+                assert_eq!(block.head.parent_hash, parent.hash());
+            }
+            block.verify_hashes().unwrap();
+            assert_eq!(block.receipts.len(), if number == 2 { 1 } else { 0 });
+            parent = Some(block.head);
+        }
+        // the last irreversible block of the node is block 3
+        let pending = tokio::time::timeout(Duration::from_millis(50), blocks.next_event()).await;
+        assert!(pending.is_err());
+
+        let mut without_txs = BlockStream::new(&client, &client, StreamMode::Head, 2);
+        match without_txs.next_event().await.unwrap() {
+            BlockEvent::Block(block) => {
+                assert!(block.receipts.is_empty());
+                assert_eq!(block.tx_hashes.len(), 1);
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use iost_chain::spv::{Block, EpochChangeProof, Verify, CONFIRM_WITNESS_NUM, VOTE_INTERVAL};
+
+use crate::error::Error;
+use crate::get_block_by_hash::BlockByNumber;
+use crate::{Client, IOST};
+
+/// Maximum number of blocks after the vote block fetched to find its confirmations
+pub const MAX_WITNESS_BLOCKS: i64 = VOTE_INTERVAL;
+
+/// Source of full blocks, with the signature, the receipts and the hashes a light client checks.
+/// The JSON API of a node does not return block signatures, so the blocks `IOST` returns only pass
+/// the hash checks; a relayer proving epoch changes implements it on top of whatever serves it
+/// signed blocks.
+#[async_trait]
+pub trait BlockSource {
+    async fn get_spv_block(&self, number: i64) -> Result<Block, Error>;
+}
+
+/// Unsigned blocks, which pass the hash checks but not the signature checks of an epoch change
+/// proof: `build_epoch_change_proof` fails with them
+#[async_trait]
+impl BlockSource for IOST {
+    async fn get_spv_block(&self, number: i64) -> Result<Block, Error> {
+        let rsp: BlockByNumber = self.get(&format!("getBlockByNumber/{}/true", number)).await?;
+        rsp.block.to_spv_block()
+    }
+}
+
+#[async_trait]
+impl<'a, T: BlockSource + Sync + ?Sized> BlockSource for &'a T {
+    async fn get_spv_block(&self, number: i64) -> Result<Block, Error> {
+        (**self).get_spv_block(number).await
+    }
+}
+
+/// Fetch the vote block `vote_block_number` and the fewest following blocks confirming it,
+/// and check the proof against `verify` before returning it
+pub async fn build_epoch_change_proof<S>(source: &S, verify: &Verify, vote_block_number: i64) -> Result<EpochChangeProof, Error>
+    where S: BlockSource + Sync
+{
+    if vote_block_number % VOTE_INTERVAL != 0 {
+        return Err(Error::Chain(iost_chain::Error::InvalidSPVStartBlock(vote_block_number)));
+    }
+    let producers = verify.producers_of(vote_block_number).map_err(Error::Chain)?;
+    let vote_block = source.get_spv_block(vote_block_number).await?;
+
+    let mut witness_blocks = Vec::new();
+    let mut confirmed: BTreeSet<String> = BTreeSet::new();
+    let mut number = vote_block_number;
+    while confirmed.len() < CONFIRM_WITNESS_NUM {
+        if number - vote_block_number >= MAX_WITNESS_BLOCKS {
+            return Err(Error::Chain(iost_chain::Error::IOSTBlockWitnessError(format!(
+                "block {} is not confirmed within {} blocks", vote_block_number, MAX_WITNESS_BLOCKS
+            ))));
+        }
+        number += 1;
+        let block = source.get_spv_block(number).await?;
+        if producers.contains(&block.head.witness) {
+            confirmed.insert(block.head.witness.clone());
+        }
+        witness_blocks.push(block);
+    }
+
+    let proof = EpochChangeProof::new(&vote_block, &witness_blocks).map_err(Error::Chain)?;
+    verify.verify_epoch_change(&proof).map_err(Error::Chain)?;
+    Ok(proof)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use iost_chain::spv::{merkle_root, Head, Sign, TxReceiptStatus, STAT_RECEIPT, VERIFIER_NUM};
+    use iost_chain::{Receipt, Signature, TxReceipt, SECP256K1_ID};
+    use iost_mock_node::{Fixtures, MockNode};
+
+    /// Unsigned blocks produced in turn by `witnesses`, counting the fetches
+    struct FakeSource {
+        witnesses: Vec<String>,
+        fetched: AtomicI64,
+    }
+
+    #[async_trait]
+    impl BlockSource for FakeSource {
+        async fn get_spv_block(&self, number: i64) -> Result<Block, Error> {
+            self.fetched.fetch_add(1, Ordering::SeqCst);
+            Ok(Block {
+                head: Head {
+                    version: 1,
+                    parent_hash: vec![],
+                    tx_merkle_hash: vec![],
+                    tx_receipt_merkle_hash: vec![],
+                    info: vec![],
+                    number,
+                    witness: self.witnesses[number as usize % self.witnesses.len()].clone(),
+                    time: 1598918258274417000,
+                },
+                sign: Sign {
+                    algorithm: 2,
+                    sig: "".to_string(),
+                    pub_key: "".to_string(),
+                },
+                receipts: vec![],
+                txs: vec![],
+                tx_hashes: vec![],
+                receipt_hashes: vec![],
+                block_type: "".to_string(),
+            })
+        }
+    }
+
+    fn producers() -> Vec<String> {
+        (0..17).map(|i| format!("producer{}", i)).collect()
+    }
+
+    fn producer_key(i: usize) -> Vec<u8> {
+        vec![i as u8 + 1; 32]
+    }
+
+    /// Witness of the producer `i` of the signed chain
+    fn producer(i: usize) -> String {
+        let signature = Signature::sign(b"", "SECP256K1", &producer_key(i)).unwrap();
+        bs58::encode(base64::decode(&signature.public_key).unwrap()).into_string()
+    }
+
+    fn elected() -> Vec<String> {
+        (VERIFIER_NUM..2 * VERIFIER_NUM).map(producer).collect()
+    }
+
+    fn stat_receipt() -> TxReceipt {
+        let content = serde_json::json!({
+            "pendingList": elected(),
+            "currentList": (0..VERIFIER_NUM).map(producer).collect::<Vec<_>>(),
+        });
+        TxReceipt {
+            tx_hash: base64::encode(&[1; 32]),
+            gas_usage: "3026000".to_string(),
+            ram_usage: Default::default(),
+            status: TxReceiptStatus { code: 0, message: "".to_string() },
+            returns: vec!["[]".to_string()],
+            receipts: vec![Receipt { func_name: STAT_RECEIPT.to_string(), content: content.to_string() }],
+        }
+    }
+
+    /// Blocks signed by their witnesses, the producers `0..VERIFIER_NUM` in turn, from the vote
+    /// block `VOTE_INTERVAL` electing the producers `VERIFIER_NUM..2 * VERIFIER_NUM`
+    struct SignedSource {
+        heads: Vec<Head>,
+    }
+
+    impl SignedSource {
+        fn new(len: usize) -> Self {
+            let mut heads: Vec<Head> = Vec::new();
+            for n in 0..len {
+                let receipts = if n == 0 { vec![stat_receipt()] } else { vec![] };
+                let tx_hashes: Vec<Vec<u8>> = receipts.iter().map(|r| r.tx_hash_bytes().unwrap()).collect();
+                let receipt_hashes: Vec<Vec<u8>> = receipts.iter().map(|r| r.hash().unwrap()).collect();
+                let number = VOTE_INTERVAL + n as i64;
+                heads.push(Head {
+                    version: 1,
+                    parent_hash: heads.last().map_or(vec![1; 32], Head::hash),
+                    tx_merkle_hash: merkle_root(&tx_hashes),
+                    tx_receipt_merkle_hash: merkle_root(&receipt_hashes),
+                    info: vec![],
+                    number,
+                    witness: producer(n % VERIFIER_NUM),
+                    time: 1598918258274417000 + number * 500_000_000,
+                });
+            }
+            SignedSource { heads }
+        }
+    }
+
+    #[async_trait]
+    impl BlockSource for SignedSource {
+        async fn get_spv_block(&self, number: i64) -> Result<Block, Error> {
+            let n = (number - VOTE_INTERVAL) as usize;
+            let head = self.heads.get(n).cloned()
+                .ok_or_else(|| Error::InvalidBlock(format!("no block {}", number)))?;
+            let signature = Signature::sign(&head.hash(), "SECP256K1", &producer_key(n % VERIFIER_NUM)).unwrap();
+            Ok(Block {
+                sign: Sign {
+                    algorithm: SECP256K1_ID,
+                    sig: signature.signature,
+                    pub_key: head.witness.clone(),
+                },
+                head,
+                receipts: if n == 0 { vec![stat_receipt()] } else { vec![] },
+                txs: vec![],
+                tx_hashes: vec![],
+                receipt_hashes: vec![],
+                block_type: "".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn proof_of_signed_blocks_should_be_built() {
+        let mut verify = Verify::with_epoch(0, (0..VERIFIER_NUM).map(producer).collect());
+        let source = SignedSource::new(1 + CONFIRM_WITNESS_NUM);
+        let proof = build_epoch_change_proof(&source, &verify, VOTE_INTERVAL).await.unwrap();
+        assert_eq!(proof.witness_blocks.len(), CONFIRM_WITNESS_NUM);
+        assert_eq!(proof.pending_list().unwrap(), elected());
+        verify.apply_epoch_change(&proof).unwrap();
+        assert_eq!(verify.producers_of(VOTE_INTERVAL + 1).unwrap(), &elected());
+
+        // the signed chain ends before the vote block is confirmed
+        let short = SignedSource::new(CONFIRM_WITNESS_NUM);
+        let verify = Verify::with_epoch(0, (0..VERIFIER_NUM).map(producer).collect());
+        assert!(build_epoch_change_proof(&short, &verify, VOTE_INTERVAL).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_fetch_the_fewest_witness_blocks() {
+        let verify = Verify::with_epoch(0, producers());
+        let source = FakeSource {
+            witnesses: producers(),
+            fetched: AtomicI64::new(0),
+        };
+        // the blocks are not signed, so the proof is rejected once assembled
+        assert!(build_epoch_change_proof(&source, &verify, VOTE_INTERVAL).await.is_err());
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 1 + CONFIRM_WITNESS_NUM as i64);
+    }
+
+    #[tokio::test]
+    async fn should_give_up_without_confirmations() {
+        let verify = Verify::with_epoch(0, producers());
+        let source = FakeSource {
+            witnesses: vec!["outsider".to_string()],
+            fetched: AtomicI64::new(0),
+        };
+        assert!(build_epoch_change_proof(&source, &verify, VOTE_INTERVAL).await.is_err());
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 1 + MAX_WITNESS_BLOCKS);
+
+        // not a vote block, or no producers known for its epoch
+        assert!(build_epoch_change_proof(&source, &verify, VOTE_INTERVAL + 1).await.is_err());
+        assert!(build_epoch_change_proof(&source, &verify, 2 * VOTE_INTERVAL).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn blocks_of_node_should_pass_hash_checks() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let client = IOST::new(&node.url());
+        let hashes = [
+            "2rc7jRvX6ahFV7ZzTMbpXT1LQ5LRMnoTQKpMU8La75W1",
+            "94bUeWP8VQq6p3HiZF24B48ExGnpnx15KbZiB7PPeVYC",
+            "AX4MMxyGuErQGf89idjjdDNr3b2JyFTSorEzcejGJCnP",
+            "57wbsUCTjWanYNWNUAH5vk3VoQJnvkWgfV84sR7Hcsj4",
+        ];
+        let mut parent_hash = vec![];
+        for (number, hash) in hashes.iter().enumerate() {
+            let block = client.get_spv_block(number as i64).await.unwrap();
+            assert_eq!(block.head.number, number as i64);
+            assert_eq!(block.head.hash(), bs58::decode(hash).into_vec().unwrap());
+            assert_eq!(block.head.parent_hash, parent_hash);
+            block.verify_hashes().unwrap();
+            parent_hash = block.head.hash();
+        }
+
+        // block 2 carries the transfer of the API documentation
+        let block = client.get_spv_block(2).await.unwrap();
+        assert_eq!(block.tx_hashes.len(), 1);
+        assert_eq!(
+            block.tx_hashes[0],
+            base64::encode(bs58::decode("CihRgNwEPjWnaXgogcFhK31r7EkWZyU9H6ZxzyWF37yK").into_vec().unwrap())
+        );
+        assert_eq!(block.receipts.len(), 1);
+        assert_eq!(block.receipts[0].tx_hash, block.tx_hashes[0]);
+        assert_eq!(block.receipts[0].gas_usage, "3879500");
+        assert_eq!(block.receipts[0].receipts[0].func_name, "token.iost/transfer");
+
+        assert!(client.get_spv_block(4).await.is_err());
+    }
+
+    /// The merkle hashes of the mainnet blocks are those go-iost computed, the fixtures only
+    /// check the code against itself. Needs the network: `cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn blocks_of_mainnet_should_pass_hash_checks() {
+        use crate::block_stream::ChainHeight;
+        let iost = IOST::new("https://api.iost.io");
+        let (_, lib) = iost.get_heights().await.unwrap();
+        let (mut even, mut odd) = (false, false);
+        for number in (lib - 10000..=lib).rev() {
+            let block = iost.get_spv_block(number).await.unwrap();
+            block.verify_hashes().unwrap();
+            match block.receipts.len() {
+                count if count >= 3 && count % 2 == 1 => odd = true,
+                count if count >= 4 => even = true,
+                _ => {}
+            }
+            if odd && even {
+                return;
+            }
+        }
+        panic!("no blocks of 3 txs or more before {}", lib);
+    }
+}
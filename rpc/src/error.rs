@@ -1,9 +1,39 @@
 use crate::message::ErrorMessage;
+use crate::status_code::StatusCode;
 
 #[derive(Debug)]
 pub enum Error {
     ///Error request message
     Reqwest(reqwest::Error),
     ///Error response message
-    ErrorMessage(ErrorMessage)
+    ErrorMessage(ErrorMessage),
+    ///The transaction was executed but did not succeed
+    TxFailed(StatusCode, String),
+    ///The transaction expired before being packed
+    TxExpired(String),
+    ///The block of the transaction did not become irreversible before the deadline
+    TxNotIrreversible(String),
+    ///A numeric string returned by the node could not be parsed
+    InvalidNumber(String),
+    ///A block returned by the node could not be converted into the block a light client checks
+    InvalidBlock(String),
+    ///The transaction cannot be sent as it is
+    InvalidTx(String),
+    ///Error from iost-chain
+    Chain(iost_chain::Error),
+    ///Stored data could not be decoded into the requested type
+    InvalidStorage(String),
+    ///A streamed event could not be parsed
+    InvalidEvent(String),
+    ///The node ended the event stream, with its error if any
+    SubscriptionClosed(String),
+    ///Error status returned by the gRPC API
+    #[cfg(feature = "grpc")]
+    Grpc(tonic::Status),
+    ///The gRPC endpoint could not be reached
+    #[cfg(feature = "grpc")]
+    GrpcTransport(tonic::transport::Error),
+    ///A request or a response could not be mapped between JSON and protobuf
+    #[cfg(feature = "grpc")]
+    GrpcMapping(String)
 }
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use iost_chain::{AmountLimit, Receipt, ReceiptEvent, Tx, MIN_GAS_LIMIT};
+
+use crate::error::Error;
+use crate::status_code::StatusCode;
+use crate::Client;
+
+/// Gas limit of the pre executed copy of the transaction
+pub const DRY_RUN_GAS_LIMIT: f64 = 1000000.0;
+/// Default factor applied to the pre executed gas usage
+pub const DEFAULT_GAS_HEADROOM: f64 = 1.2;
+
+#[derive(Debug)]
+pub struct ResourceEstimate {
+    /// GAS consumption of the pre executed transaction
+    pub gas_usage: f64,
+    /// gas usage with headroom, never lower than the minimum gas limit
+    pub gas_limit: f64,
+    /// median gas ratio of the most recently packed blocks, within [1.0, 100.0]
+    pub gas_ratio: f64,
+    /// RAM consumption of the pre executed transaction. map-key is account name, and value is RAM amount
+    pub ram_usage: HashMap<String, i64>,
+    /// minimal amount limits covering the tokens sent by the publisher and the signers
+    pub amount_limit: Vec<AmountLimit>,
+}
+
+impl ResourceEstimate {
+    /// Set gas limit, gas ratio and amount limits of an unsigned transaction
+    pub fn apply(&self, tx: &mut Tx) {
+        tx.gas_limit = self.gas_limit;
+        tx.gas_ratio = self.gas_ratio;
+        tx.amount_limit = self.amount_limit.clone();
+    }
+}
+
+/// Estimate of the resources of a transaction by a pre execution on the node, over any `Client`
+#[async_trait]
+pub trait ResourceEstimator: Client {
+    /// Pre execute `tx` on the node with `execTx` and estimate the resources it needs.
+    /// `tx.publisher` must be set, the pre executed copy is run without signatures and amount limits.
+    async fn estimate_resources(
+        &self,
+        tx: &Tx,
+        headroom: f64,
+    ) -> Result<ResourceEstimate, Error> {
+        if tx.publisher.is_empty() {
+            return Err(Error::InvalidTx(
+                "publisher is required to estimate resources".to_string(),
+            ));
+        }
+        let mut dry_run = tx.clone();
+        dry_run.gas_limit = dry_run.gas_limit.max(DRY_RUN_GAS_LIMIT);
+        dry_run.amount_limit = vec![AmountLimit::new("*".to_string(), "unlimited".to_string())];
+        dry_run.publisher_sigs = vec![];
+        dry_run.signatures = vec![];
+
+        let receipt = self.exec_tx(&dry_run).await?;
+        if receipt.status_code != StatusCode::SUCCESS {
+            return Err(Error::TxFailed(receipt.status_code, receipt.message));
+        }
+        let gas_ratio = self.get_gas_ratio().await?;
+
+        let mut transfers = vec![];
+        for r in receipt.receipts.iter() {
+            let receipt = Receipt {
+                func_name: r.func_name.clone(),
+                content: r.content.clone(),
+            };
+            if let ReceiptEvent::Transfer(transfer) = receipt.decode().map_err(Error::Chain)? {
+                transfers.push(transfer);
+            }
+        }
+        // signers are written as "account@permission"
+        let spenders: Vec<&str> = core::iter::once(tx.publisher.as_str())
+            .chain(tx.signers.iter().filter_map(|s| s.split('@').next()))
+            .collect();
+        let amount_limit =
+            AmountLimit::from_transfers(&spenders, &transfers).map_err(Error::Chain)?;
+
+        Ok(ResourceEstimate {
+            gas_usage: receipt.gas_usage,
+            gas_limit: (receipt.gas_usage * headroom).ceil().max(MIN_GAS_LIMIT),
+            gas_ratio: gas_ratio.median_gas_ratio.max(1.0).min(100.0),
+            ram_usage: receipt.ram_usage,
+            amount_limit,
+        })
+    }
+
+    /// Estimate the resources of an unsigned transaction with the default headroom and apply them to it
+    async fn prepare_tx(&self, tx: &mut Tx) -> Result<ResourceEstimate, Error> {
+        let estimate = self.estimate_resources(tx, DEFAULT_GAS_HEADROOM).await?;
+        estimate.apply(tx);
+        Ok(estimate)
+    }
+}
+
+impl<C: Client> ResourceEstimator for C {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::IOST;
+    use iost_chain::{IostAction, TxBuilder, MAINNET_CHAIN_ID};
+    use iost_mock_node::{Fixtures, MockNode};
+    use serde_json::json;
+
+    #[test]
+    fn estimate_should_be_applied() {
+        let estimate = ResourceEstimate {
+            gas_usage: 41000.0,
+            gas_limit: MIN_GAS_LIMIT,
+            gas_ratio: 1.0,
+            ram_usage: HashMap::new(),
+            amount_limit: vec![AmountLimit::new("iost".to_string(), "10".to_string())],
+        };
+        let mut tx = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time(1598918258274417000)
+            .publisher("admin")
+            .action(IostAction::transfer("admin", "lispczz3", "10", "").unwrap())
+            .build()
+            .unwrap();
+        estimate.apply(&mut tx);
+        assert_eq!(tx.gas_limit, MIN_GAS_LIMIT);
+        assert_eq!(tx.amount_limit.len(), 1);
+        assert_eq!(tx.amount_limit[0].token, "iost");
+    }
+
+    #[tokio::test]
+    async fn resources_should_be_estimated_by_mock_node() {
+        let mut fixtures = Fixtures::builtin();
+        fixtures.gas_ratio = json!({ "lowest_gas_ratio": 1.0, "median_gas_ratio": 1.5 });
+        // buying RAM transfers IOST to ram.iost, which the actions alone do not show
+        fixtures.exec_receipt = json!({
+            "tx_hash": "",
+            "gas_usage": 62345.0,
+            "ram_usage": { "admin": 261 },
+            "status_code": "SUCCESS",
+            "message": "",
+            "returns": ["[]", "[]"],
+            "receipts": [
+                { "func_name": "token.iost/transfer", "content": "[\"iost\",\"admin\",\"lispczz3\",\"10\",\"\"]" },
+                { "func_name": "token.iost/transfer", "content": "[\"iost\",\"admin\",\"ram.iost\",\"3.25\",\"\"]" },
+                { "func_name": "token.iost/transfer", "content": "[\"iost\",\"lispczz3\",\"admin\",\"1\",\"\"]" },
+            ],
+        });
+        let node = MockNode::start(fixtures).unwrap();
+        let client = IOST::new(&node.url());
+        let mut tx = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time(1598918258274417000)
+            .publisher("admin")
+            .action(IostAction::transfer("admin", "lispczz3", "10", "").unwrap())
+            .action(IostAction::new(
+                "ram.iost".to_string(),
+                "buy".to_string(),
+                r#"["admin","admin",1024]"#.to_string(),
+            ))
+            .build()
+            .unwrap();
+
+        let estimate = client.prepare_tx(&mut tx).await.unwrap();
+        assert_eq!(estimate.gas_usage, 62345.0);
+        assert_eq!(estimate.gas_limit, 74814.0);
+        assert_eq!(estimate.gas_ratio, 1.5);
+        assert_eq!(estimate.ram_usage["admin"], 261);
+        assert_eq!(estimate.amount_limit.len(), 1);
+        assert_eq!(estimate.amount_limit[0].token, "iost");
+        assert_eq!(estimate.amount_limit[0].value, "13.25");
+        assert_eq!(tx.gas_limit, 74814.0);
+        assert_eq!(tx.gas_ratio, 1.5);
+        assert_eq!(tx.amount_limit[0].value, "13.25");
+        assert!(node.sent_txs().is_empty());
+    }
+}
@@ -44,11 +44,13 @@ async fn get_account(domain: &str, account: &str, complete: bool) -> Result<Acco
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_account_should_be_ok() {
-        let response = get_account("http://api.iost.io","admin",true).await;
-        assert!(response.is_ok());
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_account(&node.url(),"admin",true).await.unwrap();
+        assert_eq!(response.name, "admin");
     }
 }
 
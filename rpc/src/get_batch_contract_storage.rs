@@ -2,7 +2,7 @@ use crate::error::Error;
 use crate::key_field::KeyField;
 use serde::{Serialize, Deserialize};
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct BatchContractStoragePost {
     /// smart contract ID
     pub id: String,
@@ -22,9 +22,9 @@ pub struct BatchContractStorage {
     pub block_number: String
 }
 
-async fn get_batch_contract_storage(new_post: BatchContractStoragePost) -> Result<BatchContractStorage, Error> {
+async fn get_batch_contract_storage(domain: &str, new_post: BatchContractStoragePost) -> Result<BatchContractStorage, Error> {
     let req = reqwest::Client::new()
-        .post("http://api.iost.io/getBatchContractStorage")
+        .post(&format!("{}/getBatchContractStorage", domain))
         .json(&new_post)
         .send()
         .await.map_err(Error::Reqwest)?;
@@ -41,17 +41,19 @@ async fn get_batch_contract_storage(new_post: BatchContractStoragePost) -> Resul
 #[cfg(test)]
 mod test {
     use super::*;
-    
+    use iost_mock_node::{Fixtures, MockNode};
+
     #[tokio::test]
     async fn get_batch_contract_storage_should_be_ok() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
         let key = KeyField {
-            key: "supply".to_string(),
-            field: "TIiost".to_string()
+            key: "TIiost".to_string(),
+            field: "supply".to_string()
         };
 
         let key1 = KeyField {
-            key: "decimal".to_string(),
-            field: "TIiost".to_string()
+            key: "TIiost".to_string(),
+            field: "decimal".to_string()
         };
 
         let new_post = BatchContractStoragePost {
@@ -60,8 +62,8 @@ mod test {
             by_longest_chain: true
         };
 
-        let res = get_batch_contract_storage(new_post).await;
-        assert!(res.is_ok());
+        let res = get_batch_contract_storage(&node.url(), new_post).await;
+        assert_eq!(res.unwrap().datas, vec!["2100000000000000000", "8"]);
     }
 }
 
@@ -47,16 +47,20 @@ async fn get_block_by_number(domain: &str, number: i32, complete: bool) -> Resul
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_block_by_hash_should_be_ok() {
-        let response = get_block_by_hash("http://api.iost.io","GexerugLra5qBArG4vqCAFNX1F7WzLzpPdmzcjLBAi3k",false).await;
-        assert!(response.is_ok());
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_block_by_hash(&node.url(),"57wbsUCTjWanYNWNUAH5vk3VoQJnvkWgfV84sR7Hcsj4",false).await;
+        assert_eq!(response.unwrap().block.number, "3");
     }
 
     #[tokio::test]
     async fn get_block_by_number_should_be_ok() {
-        let response = get_block_by_number("http://api.iost.io",3,false).await;
-        assert!(response.is_ok());
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_block_by_number(&node.url(),2,true).await.unwrap();
+        assert_eq!(response.block.transactions[0].hash, "CihRgNwEPjWnaXgogcFhK31r7EkWZyU9H6ZxzyWF37yK");
+        assert!(get_block_by_number(&node.url(),4,false).await.is_err());
     }
 }
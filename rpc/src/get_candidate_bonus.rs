@@ -23,10 +23,12 @@ async fn get_candidate_bonus(domain: &str, name: &str, by_longest_chain: bool) -
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_candidate_bonus_should_be_ok() {
-        let response = get_candidate_bonus("http://api.iost.io","erebus",true).await;
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_candidate_bonus(&node.url(),"admin",true).await;
         assert!(response.is_ok());
     }
 }
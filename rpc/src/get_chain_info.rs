@@ -28,8 +28,8 @@ pub struct ChainInfo {
     pub lib_block_time: String
 }
 
-async fn get_chain_info() -> ChainInfo {
-    let res = reqwest::get("https://api.iost.io/getChainInfo").await.unwrap()
+async fn get_chain_info(domain: &str) -> ChainInfo {
+    let res = reqwest::get(&format!("{}/getChainInfo", domain)).await.unwrap()
         .json::<ChainInfo>()
         .await.unwrap();
     res
@@ -38,11 +38,13 @@ async fn get_chain_info() -> ChainInfo {
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_chain_info_should_be_ok() {
-
-        println!("{:#?}", get_chain_info().await);
-
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let chain_info = get_chain_info(&node.url()).await;
+        assert_eq!(chain_info.chain_id, 1024);
+        assert_eq!(chain_info.head_block, "3");
     }
 }
@@ -1,7 +1,9 @@
 use crate::get_abi::ABI;
 use crate::error::Error;
 use crate::message::ErrorMessage;
+use iost_chain::IostAction;
 use serde::{Deserialize};
+use serde_json::Value;
 
 #[derive(Deserialize, Debug)]
 pub struct Contract {
@@ -17,6 +19,35 @@ pub struct Contract {
     pub abis: Vec<ABI>
 }
 
+impl From<&ABI> for iost_chain::ABI {
+    fn from(abi: &ABI) -> Self {
+        iost_chain::ABI {
+            name: abi.name.clone(),
+            args: abi.args.clone(),
+            amount_limit: abi.amount_limit.iter()
+                .map(|limit| iost_chain::AmountLimit::new(limit.token.clone(), limit.value.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl Contract {
+    /// The ABIs of the contract, to encode and decode the data of its actions
+    pub fn chain_abis(&self) -> Vec<iost_chain::ABI> {
+        self.abis.iter().map(iost_chain::ABI::from).collect()
+    }
+
+    /// Call of the ABI `action_name` of the contract, the arguments checked against the ABI
+    pub fn action(&self, action_name: &str, args: &[Value]) -> Result<IostAction, Error> {
+        IostAction::from_abi(&self.id, &self.chain_abis(), action_name, args).map_err(Error::Chain)
+    }
+
+    /// Arguments of a call of the contract, checked against the ABI
+    pub fn decode_args(&self, action: &IostAction) -> Result<Vec<Value>, Error> {
+        action.decode_args(&self.chain_abis()).map_err(Error::Chain)
+    }
+}
+
 async fn get_contract(domain: &str, id: &str, by_longest_chain: bool) -> Result<Contract, Error> {
     let url = format!("{}/getContract/{}/{}", domain, id, by_longest_chain);
     let req = reqwest::get(&url).await.map_err(Error::Reqwest)?;
@@ -32,10 +63,42 @@ async fn get_contract(domain: &str, id: &str, by_longest_chain: bool) -> Result<
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{Client, IOST};
+    use iost_chain::AbiError;
+    use iost_mock_node::{Fixtures, MockNode};
+    use serde_json::json;
 
     #[tokio::test]
     async fn get_contract_should_be_ok() {
-        let response = get_contract("http://api.iost.io","base.iost",true).await;
-        assert!(response.is_ok());
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_contract(&node.url(),"base.iost",true).await.unwrap();
+        assert_eq!(response.abis.len(), 3);
+        assert!(get_contract(&node.url(),"Contract1234",true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn contract_action_should_follow_abi() {
+        let mut fixtures = Fixtures::builtin();
+        fixtures.contracts.insert("Contract1234".to_string(), json!({
+            "id": "Contract1234",
+            "code": "",
+            "language": "javascript",
+            "version": "1.0.0",
+            "abis": [
+                { "name": "vote", "args": ["string", "number"], "amount_limit": [] },
+            ],
+        }));
+        let node = MockNode::start(fixtures).unwrap();
+        let contract = IOST::new(&node.url()).get_contract("Contract1234", true).await.unwrap();
+
+        let action = contract.action("vote", &[json!("admin"), json!(3)]).unwrap();
+        assert_eq!(action.data, br#"["admin",3]"#.to_vec());
+        assert_eq!(contract.decode_args(&action).unwrap(), vec![json!("admin"), json!(3)]);
+        match contract.action("vote", &[json!("admin"), json!("3")]) {
+            Err(Error::Chain(iost_chain::Error::AbiErr(AbiError::TypeMismatch { index, .. }))) => assert_eq!(index, 1),
+            r => panic!("unexpected {:?}", r),
+        }
+        assert!(contract.action("vote", &[json!("admin")]).is_err());
+        assert!(contract.action("unvote", &[]).is_err());
     }
 }
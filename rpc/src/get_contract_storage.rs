@@ -1,7 +1,7 @@
 use crate::error::Error;
 use serde::{Serialize, Deserialize};
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct ContractStoragePost {
     /// ID of the smart contract
     pub id: String,
@@ -23,9 +23,9 @@ pub struct ContractStorage {
     pub block_number: String
 }
 
-async fn get_contract_storage(new_post: ContractStoragePost) -> Result<ContractStorage, Error>  {
+async fn get_contract_storage(domain: &str, new_post: ContractStoragePost) -> Result<ContractStorage, Error>  {
     let res_status = reqwest::Client::new()
-        .post("http://api.iost.io/getContractStorage")
+        .post(&format!("{}/getContractStorage", domain))
         .json(&new_post)
         .send()
         .await.map_err(Error::Reqwest)?;
@@ -42,9 +42,11 @@ async fn get_contract_storage(new_post: ContractStoragePost) -> Result<ContractS
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_contract_storage_should_be_ok () {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
         let new_post = ContractStoragePost {
             id: "token.iost".to_string(),
             key: "TIiost".to_string(),
@@ -52,8 +54,8 @@ mod test {
             by_longest_chain: true
         };
 
-        let res = get_contract_storage(new_post).await;
-        assert!(res.is_ok());
+        let res = get_contract_storage(&node.url(), new_post).await;
+        assert_eq!(res.unwrap().data, "8");
     }
 }
 
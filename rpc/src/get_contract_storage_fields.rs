@@ -1,7 +1,7 @@
 use crate::error::Error;
 use serde::{Serialize, Deserialize};
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct ContractStorageFieldsPost {
     /// ID of the smart contract
     pub id: String,
@@ -18,9 +18,9 @@ pub struct ContractStorageFields {
 
 }
 
-async fn get_contract_storage_fields(new_post: ContractStorageFieldsPost) -> Result<ContractStorageFields, Error> {
+async fn get_contract_storage_fields(domain: &str, new_post: ContractStorageFieldsPost) -> Result<ContractStorageFields, Error> {
     let req = reqwest::Client::new()
-        .post("http://api.iost.io/getContractStorageFields")
+        .post(&format!("{}/getContractStorageFields", domain))
         .json(&new_post)
         .send()
         .await.map_err(Error::Reqwest)?;
@@ -39,17 +39,19 @@ async fn get_contract_storage_fields(new_post: ContractStorageFieldsPost) -> Res
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_contract_storage_fields_should_be_ok() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
         let new_post = ContractStorageFieldsPost {
             id: "token.iost".to_string(),
             key: "TIiost".to_string(),
             by_longest_chain: true
         };
 
-        let res = get_contract_storage_fields(new_post).await;
-        assert!(res.is_ok());
+        let res = get_contract_storage_fields(&node.url(), new_post).await;
+        assert_eq!(res.unwrap().fields, vec!["decimal", "fullName", "issuer", "supply", "totalSupply"]);
     }
 }
 
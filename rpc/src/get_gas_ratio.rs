@@ -8,8 +8,8 @@ pub struct GasRatio {
     pub median_gas_ratio: f64
 }
 
-async fn get_gas_ratio() -> GasRatio{
-    let res = reqwest::get("https://api.iost.io/getGasRatio").await.unwrap()
+async fn get_gas_ratio(domain: &str) -> GasRatio{
+    let res = reqwest::get(&format!("{}/getGasRatio", domain)).await.unwrap()
         .json::<GasRatio>()
         .await.unwrap();
     res
@@ -19,11 +19,12 @@ async fn get_gas_ratio() -> GasRatio{
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_gas_ratio_should_be_ok() {
-
-        println!("{:#?}", get_gas_ratio().await);
-
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let gas_ratio = get_gas_ratio(&node.url()).await;
+        assert_eq!(gas_ratio.lowest_gas_ratio, 1.0);
     }
 }
@@ -21,8 +21,8 @@ pub struct NodeInfo {
     pub server_time: String,
 }
 
-async fn get_node_info () -> Result<NodeInfo, Error>  {
-    let req = reqwest::get("https://api.iost.io/getNodeInfo").await.map_err(Error::Reqwest)?;
+async fn get_node_info (domain: &str) -> Result<NodeInfo, Error>  {
+    let req = reqwest::get(&format!("{}/getNodeInfo", domain)).await.map_err(Error::Reqwest)?;
     if req.status() == 200 {
         let rsp = req.json::<NodeInfo>().await.map_err(Error::Reqwest)?;
         Ok(rsp)
@@ -35,12 +35,13 @@ async fn get_node_info () -> Result<NodeInfo, Error>  {
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_node_info_should_be_ok() {
-
-        println!("{:#?}", get_node_info().await);
-
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_node_info(&node.url()).await.unwrap();
+        assert_eq!(response.network.peer_count, 0);
     }
 }
 
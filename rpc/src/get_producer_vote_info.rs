@@ -38,10 +38,14 @@ async fn get_producer_vote_info(domain: &str, id: &str, by_longest_chain: bool)
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_producer_vote_info_should_be_ok() {
-        let response = get_producer_vote_info("http://api.iost.io","producerName",true).await;
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_producer_vote_info(&node.url(),"admin",true).await.unwrap();
+        assert!(response.is_producer);
+        let response = get_producer_vote_info(&node.url(),"producerName",true).await;
         assert!(response.is_err());
     }
 }
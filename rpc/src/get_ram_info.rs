@@ -14,8 +14,8 @@ pub struct RamInfo {
     pub sell_price: f64
 }
 
-async fn get_ram_info() -> RamInfo {
-    let res = reqwest::get("https://api.iost.io/getRAMInfo").await.unwrap()
+async fn get_ram_info(domain: &str) -> RamInfo {
+    let res = reqwest::get(&format!("{}/getRAMInfo", domain)).await.unwrap()
         .json::<RamInfo>()
         .await.unwrap();
     res
@@ -24,11 +24,12 @@ async fn get_ram_info() -> RamInfo {
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_ram_info_should_be_ok() {
-
-        println!("{:#?}", get_ram_info().await);
-
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let ram_info = get_ram_info(&node.url()).await;
+        assert_eq!(ram_info.total_ram, "137438953472");
     }
 }
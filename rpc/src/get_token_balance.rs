@@ -1,9 +1,9 @@
 use crate::error::Error;
 use crate::frozen_balance::FrozenBalance;
 use crate::message::ErrorMessage;
-use serde::{Deserialize};
+use serde::{Serialize, Deserialize};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct TokenBalance {
     /// balance
     pub balance: f64,
@@ -26,10 +26,12 @@ async fn get_token_balance(domain: &str, account: &str, token: &str, by_longest_
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_token_balance_should_be_ok() {
-        let response = get_token_balance("http://api.iost.io","admin","iost",true).await;
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_token_balance(&node.url(),"admin","iost",true).await;
         assert!(response.is_ok());
     }
 }
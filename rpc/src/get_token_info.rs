@@ -41,10 +41,13 @@ async fn get_token_info(domain: &str, symbol: &str, by_longest_chain: bool) -> R
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_token_info_should_be_ok() {
-        let response = get_token_info("http://api.iost.io","iost",true).await;
-        assert!(response.is_ok());
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_token_info(&node.url(),"iost",true).await.unwrap();
+        assert_eq!(response.decimal, 8);
+        assert_eq!(response.issuer, "token.iost");
     }
 }
\ No newline at end of file
@@ -29,11 +29,16 @@ async fn get_tx_by_hash_info(domain: &str, hash: &str) -> Result<GetTxByHash, Er
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_tx_by_hash_info_should_be_ok (){
-        let response: Result<GetTxByHash, Error> = get_tx_by_hash_info("http://api.iost.io","Dj8bmA4Fx4LHrwLtDB6EEkNbBFU8biENxf55mNaJewYw").await;
-        assert!(response.is_ok());
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response: Result<GetTxByHash, Error> = get_tx_by_hash_info(&node.url(),"CihRgNwEPjWnaXgogcFhK31r7EkWZyU9H6ZxzyWF37yK").await;
+        let response = response.unwrap();
+        assert_eq!(response.block_number, "2");
+        assert_eq!(response.transaction.tx_receipt.tx_hash, "CihRgNwEPjWnaXgogcFhK31r7EkWZyU9H6ZxzyWF37yK");
+        assert!(get_tx_by_hash_info(&node.url(),"Dj8bmA4Fx4LHrwLtDB6EEkNbBFU8biENxf55mNaJewYw").await.is_err());
     }
 }
 
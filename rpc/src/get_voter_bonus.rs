@@ -26,11 +26,14 @@ async fn get_voter_bonus(domain: &str, name: &str, by_longest_chain: bool) -> Re
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn get_voter_bonus_should_be_ok() {
-        let response = get_voter_bonus("http://api.iost.io", "admin", true).await;
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_voter_bonus(&node.url(), "admin", true).await;
         assert!(response.is_ok());
+        assert!(get_voter_bonus(&node.url(), "nobody", true).await.is_err());
     }
 }
 
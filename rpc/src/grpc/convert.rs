@@ -0,0 +1,466 @@
+use std::convert::TryFrom;
+
+use iost_chain::{algorithm_id, spv, AmountLimit, IostAction, Transaction, Tx, TxReceiptData};
+use serde_json::{json, Value};
+
+use crate::block::{decode_hash, head_infos, unsigned_block};
+use crate::error::Error;
+use crate::grpc::pb;
+
+const STATUS_CODES: [&str; 9] = [
+    "SUCCESS",
+    "GAS_RUN_OUT",
+    "BALANCE_NOT_ENOUGH",
+    "WRONG_PARAMETER",
+    "RUNTIME_ERROR",
+    "TIMEOUT",
+    "WRONG_TX_FORMAT",
+    "DUPLICATE_SET_CODE",
+    "UNKNOWN_ERROR",
+];
+const TX_STATUSES: [&str; 3] = ["PENDING", "PACKED", "IRREVERSIBLE"];
+const BLOCK_STATUSES: [&str; 2] = ["PENDING", "IRREVERSIBLE"];
+
+impl From<&IostAction> for pb::Action {
+    fn from(action: &IostAction) -> Self {
+        pb::Action {
+            contract: String::from_utf8_lossy(&action.contract).into_owned(),
+            action_name: String::from_utf8_lossy(&action.action_name).into_owned(),
+            data: String::from_utf8_lossy(&action.data).into_owned(),
+        }
+    }
+}
+
+impl From<pb::Action> for IostAction {
+    fn from(action: pb::Action) -> Self {
+        IostAction::new(action.contract, action.action_name, action.data)
+    }
+}
+
+impl From<&AmountLimit> for pb::AmountLimit {
+    fn from(limit: &AmountLimit) -> Self {
+        pb::AmountLimit {
+            token: limit.token.clone(),
+            value: limit.value.clone(),
+        }
+    }
+}
+
+impl From<pb::AmountLimit> for AmountLimit {
+    fn from(limit: pb::AmountLimit) -> Self {
+        AmountLimit::new(limit.token, limit.value)
+    }
+}
+
+impl TryFrom<&iost_chain::Signature> for pb::Signature {
+    type Error = iost_chain::Error;
+
+    /// The algorithm goes by its id and the base64 signature and public key as raw bytes
+    fn try_from(signature: &iost_chain::Signature) -> Result<Self, Self::Error> {
+        let invalid = |_| iost_chain::Error::InvalidSignature();
+        Ok(pb::Signature {
+            algorithm: algorithm_id(&signature.algorithm).ok_or_else(iost_chain::Error::InvalidSignature)? as i32,
+            signature: base64::decode(&signature.signature).map_err(invalid)?,
+            public_key: base64::decode(&signature.public_key).map_err(invalid)?,
+        })
+    }
+}
+
+impl TryFrom<&Tx> for pb::TransactionRequest {
+    type Error = iost_chain::Error;
+
+    fn try_from(tx: &Tx) -> Result<Self, Self::Error> {
+        Ok(pb::TransactionRequest {
+            time: tx.time,
+            expiration: tx.expiration,
+            gas_ratio: tx.gas_ratio,
+            gas_limit: tx.gas_limit,
+            delay: tx.delay,
+            chain_id: tx.chain_id,
+            actions: tx.actions.iter().map(pb::Action::from).collect(),
+            amount_limit: tx.amount_limit.iter().map(pb::AmountLimit::from).collect(),
+            signers: tx.signers.clone(),
+            signatures: tx.signatures.iter().map(pb::Signature::try_from).collect::<Result<_, _>>()?,
+            publisher: tx.publisher.clone(),
+            publisher_sigs: tx.publisher_sigs.iter().map(pb::Signature::try_from).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// A packed transaction, without its signatures which the node does not return
+impl From<pb::Transaction> for Transaction {
+    fn from(tx: pb::Transaction) -> Self {
+        Transaction {
+            hash: tx.hash,
+            time: tx.time,
+            expiration: tx.expiration,
+            gas_ratio: tx.gas_ratio,
+            gas_limit: tx.gas_limit,
+            delay: tx.delay,
+            chain_id: tx.chain_id as i32,
+            actions: tx.actions.into_iter().map(IostAction::from).collect(),
+            signers: tx.signers,
+            publisher: tx.publisher,
+            referred_tx: tx.referred_tx,
+            amount_limit: tx.amount_limit.into_iter().map(AmountLimit::from).collect(),
+            signatures: vec![],
+        }
+    }
+}
+
+impl TryFrom<&pb::TxReceipt> for TxReceiptData {
+    type Error = iost_chain::Error;
+
+    /// The fields of the receipt as the node hashes them, the tx hash being base58 encoded
+    fn try_from(receipt: &pb::TxReceipt) -> Result<Self, Self::Error> {
+        let tx_hash = bs58::decode(&receipt.tx_hash).into_vec().map_err(|_| {
+            iost_chain::Error::IOSTBlockVerifyError(format!("invalid tx hash {}", receipt.tx_hash))
+        })?;
+        let mut ram_usage: Vec<(String, i64)> = receipt.ram_usage.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        ram_usage.sort();
+        Ok(TxReceiptData {
+            tx_hash,
+            gas_usage: (receipt.gas_usage * 100.0).round() as i64,
+            ram_usage,
+            status_code: receipt.status_code,
+            message: receipt.message.clone(),
+            returns: receipt.returns.clone(),
+            receipts: receipt.receipts.iter().map(|r| (r.func_name.clone(), r.content.clone())).collect(),
+        })
+    }
+}
+
+// JSON of the messages as the HTTP gateway of the node writes them: 64 bits integers as strings,
+// enums by name and integral doubles without a fraction, so that they deserialize into the same
+// types as the HTTP responses
+
+fn double(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < (1u64 << 53) as f64 {
+        json!(value as i64)
+    } else {
+        json!(value)
+    }
+}
+
+fn name(names: &[&str], value: i32) -> Value {
+    match names.get(value as usize) {
+        Some(name) => json!(name),
+        None => json!(value.to_string()),
+    }
+}
+
+pub(crate) fn node_info(r: pb::NodeInfoResponse) -> Value {
+    let network = r.network.unwrap_or_default();
+    json!({
+        "build_time": r.build_time,
+        "git_hash": r.git_hash,
+        "mode": r.mode,
+        "network": { "id": network.id, "peer_count": network.peer_count },
+        "code_version": r.code_version,
+        "server_time": r.server_time.to_string(),
+    })
+}
+
+pub(crate) fn ram_info(r: pb::RamInfoResponse) -> Value {
+    json!({
+        "used_ram": r.used_ram.to_string(),
+        "available_ram": r.available_ram.to_string(),
+        "total_ram": r.total_ram.to_string(),
+        "sell_price": double(r.sell_price),
+        "buy_price": double(r.buy_price),
+    })
+}
+
+pub(crate) fn chain_info(r: pb::ChainInfoResponse) -> Value {
+    json!({
+        "net_name": r.net_name,
+        "protocol_version": r.protocol_version,
+        "chain_id": r.chain_id,
+        "head_block": r.head_block.to_string(),
+        "head_block_hash": r.head_block_hash,
+        "lib_block": r.lib_block.to_string(),
+        "lib_block_hash": r.lib_block_hash,
+        "witness_list": r.witness_list,
+        "lib_witness_list": r.lib_witness_list,
+        "pending_witness_list": r.pending_witness_list,
+        "head_block_time": r.head_block_time.to_string(),
+        "lib_block_time": r.lib_block_time.to_string(),
+    })
+}
+
+pub(crate) fn gas_ratio(r: pb::GasRatioResponse) -> Value {
+    json!({
+        "lowest_gas_ratio": double(r.lowest_gas_ratio),
+        "median_gas_ratio": double(r.median_gas_ratio),
+    })
+}
+
+fn spv_receipt(r: &pb::TxReceipt) -> Result<iost_chain::TxReceipt, Error> {
+    Ok(iost_chain::TxReceipt {
+        tx_hash: base64::encode(decode_hash(&r.tx_hash)?),
+        gas_usage: ((r.gas_usage * 100.0).round() as i64).to_string(),
+        ram_usage: r.ram_usage.iter().map(|(account, usage)| (account.clone(), usage.to_string())).collect(),
+        status: spv::TxReceiptStatus {
+            code: r.status_code,
+            message: r.message.clone(),
+        },
+        returns: r.returns.clone(),
+        receipts: r.receipts.iter().map(|r| iost_chain::Receipt {
+            func_name: r.func_name.clone(),
+            content: r.content.clone(),
+        }).collect(),
+    })
+}
+
+/// The block as a light client checks it, failing unless its head hashes to `hash`. As over the
+/// JSON API, neither the signature of the witness nor those of the transactions are returned
+pub(crate) fn spv_block(block: pb::Block) -> Result<spv::Block, Error> {
+    let info = block.info.unwrap_or_default();
+    let head = spv::Head {
+        version: block.version,
+        parent_hash: decode_hash(&block.parent_hash)?,
+        tx_merkle_hash: decode_hash(&block.tx_merkle_hash)?,
+        tx_receipt_merkle_hash: decode_hash(&block.tx_receipt_merkle_hash)?,
+        info: vec![],
+        number: block.number,
+        witness: block.witness,
+        time: block.time,
+    };
+    let tx_hashes = block.transactions.iter()
+        .map(|tx| decode_hash(&tx.hash).map(base64::encode))
+        .collect::<Result<Vec<_>, _>>()?;
+    let receipts = block.transactions.iter()
+        .map(|tx| match tx.tx_receipt {
+            Some(ref receipt) => spv_receipt(receipt),
+            None => Err(Error::InvalidBlock(format!("tx {} has no receipt", tx.hash))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    unsigned_block(&block.hash, head, head_infos(info.mode, info.thread, &info.batch_index), tx_hashes, receipts)
+}
+
+pub(crate) fn tx_receipt(r: pb::TxReceipt) -> Value {
+    json!({
+        "tx_hash": r.tx_hash,
+        "gas_usage": double(r.gas_usage),
+        "ram_usage": r.ram_usage.into_iter().map(|(k, v)| (k, v.to_string())).collect::<serde_json::Map<_, _>>(),
+        "status_code": name(&STATUS_CODES, r.status_code),
+        "message": r.message,
+        "returns": r.returns,
+        "receipts": r.receipts.into_iter().map(|r| json!({
+            "func_name": r.func_name,
+            "content": r.content,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn transaction(tx: pb::Transaction) -> Value {
+    json!({
+        "hash": tx.hash,
+        "time": tx.time.to_string(),
+        "expiration": tx.expiration.to_string(),
+        "gas_ratio": double(tx.gas_ratio),
+        "gas_limit": double(tx.gas_limit),
+        "delay": tx.delay.to_string(),
+        "chain_id": tx.chain_id,
+        "actions": tx.actions.into_iter().map(|a| json!({
+            "contract": a.contract,
+            "action_name": a.action_name,
+            "data": a.data,
+        })).collect::<Vec<_>>(),
+        "signers": tx.signers,
+        "publisher": tx.publisher,
+        "referred_tx": tx.referred_tx,
+        "amount_limit": tx.amount_limit.into_iter().map(|l| json!({
+            "token": l.token,
+            "value": l.value,
+        })).collect::<Vec<_>>(),
+        "tx_receipt": tx_receipt(tx.tx_receipt.unwrap_or_default()),
+    })
+}
+
+pub(crate) fn tx_response(r: pb::TransactionResponse) -> Value {
+    json!({
+        "status": name(&TX_STATUSES, r.status),
+        "transaction": transaction(r.transaction.unwrap_or_default()),
+        "block_number": r.block_number.to_string(),
+    })
+}
+
+pub(crate) fn block_response(r: pb::BlockResponse) -> Value {
+    let block = r.block.unwrap_or_default();
+    let info = block.info.unwrap_or_default();
+    json!({
+        "status": name(&BLOCK_STATUSES, r.status),
+        "block": {
+            "hash": block.hash,
+            "version": block.version.to_string(),
+            "parent_hash": block.parent_hash,
+            "tx_merkle_hash": block.tx_merkle_hash,
+            "tx_receipt_merkle_hash": block.tx_receipt_merkle_hash,
+            "number": block.number.to_string(),
+            "witness": block.witness,
+            "time": block.time.to_string(),
+            "gas_usage": double(block.gas_usage),
+            "tx_count": block.tx_count.to_string(),
+            "info": {
+                "mode": info.mode,
+                "thread": info.thread,
+                "batch_index": info.batch_index,
+            },
+            "transactions": block.transactions.into_iter().map(transaction).collect::<Vec<_>>(),
+        },
+    })
+}
+
+pub(crate) fn token_balance(r: pb::GetTokenBalanceResponse) -> Value {
+    json!({
+        "balance": double(r.balance),
+        "frozen_balances": frozen_balances(r.frozen_balances),
+    })
+}
+
+fn frozen_balances(balances: Vec<pb::FrozenBalance>) -> Vec<Value> {
+    balances.into_iter().map(|f| json!({
+        "amount": double(f.amount),
+        "time": f.time.to_string(),
+    })).collect()
+}
+
+fn items(items: Vec<pb::account::Item>) -> Vec<Value> {
+    items.into_iter().map(|i| json!({
+        "id": i.id,
+        "is_key_pair": i.is_key_pair,
+        "weight": i.weight.to_string(),
+        "permission": i.permission,
+    })).collect()
+}
+
+pub(crate) fn account(r: pb::Account) -> Value {
+    let gas_info = r.gas_info.unwrap_or_default();
+    let ram_info = r.ram_info.unwrap_or_default();
+    json!({
+        "name": r.name,
+        "balance": double(r.balance),
+        "gas_info": {
+            "current_total": double(gas_info.current_total),
+            "transferable_gas": double(gas_info.transferable_gas),
+            "pledge_gas": double(gas_info.pledge_gas),
+            "increase_speed": double(gas_info.increase_speed),
+            "limit": double(gas_info.limit),
+            "pledged_info": gas_info.pledged_info.into_iter().map(|p| json!({
+                "pledger": p.pledger,
+                "amount": double(p.amount),
+            })).collect::<Vec<_>>(),
+        },
+        "ram_info": {
+            "available": ram_info.available.to_string(),
+            "used": ram_info.used.to_string(),
+            "total": ram_info.total.to_string(),
+        },
+        "permissions": r.permissions.into_iter().map(|(name, p)| (name, json!({
+            "name": p.name,
+            "group_names": p.group_names,
+            "items": items(p.items),
+            "threshold": p.threshold.to_string(),
+        }))).collect::<serde_json::Map<_, _>>(),
+        "groups": r.groups.into_iter().map(|(name, g)| (name, json!({
+            "name": g.name,
+            "items": items(g.items),
+        }))).collect::<serde_json::Map<_, _>>(),
+        "frozen_balances": frozen_balances(r.frozen_balances),
+        "vote_infos": r.vote_infos.into_iter().map(|v| json!({
+            "option": v.option,
+            "votes": v.votes,
+            "cleared_votes": v.cleared_votes,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+pub(crate) fn contract(r: pb::Contract) -> Value {
+    json!({
+        "id": r.id,
+        "code": r.code,
+        "language": r.language,
+        "version": r.version,
+        "abis": r.abis.into_iter().map(|abi| json!({
+            "name": abi.name,
+            "args": abi.args,
+            "amount_limit": abi.amount_limit.into_iter().map(|l| json!({
+                "token": l.token,
+                "value": l.value,
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+pub(crate) fn producer_vote_info(r: pb::GetProducerVoteInfoResponse) -> Value {
+    json!({
+        "pubkey": r.pubkey,
+        "loc": r.loc,
+        "url": r.url,
+        "net_id": r.net_id,
+        "is_producer": r.is_producer,
+        "status": r.status,
+        "online": r.online,
+        "votes": double(r.votes),
+    })
+}
+
+pub(crate) fn contract_storage(r: pb::GetContractStorageResponse) -> Value {
+    json!({
+        "data": r.data,
+        "block_hash": r.block_hash,
+        "block_number": r.block_number.to_string(),
+    })
+}
+
+pub(crate) fn batch_contract_storage(r: pb::GetBatchContractStorageResponse) -> Value {
+    json!({
+        "datas": r.datas,
+        "block_hash": r.block_hash,
+        "block_number": r.block_number.to_string(),
+    })
+}
+
+pub(crate) fn contract_storage_fields(r: pb::GetContractStorageFieldsResponse) -> Value {
+    json!({
+        "fields": r.fields,
+        "block_hash": r.block_hash,
+        "block_number": r.block_number.to_string(),
+    })
+}
+
+pub(crate) fn token_info(r: pb::TokenInfo) -> Value {
+    json!({
+        "symbol": r.symbol,
+        "full_name": r.full_name,
+        "issuer": r.issuer,
+        "total_supply": r.total_supply.to_string(),
+        "current_supply": r.current_supply.to_string(),
+        "decimal": r.decimal,
+        "can_transfer": r.can_transfer,
+        "only_issuer_can_transfer": r.only_issuer_can_transfer,
+        "total_supply_float": double(r.total_supply_float),
+        "current_supply_float": double(r.current_supply_float),
+    })
+}
+
+pub(crate) fn voter_bonus(r: pb::VoterBonus) -> Value {
+    json!({
+        "bonus": double(r.bonus),
+        "detail": r.detail.into_iter().map(|(k, v)| (k, double(v))).collect::<serde_json::Map<_, _>>(),
+    })
+}
+
+pub(crate) fn candidate_bonus(r: pb::CandidateBonus) -> Value {
+    json!({
+        "bonus": double(r.bonus),
+    })
+}
+
+pub(crate) fn send_response(r: pb::SendTransactionResponse) -> Value {
+    json!({
+        "hash": r.hash,
+        "pre_tx_receipt": r.pre_tx_receipt.map(tx_receipt),
+    })
+}
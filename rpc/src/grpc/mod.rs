@@ -0,0 +1,655 @@
+use std::convert::TryFrom;
+
+use async_trait::async_trait;
+use iost_chain::spv::Block;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tonic::transport::Channel;
+
+use crate::block_stream::ChainHeight;
+use crate::epoch_proof::BlockSource;
+use crate::error::Error;
+use crate::{BatchContractStoragePost, Client, ContractStorageFieldsPost, ContractStoragePost};
+
+use self::pb::api_service_client::ApiServiceClient;
+
+mod convert;
+
+/// Messages and service of `proto/rpc.proto`, generated by `build.rs`
+pub mod pb {
+    tonic::include_proto!("rpcpb");
+}
+
+/// Client of the gRPC API of a node, port 30002 by default. It answers the paths of the HTTP API
+/// with the same JSON, so that the responses deserialize into the same types as with `IOST` and
+/// the typed requests of `Client`, the storage reads and `TxTracker` work over either, while
+/// `ChainHeight` and `BlockSource` read the messages of the node directly. The events of
+/// `IOST::subscribe` are only streamed by the HTTP gateway.
+/// The connection is opened by the first request.
+pub struct GrpcClient {
+    endpoint: String,
+    client: Mutex<Option<ApiServiceClient<Channel>>>,
+}
+
+fn mapping_error<E: std::fmt::Display>(e: E) -> Error {
+    Error::GrpcMapping(e.to_string())
+}
+
+fn unsupported(path: &str) -> Error {
+    Error::GrpcMapping(format!("{} is not supported over gRPC", path))
+}
+
+fn parse_bool(s: &str) -> Result<bool, Error> {
+    s.parse().map_err(mapping_error)
+}
+
+impl GrpcClient {
+    async fn api(&self) -> Result<ApiServiceClient<Channel>, Error> {
+        let mut client = self.client.lock().await;
+        // the methods of the client take `&mut self`, its clones share the connection
+        if let Some(ref client) = *client {
+            return Ok(client.clone());
+        }
+        let connected = ApiServiceClient::connect(self.endpoint.clone()).await.map_err(Error::GrpcTransport)?;
+        *client = Some(connected.clone());
+        Ok(connected)
+    }
+
+    async fn get_value(&self, path: &str) -> Result<Value, Error> {
+        let mut api = self.api().await?;
+        let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let value = match parts.as_slice() {
+            ["getNodeInfo"] => convert::node_info(api.get_node_info(pb::EmptyRequest {}).await.map_err(Error::Grpc)?.into_inner()),
+            ["getChainInfo"] => convert::chain_info(api.get_chain_info(pb::EmptyRequest {}).await.map_err(Error::Grpc)?.into_inner()),
+            ["getGasRatio"] => convert::gas_ratio(api.get_gas_ratio(pb::EmptyRequest {}).await.map_err(Error::Grpc)?.into_inner()),
+            ["getRAMInfo"] => convert::ram_info(api.get_ram_info(pb::EmptyRequest {}).await.map_err(Error::Grpc)?.into_inner()),
+            ["getTxByHash", hash] => {
+                let request = pb::TxHashRequest { hash: hash.to_string() };
+                convert::tx_response(api.get_tx_by_hash(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            ["getTxReceiptByTxHash", hash] => {
+                let request = pb::TxHashRequest { hash: hash.to_string() };
+                convert::tx_receipt(api.get_tx_receipt_by_tx_hash(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            ["getBlockByHash", hash, complete] => {
+                let request = pb::GetBlockByHashRequest { hash: hash.to_string(), complete: parse_bool(complete)? };
+                convert::block_response(api.get_block_by_hash(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            ["getBlockByNumber", number, complete] => {
+                let request = pb::GetBlockByNumberRequest {
+                    number: number.parse().map_err(|_| Error::InvalidNumber(number.to_string()))?,
+                    complete: parse_bool(complete)?,
+                };
+                convert::block_response(api.get_block_by_number(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            ["getTokenBalance", account, token, by_longest_chain] => {
+                let request = pb::GetTokenBalanceRequest {
+                    account: account.to_string(),
+                    token: token.to_string(),
+                    by_longest_chain: parse_bool(by_longest_chain)?,
+                };
+                convert::token_balance(api.get_token_balance(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            ["getAccount", name, by_longest_chain] => {
+                let request = pb::GetAccountRequest { name: name.to_string(), by_longest_chain: parse_bool(by_longest_chain)? };
+                convert::account(api.get_account(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            ["getContract", id, by_longest_chain] => {
+                let request = pb::GetContractRequest { id: id.to_string(), by_longest_chain: parse_bool(by_longest_chain)? };
+                convert::contract(api.get_contract(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            ["getProducerVoteInfo", account, by_longest_chain] => {
+                let request = pb::GetProducerVoteInfoRequest { account: account.to_string(), by_longest_chain: parse_bool(by_longest_chain)? };
+                convert::producer_vote_info(api.get_producer_vote_info(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            ["getTokenInfo", symbol, by_longest_chain] => {
+                let request = pb::GetTokenInfoRequest { symbol: symbol.to_string(), by_longest_chain: parse_bool(by_longest_chain)? };
+                convert::token_info(api.get_token_info(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            ["getVoterBonus", name, by_longest_chain] => {
+                let request = pb::GetAccountRequest { name: name.to_string(), by_longest_chain: parse_bool(by_longest_chain)? };
+                convert::voter_bonus(api.get_voter_bonus(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            ["getCandidateBonus", name, by_longest_chain] => {
+                let request = pb::GetAccountRequest { name: name.to_string(), by_longest_chain: parse_bool(by_longest_chain)? };
+                convert::candidate_bonus(api.get_candidate_bonus(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            _ => return Err(unsupported(path)),
+        };
+        Ok(value)
+    }
+
+    async fn post_value(&self, path: &str, param: Value) -> Result<Value, Error> {
+        let mut api = self.api().await?;
+        let value = match path {
+            "sendTx" | "execTx" => {
+                let tx: iost_chain::Tx = serde_json::from_value(param).map_err(mapping_error)?;
+                let request = pb::TransactionRequest::try_from(&tx).map_err(Error::Chain)?;
+                if path == "sendTx" {
+                    convert::send_response(api.send_transaction(request).await.map_err(Error::Grpc)?.into_inner())
+                } else {
+                    convert::tx_receipt(api.exec_transaction(request).await.map_err(Error::Grpc)?.into_inner())
+                }
+            }
+            "getContractStorage" => {
+                let post: ContractStoragePost = serde_json::from_value(param).map_err(mapping_error)?;
+                let request = pb::GetContractStorageRequest {
+                    id: post.id,
+                    key: post.key,
+                    field: post.field,
+                    by_longest_chain: post.by_longest_chain,
+                };
+                convert::contract_storage(api.get_contract_storage(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            "getBatchContractStorage" => {
+                let post: BatchContractStoragePost = serde_json::from_value(param).map_err(mapping_error)?;
+                let request = pb::GetBatchContractStorageRequest {
+                    id: post.id,
+                    key_fields: post.key_fields.into_iter()
+                        .map(|kf| pb::get_batch_contract_storage_request::KeyField { key: kf.key, field: kf.field })
+                        .collect(),
+                    by_longest_chain: post.by_longest_chain,
+                };
+                convert::batch_contract_storage(api.get_batch_contract_storage(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            "getContractStorageFields" => {
+                let post: ContractStorageFieldsPost = serde_json::from_value(param).map_err(mapping_error)?;
+                let request = pb::GetContractStorageFieldsRequest {
+                    id: post.id,
+                    key: post.key,
+                    by_longest_chain: post.by_longest_chain,
+                };
+                convert::contract_storage_fields(api.get_contract_storage_fields(request).await.map_err(Error::Grpc)?.into_inner())
+            }
+            _ => return Err(unsupported(path)),
+        };
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl Client for GrpcClient {
+
+    /// `host` is the URL of the gRPC endpoint, such as `http://127.0.0.1:30002`
+    fn new(host: &str) -> Self {
+        Self {
+            endpoint: host.to_owned(),
+            client: Mutex::new(None),
+        }
+    }
+
+    async fn get<T>(&self, path: &str) -> Result<T, Error> where T: 'static + for<'de>Deserialize<'de> {
+        let value = self.get_value(path).await?;
+        serde_json::from_value(value).map_err(mapping_error)
+    }
+
+    /// `sendTx` and `execTx` take a transaction of `iost_chain::Tx` as parameter, the storage
+    /// queries their `*Post` parameters
+    async fn post<T, R>(&self, path: &str, param: R) -> Result<T, Error>
+        where T: 'static + for<'de> Deserialize<'de>,
+              R: Serialize + Send + Sync
+    {
+        let param = serde_json::to_value(&param).map_err(mapping_error)?;
+        let value = self.post_value(path, param).await?;
+        serde_json::from_value(value).map_err(mapping_error)
+    }
+}
+
+#[async_trait]
+impl ChainHeight for GrpcClient {
+    async fn get_heights(&self) -> Result<(i64, i64), Error> {
+        let chain_info = self.api().await?.get_chain_info(pb::EmptyRequest {}).await.map_err(Error::Grpc)?.into_inner();
+        Ok((chain_info.head_block, chain_info.lib_block))
+    }
+}
+
+#[async_trait]
+impl BlockSource for GrpcClient {
+    async fn get_spv_block(&self, number: i64) -> Result<Block, Error> {
+        let request = pb::GetBlockByNumberRequest { number, complete: true };
+        let block = self.api().await?.get_block_by_number(request).await.map_err(Error::Grpc)?.into_inner().block;
+        convert::spv_block(block.ok_or_else(|| Error::InvalidBlock(format!("block {} is missing", number)))?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use iost_chain::{IostAction, TxBuilder, TxReceiptData, MAINNET_CHAIN_ID};
+    use iost_chain::spv::Head;
+    use tonic::{Request, Response, Status};
+    use tonic::transport::Server;
+    use crate::get_block_by_hash::BlockByNumber;
+    use crate::status_code::StatusCode;
+    use crate::{ChainInfo, StorageReader, TokenBalance, TxReceipt, TxTracker};
+    use crate::tx_response::TxResponse;
+    use self::pb::api_service_server::{ApiService, ApiServiceServer};
+
+    const TX_HASH: &str = "Dj8bmA4Fx4LHrwLtDB6EEkNbBFU8biENxf55mNaJewYw";
+
+    #[derive(Default)]
+    struct Fake {
+        sent: Arc<std::sync::Mutex<Vec<pb::TransactionRequest>>>,
+    }
+
+    fn receipt() -> pb::TxReceipt {
+        let mut ram_usage = HashMap::new();
+        ram_usage.insert("admin".to_string(), 312);
+        pb::TxReceipt {
+            tx_hash: TX_HASH.to_string(),
+            gas_usage: 2.25,
+            ram_usage,
+            status_code: pb::tx_receipt::StatusCode::Success as i32,
+            message: "".to_string(),
+            returns: vec!["[\"\"]".to_string()],
+            receipts: vec![pb::Receipt {
+                func_name: "token.iost/transfer".to_string(),
+                content: "[\"iost\",\"admin\",\"alice\",\"10\",\"\"]".to_string(),
+            }],
+        }
+    }
+
+    const INFO: &[u8] = br#"{"mode":0,"thread":1,"batch_index":[0]}"#;
+
+    /// Block `number` of the fake node, whose hash is that of its head
+    fn fake_block(number: i64, transactions: Vec<pb::Transaction>) -> pb::Block {
+        let head = Head {
+            version: 0,
+            parent_hash: vec![],
+            tx_merkle_hash: vec![],
+            tx_receipt_merkle_hash: vec![],
+            info: INFO.to_vec(),
+            number,
+            witness: "admin".to_string(),
+            time: 0,
+        };
+        pb::Block {
+            hash: bs58::encode(head.hash()).into_string(),
+            number,
+            witness: head.witness,
+            tx_count: 1,
+            info: Some(pb::block::Info { mode: 0, thread: 1, batch_index: vec![0] }),
+            transactions,
+            ..Default::default()
+        }
+    }
+
+    #[tonic::async_trait]
+    impl ApiService for Fake {
+        async fn get_node_info(&self, _: Request<pb::EmptyRequest>) -> Result<Response<pb::NodeInfoResponse>, Status> {
+            Err(Status::unimplemented("getNodeInfo"))
+        }
+
+        async fn get_ram_info(&self, _: Request<pb::EmptyRequest>) -> Result<Response<pb::RamInfoResponse>, Status> {
+            Err(Status::unimplemented("getRAMInfo"))
+        }
+
+        async fn get_chain_info(&self, _: Request<pb::EmptyRequest>) -> Result<Response<pb::ChainInfoResponse>, Status> {
+            Ok(Response::new(pb::ChainInfoResponse {
+                net_name: "debugnet".to_string(),
+                protocol_version: "1.0".to_string(),
+                chain_id: 1024,
+                head_block: 12,
+                lib_block: 3,
+                witness_list: vec!["admin".to_string()],
+                head_block_time: 1598918258274417000,
+                ..Default::default()
+            }))
+        }
+
+        async fn get_gas_ratio(&self, _: Request<pb::EmptyRequest>) -> Result<Response<pb::GasRatioResponse>, Status> {
+            Err(Status::unimplemented("getGasRatio"))
+        }
+
+        async fn get_tx_by_hash(&self, _: Request<pb::TxHashRequest>) -> Result<Response<pb::TransactionResponse>, Status> {
+            Err(Status::unimplemented("getTxByHash"))
+        }
+
+        async fn get_tx_receipt_by_tx_hash(&self, request: Request<pb::TxHashRequest>) -> Result<Response<pb::TxReceipt>, Status> {
+            if request.get_ref().hash == TX_HASH {
+                Ok(Response::new(receipt()))
+            } else {
+                Err(Status::not_found("tx not found"))
+            }
+        }
+
+        async fn get_block_by_hash(&self, _: Request<pb::GetBlockByHashRequest>) -> Result<Response<pb::BlockResponse>, Status> {
+            Err(Status::unimplemented("getBlockByHash"))
+        }
+
+        async fn get_block_by_number(&self, request: Request<pb::GetBlockByNumberRequest>) -> Result<Response<pb::BlockResponse>, Status> {
+            let request = request.into_inner();
+            let transactions = if request.complete {
+                vec![pb::Transaction {
+                    hash: TX_HASH.to_string(),
+                    gas_ratio: 1.0,
+                    gas_limit: 1000000.0,
+                    chain_id: 1024,
+                    publisher: "admin".to_string(),
+                    tx_receipt: Some(receipt()),
+                    ..Default::default()
+                }]
+            } else {
+                vec![]
+            };
+            Ok(Response::new(pb::BlockResponse {
+                status: pb::block_response::BlockStatus::Irreversible as i32,
+                block: Some(fake_block(request.number, transactions)),
+            }))
+        }
+
+        async fn get_token_balance(&self, request: Request<pb::GetTokenBalanceRequest>) -> Result<Response<pb::GetTokenBalanceResponse>, Status> {
+            let request = request.into_inner();
+            Ok(Response::new(pb::GetTokenBalanceResponse {
+                balance: if request.by_longest_chain { 1000000.0 } else { 0.5 },
+                frozen_balances: vec![pb::FrozenBalance { amount: 10.0, time: 1598918258274417000 }],
+            }))
+        }
+
+        async fn get_account(&self, request: Request<pb::GetAccountRequest>) -> Result<Response<pb::Account>, Status> {
+            let item = pb::account::Item {
+                id: "Gcv8c2tH8qZrUYnKdEEdTtASsxivic2834MQW6mgxqto".to_string(),
+                is_key_pair: true,
+                weight: 100,
+                permission: "".to_string(),
+            };
+            let mut permissions = HashMap::new();
+            permissions.insert("active".to_string(), pb::account::Permission {
+                name: "active".to_string(),
+                group_names: vec![],
+                items: vec![item],
+                threshold: 100,
+            });
+            Ok(Response::new(pb::Account {
+                name: request.into_inner().name,
+                balance: 1000000.0,
+                gas_info: Some(pb::GasInfo {
+                    current_total: 3000000.5,
+                    pledged_info: vec![pb::PledgeInfo { pledger: "admin".to_string(), amount: 10.0 }],
+                    ..Default::default()
+                }),
+                ram_info: Some(pb::AccountRamInfo { available: 2048, used: 1024, total: 3072 }),
+                permissions,
+                groups: HashMap::new(),
+                frozen_balances: vec![],
+                vote_infos: vec![pb::account::VoteInfo {
+                    option: "producer".to_string(),
+                    votes: "2100".to_string(),
+                    cleared_votes: "0".to_string(),
+                }],
+            }))
+        }
+
+        async fn get_contract(&self, request: Request<pb::GetContractRequest>) -> Result<Response<pb::Contract>, Status> {
+            Ok(Response::new(pb::Contract {
+                id: request.into_inner().id,
+                language: "javascript".to_string(),
+                version: "1.0.0".to_string(),
+                abis: vec![pb::contract::Abi {
+                    name: "transfer".to_string(),
+                    args: vec!["string".to_string(), "string".to_string(), "string".to_string(), "string".to_string(), "string".to_string()],
+                    amount_limit: vec![],
+                }],
+                ..Default::default()
+            }))
+        }
+
+        async fn get_producer_vote_info(&self, _: Request<pb::GetProducerVoteInfoRequest>) -> Result<Response<pb::GetProducerVoteInfoResponse>, Status> {
+            Ok(Response::new(pb::GetProducerVoteInfoResponse {
+                pubkey: "Gcv8c2tH8qZrUYnKdEEdTtASsxivic2834MQW6mgxqto".to_string(),
+                is_producer: true,
+                status: "APPROVED".to_string(),
+                online: true,
+                votes: 2100.0,
+                ..Default::default()
+            }))
+        }
+
+        async fn get_contract_storage(&self, request: Request<pb::GetContractStorageRequest>) -> Result<Response<pb::GetContractStorageResponse>, Status> {
+            let request = request.into_inner();
+            let data = match (request.key.as_str(), request.field.as_str()) {
+                ("TIiost", "decimal") => "8",
+                _ => "null",
+            };
+            Ok(Response::new(pb::GetContractStorageResponse { data: data.to_string(), block_hash: "".to_string(), block_number: 12 }))
+        }
+
+        async fn get_batch_contract_storage(&self, request: Request<pb::GetBatchContractStorageRequest>) -> Result<Response<pb::GetBatchContractStorageResponse>, Status> {
+            let datas = request.into_inner().key_fields.iter()
+                .map(|kf| if kf.field == "fullName" { "IOST" } else { "null" }.to_string())
+                .collect();
+            Ok(Response::new(pb::GetBatchContractStorageResponse { datas, block_hash: "".to_string(), block_number: 12 }))
+        }
+
+        async fn get_contract_storage_fields(&self, _: Request<pb::GetContractStorageFieldsRequest>) -> Result<Response<pb::GetContractStorageFieldsResponse>, Status> {
+            Ok(Response::new(pb::GetContractStorageFieldsResponse {
+                fields: vec!["decimal".to_string(), "fullName".to_string()],
+                block_hash: "".to_string(),
+                block_number: 12,
+            }))
+        }
+
+        async fn send_transaction(&self, request: Request<pb::TransactionRequest>) -> Result<Response<pb::SendTransactionResponse>, Status> {
+            self.sent.lock().unwrap().push(request.into_inner());
+            Ok(Response::new(pb::SendTransactionResponse { hash: TX_HASH.to_string(), pre_tx_receipt: None }))
+        }
+
+        async fn exec_transaction(&self, _: Request<pb::TransactionRequest>) -> Result<Response<pb::TxReceipt>, Status> {
+            Err(Status::invalid_argument("gas limit too low"))
+        }
+
+        async fn get_voter_bonus(&self, _: Request<pb::GetAccountRequest>) -> Result<Response<pb::VoterBonus>, Status> {
+            let mut detail = HashMap::new();
+            detail.insert("producer".to_string(), 1.5);
+            Ok(Response::new(pb::VoterBonus { bonus: 1.5, detail }))
+        }
+
+        async fn get_candidate_bonus(&self, _: Request<pb::GetAccountRequest>) -> Result<Response<pb::CandidateBonus>, Status> {
+            Ok(Response::new(pb::CandidateBonus { bonus: 20.0 }))
+        }
+
+        async fn get_token_info(&self, request: Request<pb::GetTokenInfoRequest>) -> Result<Response<pb::TokenInfo>, Status> {
+            Ok(Response::new(pb::TokenInfo {
+                symbol: request.into_inner().symbol,
+                full_name: "IOST".to_string(),
+                issuer: "token.iost".to_string(),
+                total_supply: 2100000000000000000,
+                current_supply: 2100000000000000000,
+                decimal: 8,
+                can_transfer: true,
+                only_issuer_can_transfer: false,
+                total_supply_float: 21000000000.0,
+                current_supply_float: 21000000000.0,
+            }))
+        }
+    }
+
+    /// Serve `fake` on a free port and return the endpoint of the server
+    async fn serve(fake: Fake) -> String {
+        let mut listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            Server::builder()
+                .add_service(ApiServiceServer::new(fake))
+                .serve_with_incoming(listener.incoming())
+                .await
+                .unwrap();
+        });
+        endpoint
+    }
+
+    #[tokio::test]
+    async fn grpc_get_method_should_be_ok() {
+        let client = GrpcClient::new(&serve(Fake::default()).await);
+        let chain_info: ChainInfo = client.get("getChainInfo").await.unwrap();
+        assert_eq!(chain_info.chain_id, 1024);
+        assert_eq!(chain_info.head_block, "12");
+        assert_eq!(chain_info.lib_block, "3");
+        assert_eq!(chain_info.head_block_time, "1598918258274417000");
+        assert_eq!(client.get_heights().await.unwrap(), (12, 3));
+
+        let receipt: TxReceipt = client.get(&format!("getTxReceiptByTxHash/{}", TX_HASH)).await.unwrap();
+        assert_eq!(receipt.status_code, StatusCode::SUCCESS);
+        assert_eq!(receipt.gas_usage, 2.25);
+        assert_eq!(receipt.ram_usage["admin"], 312);
+        assert_eq!(receipt.receipts[0].func_name, "token.iost/transfer");
+
+        let block: BlockByNumber = client.get("getBlockByNumber/3/true").await.unwrap();
+        assert_eq!(block.block.number, "3");
+        assert_eq!(block.block.info.batch_index, vec![0]);
+        assert_eq!(block.block.transactions[0].gas_ratio, 1);
+        assert_eq!(block.block.transactions[0].tx_receipt.tx_hash, TX_HASH);
+
+        let balance: TokenBalance = client.get("getTokenBalance/admin/iost/true").await.unwrap();
+        assert_eq!(balance.balance, 1000000.0);
+        assert_eq!(balance.frozen_balances[0].time, "1598918258274417000");
+    }
+
+    #[tokio::test]
+    async fn grpc_typed_requests_should_be_ok() {
+        let client = GrpcClient::new(&serve(Fake::default()).await);
+        assert_eq!(client.get_chain_info().await.unwrap().head_block, "12");
+
+        let account = client.get_account("admin", true).await.unwrap();
+        assert_eq!(account.name, "admin");
+        assert_eq!(account.gas_info.current_total, 3000000.5);
+        assert_eq!(account.gas_info.pledged_info[0].pledger, "admin");
+        assert_eq!(account.ram_info.used, "1024");
+        assert_eq!(account.permissions["active"].threshold, "100");
+        assert_eq!(account.permissions["active"].items[0].weight, "100");
+        assert_eq!(account.vote_infos[0].votes, "2100");
+
+        let contract = client.get_contract("token.iost", true).await.unwrap();
+        assert_eq!(contract.id, "token.iost");
+        assert_eq!(contract.abis[0].name, "transfer");
+        assert_eq!(contract.abis[0].args.len(), 5);
+
+        let vote_info = client.get_producer_vote_info("admin", true).await.unwrap();
+        assert!(vote_info.is_producer);
+        assert_eq!(vote_info.status, crate::Status::APPROVED);
+        assert_eq!(vote_info.votes, 2100);
+
+        let token_info = client.get_token_info("iost", true).await.unwrap();
+        assert_eq!(token_info.symbol, "iost");
+        assert_eq!(token_info.total_supply, "2100000000000000000");
+        assert_eq!(token_info.decimal, 8);
+        assert_eq!(client.get_voter_bonus("admin", true).await.unwrap().detail["producer"], 1.5);
+        assert_eq!(client.get_candidate_bonus("admin", true).await.unwrap().bonus, 20.0);
+
+        assert_eq!(client.storage::<u8>("token.iost", "TIiost", "decimal").await.unwrap(), Some(8));
+        let page = client.storage_page::<String>("token.iost", "TIiost", 0, 2).await.unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.entries, vec![("decimal".to_string(), None), ("fullName".to_string(), Some("IOST".to_string()))]);
+
+        let receipt = TxTracker::new(&client)
+            .poll_interval(Duration::from_millis(10))
+            .wait_receipt(TX_HASH, i64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(receipt.gas_usage, 2.25);
+    }
+
+    #[tokio::test]
+    async fn grpc_blocks_should_pass_hash_checks() {
+        let client = GrpcClient::new(&serve(Fake::default()).await);
+        let block = client.get_spv_block(3).await.unwrap();
+        assert_eq!(block.head.number, 3);
+        assert_eq!(block.head.witness, "admin");
+        assert_eq!(block.head.info, INFO.to_vec());
+        assert_eq!(block.tx_hashes, vec![base64::encode(bs58::decode(TX_HASH).into_vec().unwrap())]);
+        assert_eq!(block.receipts[0].tx_hash, block.tx_hashes[0]);
+        assert_eq!(block.receipts[0].gas_usage, "225");
+        assert_eq!(block.receipts[0].ram_usage["admin"], "312");
+        assert_eq!(block.receipts[0].receipts[0].func_name, "token.iost/transfer");
+
+        let mut forged = fake_block(3, vec![]);
+        forged.witness = "alice".to_string();
+        match convert::spv_block(forged) {
+            Err(Error::InvalidBlock(_)) => {}
+            r => panic!("unexpected {:?}", r),
+        }
+        let unreceipted = fake_block(3, vec![pb::Transaction { hash: TX_HASH.to_string(), ..Default::default() }]);
+        assert!(convert::spv_block(unreceipted).is_err());
+    }
+
+    #[tokio::test]
+    async fn grpc_errors_should_be_reported() {
+        let client = GrpcClient::new(&serve(Fake::default()).await);
+        match client.get::<TxReceipt>("getTxReceiptByTxHash/unknown").await {
+            Err(Error::Grpc(status)) => assert_eq!(status.code(), tonic::Code::NotFound),
+            r => panic!("unexpected {:?}", r),
+        }
+        match client.get::<Value>("getContractVote/token.iost/true").await {
+            Err(Error::GrpcMapping(_)) => {}
+            r => panic!("unexpected {:?}", r),
+        }
+        match client.get::<Value>("getBlockByNumber/x/true").await {
+            Err(Error::InvalidNumber(n)) => assert_eq!(n, "x"),
+            r => panic!("unexpected {:?}", r),
+        }
+
+        let unreachable = GrpcClient::new("http://127.0.0.1:1");
+        match unreachable.get::<ChainInfo>("getChainInfo").await {
+            Err(Error::GrpcTransport(_)) => {}
+            r => panic!("unexpected {:?}", r),
+        }
+    }
+
+    #[tokio::test]
+    async fn grpc_send_tx_should_be_ok() {
+        let fake = Fake::default();
+        let sent = fake.sent.clone();
+        let client = GrpcClient::new(&serve(fake).await);
+        let mut tx = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time(1598918258274417000)
+            .publisher("admin")
+            .action(IostAction::transfer("admin", "lispczz3", "10", "").unwrap())
+            .build()
+            .unwrap();
+        tx.sign("admin".to_string(), "SECP256K1", &[1; 32]).unwrap();
+
+        let response: TxResponse = client.post("sendTx", &tx).await.unwrap();
+        assert_eq!(response.hash, TX_HASH);
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].publisher, "admin");
+        assert_eq!(sent[0].time, tx.time);
+        assert_eq!(sent[0].actions[0], pb::Action::from(&tx.actions[0]));
+        assert_eq!(sent[0].publisher_sigs[0].algorithm, pb::signature::Algorithm::Secp256k1 as i32);
+        assert_eq!(base64::encode(&sent[0].publisher_sigs[0].public_key), tx.publisher_sigs[0].public_key);
+
+        match client.post::<TxReceipt, _>("execTx", &tx).await {
+            Err(Error::Grpc(status)) => assert_eq!(status.code(), tonic::Code::InvalidArgument),
+            r => panic!("unexpected {:?}", r),
+        }
+        match client.post::<Value, _>("getContractStorage", &tx).await {
+            Err(Error::GrpcMapping(_)) => {}
+            r => panic!("unexpected {:?}", r),
+        }
+    }
+
+    #[test]
+    fn grpc_receipt_should_be_converted() {
+        let data = TxReceiptData::try_from(&receipt()).unwrap();
+        assert_eq!(data.tx_hash, bs58::decode(TX_HASH).into_vec().unwrap());
+        assert_eq!(data.gas_usage, 225);
+        assert_eq!(data.ram_usage, vec![("admin".to_string(), 312)]);
+        assert_eq!(data.receipts[0].0, "token.iost/transfer");
+
+        let tx = iost_chain::Transaction::from(pb::Transaction {
+            actions: vec![pb::Action {
+                contract: "token.iost".to_string(),
+                action_name: "transfer".to_string(),
+                data: "[]".to_string(),
+            }],
+            chain_id: 1024,
+            ..Default::default()
+        });
+        assert_eq!(tx.actions[0], IostAction::new("token.iost".to_string(), "transfer".to_string(), "[]".to_string()));
+        assert_eq!(tx.chain_id, 1024);
+    }
+}
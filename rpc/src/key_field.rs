@@ -1,6 +1,6 @@
-use serde::{Serialize};
+use serde::{Serialize, Deserialize};
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct KeyField {
     /// the key of StateDB
     pub key: String,
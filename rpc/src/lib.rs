@@ -9,11 +9,32 @@ pub use crate::get_node_info::NodeInfo;
 pub use crate::get_chain_info::ChainInfo;
 pub use crate::get_gas_ratio::GasRatio;
 pub use crate::get_ram_info::RamInfo;
+pub use crate::get_token_balance::TokenBalance;
+pub use crate::get_contract::Contract;
+pub use crate::get_account::Account;
+pub use crate::get_token_info::TokenInfo;
+pub use crate::get_producer_vote_info::ProducerVoteInfo;
+pub use crate::get_voter_bonus::VoterBonus;
+pub use crate::get_candidate_bonus::CandidateBonus;
+pub use crate::get_abi::ABI;
+pub use crate::frozen_balance::FrozenBalance;
 pub use crate::get_contract_storage::{ContractStorage, ContractStoragePost};
 pub use crate::get_contract_storage_fields::{ContractStorageFields, ContractStorageFieldsPost};
 pub use crate::get_batch_contract_storage::{BatchContractStorage, BatchContractStoragePost};
 pub use crate::key_field::KeyField;
 pub use crate::message::ErrorMessage;
+pub use crate::get_tx_by_hash::GetTxByHash;
+pub use crate::status::Status;
+pub use crate::status_code::StatusCode;
+pub use crate::tx_receipt::TxReceipt;
+pub use crate::tx_tracker::{TxConfirmation, TxTracker};
+pub use crate::block_stream::{BlockEvent, BlockStream, ChainHeight, StreamMode, DEFAULT_RETRIES, DEFAULT_RETRY_INTERVAL, MAX_FORK_DEPTH};
+pub use crate::epoch_proof::{build_epoch_change_proof, BlockSource, MAX_WITNESS_BLOCKS};
+pub use crate::estimate::{ResourceEstimate, ResourceEstimator};
+pub use crate::storage::{decode_storage, StoragePage, StorageReader, NULL_DATA};
+pub use crate::subscribe::{Event, SubscribeFilter, SubscribeRequest, Subscription, Topic, DEFAULT_MAX_RETRY_INTERVAL};
+#[cfg(feature = "grpc")]
+pub use crate::grpc::GrpcClient;
 use serde::{Serialize, Deserialize};
 use crate::tx::Tx;
 use crate::tx_response::TxResponse;
@@ -22,10 +43,15 @@ mod get_abi;
 mod action;
 mod amount_limit;
 mod block;
+mod block_stream;
 mod bytes;
+mod epoch_proof;
 mod error;
+mod estimate;
 mod frozen_balance;
 mod gas_info;
+#[cfg(feature = "grpc")]
+mod grpc;
 mod get_block_by_hash;
 mod get_batch_contract_storage;
 mod get_contract_storage;
@@ -54,21 +80,28 @@ mod ram_info;
 mod receipts;
 mod status;
 mod status_code;
+mod storage;
+mod subscribe;
 mod signature;
 mod transaction;
 mod tx;
 mod tx_receipt;
 mod tx_response;
+mod tx_tracker;
 mod unsigned_int;
 mod vote_info;
 
-struct IOST {
+/// Client of the JSON API of a node. The blocks it returns as a `BlockSource` are not signed, so
+/// they can not feed `build_epoch_change_proof`
+pub struct IOST {
     host: String,
     client: reqwest::Client,
 }
 
+/// Requests of the API of a node, by the paths and the JSON of its HTTP gateway. The typed
+/// requests are provided on top of `get` and `post`, for `IOST` as for `GrpcClient`
 #[async_trait]
-trait Client {
+pub trait Client: Sync {
     fn new(host: &str) -> Self;
 
     async fn get<T>(&self, path: &str) -> Result<T, Error> where T: 'static + for<'de>Deserialize<'de>;
@@ -76,6 +109,82 @@ trait Client {
     async fn post<T, R>(&self, path: &str, param: R) -> Result<T, Error>
         where T: 'static + for<'de>Deserialize<'de>,
               R: Serialize + Send +Sync;
+
+    async fn get_node_info(&self) -> Result<NodeInfo, Error> {
+        self.get("getNodeInfo").await
+    }
+
+    async fn get_chain_info(&self) -> Result<ChainInfo, Error> {
+        self.get("getChainInfo").await
+    }
+
+    async fn get_gas_ratio(&self) -> Result<GasRatio, Error> {
+        self.get("getGasRatio").await
+    }
+
+    async fn get_ram_info(&self) -> Result<RamInfo, Error> {
+        self.get("getRAMInfo").await
+    }
+
+    async fn get_contract(&self, id: &str, by_longest_chain: bool) -> Result<Contract, Error> {
+        self.get(&format!("getContract/{}/{}", id, by_longest_chain)).await
+    }
+
+    async fn get_token_balance(&self, account: &str, token: &str, by_longest_chain: bool) -> Result<TokenBalance, Error> {
+        self.get(&format!("getTokenBalance/{}/{}/{}", account, token, by_longest_chain)).await
+    }
+
+    async fn get_account(&self, name: &str, by_longest_chain: bool) -> Result<Account, Error> {
+        self.get(&format!("getAccount/{}/{}", name, by_longest_chain)).await
+    }
+
+    async fn get_token_info(&self, symbol: &str, by_longest_chain: bool) -> Result<TokenInfo, Error> {
+        self.get(&format!("getTokenInfo/{}/{}", symbol, by_longest_chain)).await
+    }
+
+    async fn get_producer_vote_info(&self, account: &str, by_longest_chain: bool) -> Result<ProducerVoteInfo, Error> {
+        self.get(&format!("getProducerVoteInfo/{}/{}", account, by_longest_chain)).await
+    }
+
+    async fn get_voter_bonus(&self, name: &str, by_longest_chain: bool) -> Result<VoterBonus, Error> {
+        self.get(&format!("getVoterBonus/{}/{}", name, by_longest_chain)).await
+    }
+
+    async fn get_candidate_bonus(&self, name: &str, by_longest_chain: bool) -> Result<CandidateBonus, Error> {
+        self.get(&format!("getCandidateBonus/{}/{}", name, by_longest_chain)).await
+    }
+
+    async fn get_contract_storage(&self, par: ContractStoragePost) -> Result<ContractStorage, Error> {
+        self.post("getContractStorage",&par).await
+    }
+
+    async fn get_contract_storage_fields(&self, par: ContractStorageFieldsPost) -> Result<ContractStorageFields, Error> {
+        self.post("getContractStorageFields",&par).await
+    }
+
+    async fn get_batch_contract_storage(&self, par: BatchContractStoragePost) -> Result<BatchContractStorage, Error> {
+        self.post("getBatchContractStorage", &par).await
+    }
+
+    async fn send_tx(&self, par: Tx) -> Result<TxResponse, Error> {
+        self.post("sendTx", par).await
+    }
+
+    async fn send_signed_tx(&self, par: &iost_chain::Tx) -> Result<TxResponse, Error> {
+        self.post("sendTx", par).await
+    }
+
+    async fn exec_tx(&self, par: &iost_chain::Tx) -> Result<TxReceipt, Error> {
+        self.post("execTx", par).await
+    }
+
+    async fn get_tx_by_hash(&self, hash: &str) -> Result<GetTxByHash, Error> {
+        self.get(&format!("getTxByHash/{}", hash)).await
+    }
+
+    async fn get_tx_receipt_by_tx_hash(&self, hash: &str) -> Result<TxReceipt, Error> {
+        self.get(&format!("getTxReceiptByTxHash/{}", hash)).await
+    }
 }
 
 #[async_trait]
@@ -121,40 +230,6 @@ impl Client for IOST {
     }
 }
 
-impl IOST {
-    pub async fn get_node_info(&self) -> Result<NodeInfo, Error> {
-        self.get("getNodeInfo").await
-    }
-
-    pub async fn get_chain_info(&self) -> Result<ChainInfo, Error> {
-        self.get("getChainInfo").await
-    }
-
-    pub async fn get_gas_ratio(&self) -> Result<GasRatio, Error> {
-        self.get("getGasRatio").await
-    }
-
-    pub async fn get_ram_info(&self) -> Result<RamInfo, Error> {
-        self.get("getRAMInfo").await
-    }
-
-    pub async fn get_contract_storage(&self, par: ContractStoragePost) -> Result<ContractStorage, Error> {
-        self.post("getContractStorage",&par).await
-    }
-
-    pub async fn get_contract_storage_fields(&self, par: ContractStorageFieldsPost) -> Result<ContractStorageFields, Error> {
-        self.post("getContractStorageFields",&par).await
-    }
-
-    pub async fn get_batch_contract_storage(&self, par: BatchContractStoragePost) -> Result<BatchContractStorage, Error> {
-        self.post("getBatchContractStorage", &par).await
-    }
-
-    pub async fn send_tx(&self, par: Tx) -> Result<TxResponse, Error> {
-        self.post("sendTx", par).await
-    }
-}
-
 /// Execute test file command "cargo test iost_basic_test -- --nocapture"
 #[cfg(test)]
 mod tests {
@@ -162,11 +237,12 @@ mod tests {
     use crate::amount_limit::AmountLimit;
     use crate::signature::Signature;
     use crate::action::Action;
+    use iost_mock_node::{Fixtures, MockNode};
 
     #[tokio::test]
     async fn iost_basic_get_method_should_be_ok() {
-        let host = "https://api.iost.io";
-        let iost = IOST::new(host);
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let iost = IOST::new(&node.url());
         let result = iost.get_node_info().await;
         assert!(result.is_ok());
         let chain_result = iost.get_chain_info().await;
@@ -179,8 +255,8 @@ mod tests {
 
     #[tokio::test]
     async fn iost_basic_post_method_should_be_ok() {
-        let host = "https://api.iost.io";
-        let iost = IOST::new(host);
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let iost = IOST::new(&node.url());
         let new_post = ContractStoragePost {
             id: "token.iost".to_string(),
             key: "TIiost".to_string(),
@@ -197,13 +273,13 @@ mod tests {
         let field_result = iost.get_contract_storage_fields(new_post).await;
         assert!(field_result.is_ok());
         let key = KeyField {
-            key: "supply".to_string(),
-            field: "TIiost".to_string()
+            key: "TIiost".to_string(),
+            field: "supply".to_string()
         };
 
         let key1 = KeyField {
-            key: "decimal".to_string(),
-            field: "TIiost".to_string()
+            key: "TIiost".to_string(),
+            field: "decimal".to_string()
         };
 
         let posts = BatchContractStoragePost {
@@ -212,13 +288,13 @@ mod tests {
             by_longest_chain: true
         };
         let storage_result = iost.get_batch_contract_storage(posts).await;
-        assert!(storage_result.is_ok());
+        assert_eq!(storage_result.unwrap().datas, vec!["2100000000000000000", "8"]);
     }
 
     #[tokio::test]
     async fn test_send_tx_should_be_ok() {
-        let host = "https://api.iost.io";
-        let iost = IOST::new(host);
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let iost = IOST::new(&node.url());
         let action = Action{
             contract: "token.iost".to_string(),
             action_name: "transfer".to_string(),
@@ -232,8 +308,8 @@ mod tests {
 
         let signature = Signature{
             algorithm: "ED25519".to_string(),
-            signature: "lDS+SdM+aiVHbDyXapvrsgyKxFg9mJuHWPZb/INBRWY=".to_string(),
-            public_key: "/K1HM0OEbfJ4+D3BmalpLmb03WS7BeCz4nVHBNbDrx3/A31aN2RJNxyEKhv+VSoWctfevDNRnL1kadRVxSt8CA==".to_string()
+            signature: "/K1HM0OEbfJ4+D3BmalpLmb03WS7BeCz4nVHBNbDrx3/A31aN2RJNxyEKhv+VSoWctfevDNRnL1kadRVxSt8CA==".to_string(),
+            public_key: "lDS+SdM+aiVHbDyXapvrsgyKxFg9mJuHWPZb/INBRWY=".to_string()
         };
 
         let tx = Tx{
@@ -251,8 +327,9 @@ mod tests {
             signatures: vec![]
         };
 
-        let tx_result = iost.send_tx(tx).await;
-        dbg!(tx_result);
+        let tx_result = iost.send_tx(tx).await.unwrap();
+        assert_eq!(tx_result.hash, "CihRgNwEPjWnaXgogcFhK31r7EkWZyU9H6ZxzyWF37yK");
+        assert_eq!(node.sent_txs().len(), 1);
     }
 }
 
@@ -1,7 +1,7 @@
 use serde::{Serialize, Deserialize};
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum StatusCode {
     SUCCESS,
     GAS_RUN_OUT,
@@ -13,3 +13,20 @@ pub enum StatusCode {
     DUPLICATE_SET_CODE,
     UNKNOWN_ERROR
 }
+
+impl StatusCode {
+    /// Code of the status as the node hashes it in the receipts
+    pub fn code(&self) -> i32 {
+        match self {
+            StatusCode::SUCCESS => 0,
+            StatusCode::GAS_RUN_OUT => 1,
+            StatusCode::BALANCE_NOT_ENOUGH => 2,
+            StatusCode::WRONG_PARAMETER => 3,
+            StatusCode::RUNTIME_ERROR => 4,
+            StatusCode::TIMEOUT => 5,
+            StatusCode::WRONG_TX_FORMAT => 6,
+            StatusCode::DUPLICATE_SET_CODE => 7,
+            StatusCode::UNKNOWN_ERROR => 8,
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::get_batch_contract_storage::BatchContractStoragePost;
+use crate::get_contract_storage::ContractStoragePost;
+use crate::get_contract_storage_fields::ContractStorageFieldsPost;
+use crate::key_field::KeyField;
+use crate::Client;
+
+/// What the node answers for a missing key or field
+pub const NULL_DATA: &str = "null";
+
+/// A page of the fields of a map with their values
+#[derive(Debug)]
+pub struct StoragePage<T> {
+    /// fields of the page with their values, `None` for a field removed since the fields were listed
+    pub entries: Vec<(String, Option<T>)>,
+    /// number of fields of the map
+    pub total: usize,
+    /// offset of the next page, `None` on the last page and on an empty page, as with a zero limit
+    pub next_offset: Option<usize>,
+}
+
+/// Decode stored data into `T`: as JSON, or else as the string itself, since contracts store
+/// both. `None` when the data is missing
+pub fn decode_storage<T>(data: &str) -> Result<Option<T>, serde_json::Error>
+    where T: for<'de> Deserialize<'de>
+{
+    if data == NULL_DATA {
+        return Ok(None);
+    }
+    match serde_json::from_str(data) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => serde_json::from_value(Value::String(data.to_string())).map(Some).map_err(|_| e),
+    }
+}
+
+fn decode<T>(contract: &str, key: &str, field: &str, data: &str) -> Result<Option<T>, Error>
+    where T: for<'de> Deserialize<'de>
+{
+    decode_storage(data).map_err(|e| {
+        Error::InvalidStorage(format!("{} {} {}: {} in {:?}", contract, key, field, e, data))
+    })
+}
+
+/// Typed reads of the storage of the contracts, from the longest chain, over any `Client`
+#[async_trait]
+pub trait StorageReader: Client {
+    /// Value of `key`, or of `field` of the map at `key` when `field` is not empty
+    async fn storage<T>(&self, contract: &str, key: &str, field: &str) -> Result<Option<T>, Error>
+        where T: for<'de> Deserialize<'de>
+    {
+        let post = ContractStoragePost {
+            id: contract.to_string(),
+            key: key.to_string(),
+            field: field.to_string(),
+            by_longest_chain: true,
+        };
+        let storage = self.get_contract_storage(post).await?;
+        decode(contract, key, field, &storage.data)
+    }
+
+    /// Values of many keys and fields of a contract, in the order of `key_fields`
+    async fn storage_batch<T>(&self, contract: &str, key_fields: &[KeyField]) -> Result<Vec<Option<T>>, Error>
+        where T: for<'de> Deserialize<'de>
+    {
+        if key_fields.is_empty() {
+            return Ok(vec![]);
+        }
+        let post = BatchContractStoragePost {
+            id: contract.to_string(),
+            key_fields: key_fields.to_vec(),
+            by_longest_chain: true,
+        };
+        let storage = self.get_batch_contract_storage(post).await?;
+        if storage.datas.len() != key_fields.len() {
+            return Err(Error::InvalidStorage(format!(
+                "{} values for {} key fields of {}", storage.datas.len(), key_fields.len(), contract
+            )));
+        }
+        key_fields.iter().zip(storage.datas.iter())
+            .map(|(kf, data)| decode(contract, &kf.key, &kf.field, data))
+            .collect()
+    }
+
+    /// Fields of the map at `key`, empty when there is no map
+    async fn storage_fields(&self, contract: &str, key: &str) -> Result<Vec<String>, Error> {
+        let post = ContractStorageFieldsPost {
+            id: contract.to_string(),
+            key: key.to_string(),
+            by_longest_chain: true,
+        };
+        Ok(self.get_contract_storage_fields(post).await?.fields)
+    }
+
+    /// The fields of the map at `key` from `offset`, at most `limit` of them, with their values
+    /// read in one batch. The node lists all the fields at once, they are listed again for each page
+    async fn storage_page<T>(&self, contract: &str, key: &str, offset: usize, limit: usize) -> Result<StoragePage<T>, Error>
+        where T: for<'de> Deserialize<'de>
+    {
+        let fields = self.storage_fields(contract, key).await?;
+        let total = fields.len();
+        let end = total.min(offset.saturating_add(limit));
+        let page: Vec<String> = fields.into_iter().skip(offset).take(end.saturating_sub(offset)).collect();
+        let key_fields: Vec<KeyField> = page.iter()
+            .map(|field| KeyField { key: key.to_string(), field: field.clone() })
+            .collect();
+        let values = self.storage_batch(contract, &key_fields).await?;
+        Ok(StoragePage {
+            entries: page.into_iter().zip(values).collect(),
+            total,
+            next_offset: if offset < end && end < total { Some(end) } else { None },
+        })
+    }
+}
+
+impl<C: Client> StorageReader for C {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::IOST;
+    use iost_mock_node::{Fixtures, MockNode};
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Producer {
+        pubkey: String,
+        is_producer: bool,
+        status: i32,
+    }
+
+    #[test]
+    fn storage_data_should_be_decoded() {
+        assert_eq!(decode_storage::<u8>("8").unwrap(), Some(8));
+        assert_eq!(decode_storage::<String>("8").unwrap(), Some("8".to_string()));
+        assert_eq!(decode_storage::<String>("IOST").unwrap(), Some("IOST".to_string()));
+        assert_eq!(decode_storage::<String>("\"IOST\"").unwrap(), Some("IOST".to_string()));
+        assert_eq!(decode_storage::<Vec<String>>("[\"a\"]").unwrap(), Some(vec!["a".to_string()]));
+        assert_eq!(decode_storage::<u8>("null").unwrap(), None);
+        assert!(decode_storage::<u8>("IOST").is_err());
+    }
+
+    #[tokio::test]
+    async fn storage_should_be_typed() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let iost = IOST::new(&node.url());
+        assert_eq!(iost.storage::<u8>("token.iost", "TIiost", "decimal").await.unwrap(), Some(8));
+        assert_eq!(iost.storage::<u64>("token.iost", "TIiost", "supply").await.unwrap(), Some(2100000000000000000));
+        assert_eq!(iost.storage::<String>("token.iost", "TIiost", "fullName").await.unwrap(), Some("IOST".to_string()));
+        assert_eq!(iost.storage::<String>("token.iost", "TIiost", "unknown").await.unwrap(), None);
+        assert_eq!(iost.storage::<String>("token.iost", "TIunknown", "decimal").await.unwrap(), None);
+        match iost.storage::<u8>("token.iost", "TIiost", "fullName").await {
+            Err(Error::InvalidStorage(message)) => assert!(message.starts_with("token.iost TIiost fullName")),
+            r => panic!("unexpected {:?}", r),
+        }
+
+        let producer: Producer = iost.storage("vote_producer.iost", "producerTable", "admin").await.unwrap().unwrap();
+        assert!(producer.is_producer);
+        assert_eq!(producer.status, 1);
+        let producers: Vec<String> = iost.storage("vote_producer.iost", "currentProducerList", "").await.unwrap().unwrap();
+        assert_eq!(producers, vec![producer.pubkey]);
+    }
+
+    #[tokio::test]
+    async fn storage_should_be_read_in_batch_and_pages() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let iost = IOST::new(&node.url());
+        let key_fields = vec![
+            KeyField { key: "TIiost".to_string(), field: "decimal".to_string() },
+            KeyField { key: "TIiost".to_string(), field: "unknown".to_string() },
+            KeyField { key: "TIiost".to_string(), field: "fullName".to_string() },
+        ];
+        let values: Vec<Option<String>> = iost.storage_batch("token.iost", &key_fields).await.unwrap();
+        assert_eq!(values, vec![Some("8".to_string()), None, Some("IOST".to_string())]);
+
+        let mut offset = Some(0);
+        let mut entries = Vec::new();
+        while let Some(o) = offset {
+            let page = iost.storage_page::<String>("token.iost", "TIiost", o, 2).await.unwrap();
+            assert_eq!(page.total, 5);
+            assert!(page.entries.len() <= 2);
+            entries.extend(page.entries);
+            offset = page.next_offset;
+        }
+        assert_eq!(entries.len(), 5);
+        assert!(entries.contains(&("decimal".to_string(), Some("8".to_string()))));
+
+        let fields = iost.storage_fields("token.iost", "TIunknown").await.unwrap();
+        assert!(fields.is_empty());
+        let page = iost.storage_page::<String>("token.iost", "TIiost", 10, 2).await.unwrap();
+        assert!(page.entries.is_empty());
+        assert_eq!(page.next_offset, None);
+        let page = iost.storage_page::<String>("token.iost", "TIiost", 1, 0).await.unwrap();
+        assert!(page.entries.is_empty());
+        assert_eq!(page.total, 5);
+        assert_eq!(page.next_offset, None);
+    }
+}
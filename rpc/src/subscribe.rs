@@ -0,0 +1,476 @@
+use std::cmp;
+use std::time::Duration;
+
+use futures::stream::{self, Stream, StreamExt};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::block_stream::{DEFAULT_RETRIES, DEFAULT_RETRY_INTERVAL};
+use crate::error::Error;
+use crate::message::ErrorMessage;
+use crate::IOST;
+
+/// Default upper bound of the interval between two reconnections
+pub const DEFAULT_MAX_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Kind of the events streamed by `subscribe`
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Topic {
+    /// events emitted by contracts with `blockchain.event`
+    CONTRACT_EVENT,
+    /// receipts written by contracts with `blockchain.receipt`
+    CONTRACT_RECEIPT,
+}
+
+impl Default for Topic {
+    // the gateway may omit the first value of an enum
+    fn default() -> Self {
+        Topic::CONTRACT_EVENT
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubscribeFilter {
+    pub contract_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubscribeRequest {
+    pub topics: Vec<Topic>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<SubscribeFilter>,
+}
+
+/// An event streamed by the node
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub topic: Topic,
+    /// content of the event or of the receipt, as written by the contract
+    pub data: String,
+    /// Unixepoch in nanoseconds
+    pub time: i64,
+    /// contract the subscription was filtered by, if any
+    pub contract_id: Option<String>,
+    /// `contract/action` when the data is a serialized `Receipt` with a `func_name`
+    pub func_name: Option<String>,
+}
+
+impl Event {
+    /// Parse the data, which contracts usually write in JSON
+    pub fn data_as<T: for<'de> Deserialize<'de>>(&self) -> Result<T, Error> {
+        serde_json::from_str(&self.data).map_err(|e| Error::InvalidEvent(e.to_string()))
+    }
+
+    /// Whether the event matches a `Receipt.func_name` style name: `contract` matches every
+    /// event of the contract, `contract/action` only the events with this function name
+    pub fn matches(&self, name: &str) -> bool {
+        let func_name = self.func_name.as_deref();
+        if name.contains('/') {
+            return func_name == Some(name);
+        }
+        match self.contract_id {
+            Some(ref contract_id) => contract_id == name,
+            None => func_name.map_or(false, |f| f.split('/').next() == Some(name)),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct RawEvent {
+    #[serde(default)]
+    topic: Topic,
+    #[serde(default)]
+    data: String,
+    #[serde(default)]
+    time: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawResult {
+    event: RawEvent,
+}
+
+/// A line of the stream: `{"result": {"event": ...}}`, or `{"error": ...}` when the node ends it
+#[derive(Deserialize, Debug)]
+struct StreamLine {
+    result: Option<RawResult>,
+    error: Option<Value>,
+}
+
+fn func_name(data: &str) -> Option<String> {
+    let receipt: Value = serde_json::from_str(data).ok()?;
+    receipt.get("func_name").or_else(|| receipt.get("funcName"))?.as_str().map(str::to_string)
+}
+
+/// One streaming request, reopened when it fails or ends
+struct Connection {
+    url: String,
+    client: reqwest::Client,
+    request: SubscribeRequest,
+    names: Vec<String>,
+    response: Option<reqwest::Response>,
+    buffer: Vec<u8>,
+    failures: usize,
+    /// whether the node rejected the last request, which is retried after the interval
+    rejected: bool,
+    retries: usize,
+    retry_interval: Duration,
+    max_retry_interval: Duration,
+    interval: Duration,
+}
+
+impl Connection {
+    async fn connect(&self) -> Result<reqwest::Response, Error> {
+        let response = self.client.post(&self.url).json(&self.request).send().await.map_err(Error::Reqwest)?;
+        if response.status() == 200 {
+            Ok(response)
+        } else {
+            let rsp = response.json::<ErrorMessage>().await.map_err(Error::Reqwest)?;
+            Err(Error::ErrorMessage(rsp))
+        }
+    }
+
+    /// Drop the stream and wait before reconnecting, or give up with `error` once the retries are
+    /// exhausted. The next call reconnects anyway
+    async fn backoff(&mut self, error: Error) -> Result<(), Error> {
+        self.response = None;
+        self.buffer.clear();
+        if self.failures >= self.retries {
+            self.failures = 0;
+            return Err(error);
+        }
+        self.wait().await;
+        self.failures += 1;
+        Ok(())
+    }
+
+    async fn wait(&mut self) {
+        tokio::time::delay_for(self.interval).await;
+        self.interval = cmp::min(self.interval * 2, self.max_retry_interval);
+    }
+
+    fn parse(&self, line: &[u8]) -> Result<Event, Error> {
+        let line: StreamLine = serde_json::from_slice(line).map_err(|e| Error::InvalidEvent(e.to_string()))?;
+        let event = match (line.result, line.error) {
+            (Some(result), _) => result.event,
+            (None, Some(error)) => return Err(Error::SubscriptionClosed(error.to_string())),
+            (None, None) => return Err(Error::InvalidEvent("no event".to_string())),
+        };
+        Ok(Event {
+            topic: event.topic,
+            func_name: func_name(&event.data),
+            data: event.data,
+            time: event.time.parse().map_err(|_| Error::InvalidEvent(format!("invalid time {}", event.time)))?,
+            contract_id: self.request.filter.as_ref().map(|f| f.contract_id.clone()),
+        })
+    }
+
+    /// Next line of the stream, `None` when the node closed it
+    async fn next_line(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        loop {
+            if let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=end).collect();
+                return Ok(Some(line));
+            }
+            let response = match self.response {
+                Some(ref mut response) => response,
+                None => return Ok(None),
+            };
+            match response.chunk().await.map_err(Error::Reqwest)? {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk),
+                None if self.buffer.is_empty() => return Ok(None),
+                // last line without a line feed
+                None => return Ok(Some(self.buffer.split_off(0))),
+            }
+        }
+    }
+
+    /// Next event of the stream. A line which cannot be parsed is yielded as an error and the
+    /// stream goes on; only a transport error or the end of the stream reconnects
+    async fn next_event(&mut self) -> Result<Event, Error> {
+        loop {
+            if self.response.is_none() {
+                if self.rejected {
+                    self.wait().await;
+                }
+                match self.connect().await {
+                    Ok(response) => {
+                        self.rejected = false;
+                        self.response = Some(response);
+                    }
+                    Err(e @ Error::ErrorMessage(_)) => {
+                        self.rejected = true;
+                        return Err(e);
+                    }
+                    Err(e) => {
+                        self.backoff(e).await?;
+                        continue;
+                    }
+                }
+            }
+            let line = match self.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => {
+                    self.backoff(Error::SubscriptionClosed("end of stream".to_string())).await?;
+                    continue;
+                }
+                Err(e) => {
+                    self.backoff(e).await?;
+                    continue;
+                }
+            };
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            let event = match self.parse(&line) {
+                Ok(event) => event,
+                // the node ends the stream with an error line
+                Err(e @ Error::SubscriptionClosed(_)) => {
+                    self.backoff(e).await?;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            self.failures = 0;
+            self.interval = self.retry_interval;
+            if self.names.is_empty() || self.names.iter().any(|name| event.matches(name)) {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+/// Subscription to the events of the node, reconnecting with a growing interval when the stream
+/// fails or ends. Events received again after a reconnection are not deduplicated
+pub struct Subscription {
+    url: String,
+    client: reqwest::Client,
+    topics: Vec<Topic>,
+    names: Vec<String>,
+    retries: usize,
+    retry_interval: Duration,
+    max_retry_interval: Duration,
+}
+
+impl Subscription {
+    /// Only keep the events of a contract, `token.iost`, or of a function, `token.iost/transfer`.
+    /// The node filters by contract; function names are only known for the events whose data is
+    /// a serialized `Receipt`
+    pub fn func_name(mut self, name: &str) -> Self {
+        self.names.push(name.to_string());
+        self
+    }
+
+    /// Number of reconnections before an error is yielded
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Interval before the first reconnection, doubled at each reconnection
+    pub fn retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    pub fn max_retry_interval(mut self, max_retry_interval: Duration) -> Self {
+        self.max_retry_interval = max_retry_interval;
+        self
+    }
+
+    fn connection(&self, contract_id: Option<String>) -> Connection {
+        let names = match contract_id {
+            Some(ref id) => self.names.iter().filter(|n| n.split('/').next() == Some(id.as_str())).cloned().collect(),
+            None => vec![],
+        };
+        Connection {
+            url: self.url.clone(),
+            client: self.client.clone(),
+            request: SubscribeRequest {
+                topics: self.topics.clone(),
+                filter: contract_id.map(|contract_id| SubscribeFilter { contract_id }),
+            },
+            names,
+            response: None,
+            buffer: Vec::new(),
+            failures: 0,
+            rejected: false,
+            retries: self.retries,
+            retry_interval: self.retry_interval,
+            max_retry_interval: self.max_retry_interval,
+            interval: self.retry_interval,
+        }
+    }
+
+    /// Endless stream of the events, one request per filtered contract. An error is yielded when
+    /// a line cannot be parsed, when the node rejects the subscription, which is retried after
+    /// the retry interval, or when the retries are exhausted, and the stream goes on
+    pub fn into_stream(self) -> impl Stream<Item = Result<Event, Error>> {
+        let mut contracts: Vec<String> = self.names.iter().map(|n| n.split('/').next().unwrap_or_default().to_string()).collect();
+        contracts.sort();
+        contracts.dedup();
+        let connections = if contracts.is_empty() {
+            vec![self.connection(None)]
+        } else {
+            contracts.into_iter().map(|c| self.connection(Some(c))).collect()
+        };
+        stream::select_all(connections.into_iter().map(|connection| {
+            stream::unfold(connection, |mut connection| async move {
+                let event = connection.next_event().await;
+                Some((event, connection))
+            }).boxed()
+        }))
+    }
+}
+
+impl IOST {
+    /// Subscribe to the events of `topics`, all contracts unless filtered with `func_name`
+    pub fn subscribe(&self, topics: &[Topic]) -> Subscription {
+        Subscription {
+            url: format!("{}/subscribe", self.host),
+            client: self.client.clone(),
+            topics: topics.to_vec(),
+            names: Vec::new(),
+            retries: DEFAULT_RETRIES,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            max_retry_interval: DEFAULT_MAX_RETRY_INTERVAL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Client;
+    use iost_mock_node::{Fixtures, MockNode};
+    use serde_json::json;
+
+    const TIME: i64 = 1598918258274417000;
+
+    fn fixtures() -> Fixtures {
+        let mut fixtures = Fixtures::builtin();
+        let transfer = json!({"func_name": "token.iost/transfer", "content": "[\"iost\",\"admin\",\"lispczz3\",\"10\",\"\"]"});
+        let issue = json!({"func_name": "token.iost/issue", "content": "[\"iost\",\"admin\",\"10\"]"});
+        fixtures.events = vec![
+            json!({"contract_id": "token.iost", "topic": "CONTRACT_RECEIPT", "data": transfer.to_string(), "time": TIME.to_string()}),
+            json!({"contract_id": "token.iost", "topic": "CONTRACT_RECEIPT", "data": issue.to_string(), "time": (TIME + 1).to_string()}),
+            json!({"contract_id": "vote.iost", "topic": "CONTRACT_RECEIPT", "data": "[\"admin\"]", "time": (TIME + 2).to_string()}),
+            json!({"contract_id": "vote.iost", "topic": "CONTRACT_EVENT", "data": "{\"id\":1}", "time": (TIME + 3).to_string()}),
+        ];
+        fixtures
+    }
+
+    #[test]
+    fn event_names_should_be_matched() {
+        let event = Event {
+            topic: Topic::CONTRACT_RECEIPT,
+            data: "".to_string(),
+            time: TIME,
+            contract_id: Some("token.iost".to_string()),
+            func_name: Some("token.iost/transfer".to_string()),
+        };
+        assert!(event.matches("token.iost"));
+        assert!(event.matches("token.iost/transfer"));
+        assert!(!event.matches("token.iost/issue"));
+        assert!(!event.matches("vote.iost"));
+        let unnamed = Event { func_name: None, ..event };
+        assert!(unnamed.matches("token.iost"));
+        assert!(!unnamed.matches("token.iost/transfer"));
+    }
+
+    #[tokio::test]
+    async fn subscription_should_reconnect() {
+        let node = MockNode::start(fixtures()).unwrap();
+        let iost = IOST::new(&node.url());
+        let events: Vec<Event> = iost.subscribe(&[Topic::CONTRACT_RECEIPT])
+            .retry_interval(Duration::from_millis(10))
+            .into_stream()
+            .take(4)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        // the mock node closes the stream after the three receipts
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].time, TIME);
+        assert_eq!(events[0].func_name.as_ref().unwrap(), "token.iost/transfer");
+        assert_eq!(events[2].data_as::<Vec<String>>().unwrap(), vec!["admin"]);
+        assert_eq!(events[2].contract_id, None);
+        assert_eq!(events[3], events[0]);
+        assert_eq!(node.subscriptions(), 2);
+    }
+
+    #[tokio::test]
+    async fn subscription_should_be_filtered_by_func_name() {
+        let node = MockNode::start(fixtures()).unwrap();
+        let iost = IOST::new(&node.url());
+        let mut events = Box::pin(iost.subscribe(&[Topic::CONTRACT_EVENT, Topic::CONTRACT_RECEIPT])
+            .func_name("token.iost/transfer")
+            .func_name("vote.iost")
+            .retry_interval(Duration::from_millis(10))
+            .into_stream());
+        let mut received = Vec::new();
+        while received.len() < 6 {
+            received.push(events.next().await.unwrap().unwrap());
+        }
+        let transfers: Vec<&Event> = received.iter().filter(|e| e.contract_id.as_deref() == Some("token.iost")).collect();
+        assert!(!transfers.is_empty());
+        assert!(transfers.iter().all(|e| e.func_name.as_deref() == Some("token.iost/transfer")));
+        assert!(received.iter().any(|e| e.topic == Topic::CONTRACT_EVENT && e.contract_id.as_deref() == Some("vote.iost")));
+    }
+
+    #[tokio::test]
+    async fn subscription_errors_should_be_yielded() {
+        let iost = IOST::new("http://127.0.0.1:1");
+        let mut events = Box::pin(iost.subscribe(&[Topic::CONTRACT_RECEIPT])
+            .retries(2)
+            .retry_interval(Duration::from_millis(1))
+            .into_stream());
+        match events.next().await {
+            Some(Err(Error::Reqwest(_))) => {}
+            r => panic!("unexpected {:?}", r),
+        }
+
+        let node = MockNode::start(fixtures()).unwrap();
+        let iost = IOST::new(&format!("{}/unknown", node.url()));
+        let mut events = Box::pin(iost.subscribe(&[Topic::CONTRACT_RECEIPT]).into_stream());
+        match events.next().await {
+            Some(Err(Error::ErrorMessage(_))) => {}
+            r => panic!("unexpected {:?}", r),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejected_subscription_should_be_retried_after_interval() {
+        let node = MockNode::start(fixtures()).unwrap();
+        let iost = IOST::new(&format!("{}/unknown", node.url()));
+        let mut events = Box::pin(iost.subscribe(&[Topic::CONTRACT_RECEIPT])
+            .retry_interval(Duration::from_millis(50))
+            .into_stream());
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            match events.next().await {
+                Some(Err(Error::ErrorMessage(_))) => {}
+                r => panic!("unexpected {:?}", r),
+            }
+        }
+        // no wait before the first request, then 50ms and 100ms
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn invalid_lines_should_not_reconnect() {
+        let mut fixtures = fixtures();
+        fixtures.events.insert(1, json!({"contract_id": "token.iost", "topic": "CONTRACT_RECEIPT", "data": "[]", "time": "never"}));
+        let node = MockNode::start(fixtures).unwrap();
+        let iost = IOST::new(&node.url());
+        let mut events = Box::pin(iost.subscribe(&[Topic::CONTRACT_RECEIPT]).into_stream());
+        assert_eq!(events.next().await.unwrap().unwrap().time, TIME);
+        match events.next().await {
+            Some(Err(Error::InvalidEvent(_))) => {}
+            r => panic!("unexpected {:?}", r),
+        }
+        assert_eq!(events.next().await.unwrap().unwrap().time, TIME + 1);
+        assert_eq!(node.subscriptions(), 1);
+    }
+}
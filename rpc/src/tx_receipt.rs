@@ -29,6 +29,33 @@ pub struct TxReceipt {
     pub receipts: Vec<Receipt>
 }
 
+impl TxReceipt {
+    /// Id of the contract the `setCode` action of the transaction deployed
+    pub fn deployed_contract_id(&self) -> Result<String, Error> {
+        iost_chain::deployed_contract_id(&self.returns).map_err(Error::Chain)
+    }
+
+    /// The receipt as blocks carry it, with the tx hash in base64 and the gas usage multiplied by 100
+    pub fn to_spv_receipt(&self) -> Result<iost_chain::TxReceipt, Error> {
+        let tx_hash = bs58::decode(&self.tx_hash).into_vec()
+            .map_err(|_| Error::InvalidBlock(format!("invalid tx hash {}", self.tx_hash)))?;
+        Ok(iost_chain::TxReceipt {
+            tx_hash: base64::encode(tx_hash),
+            gas_usage: ((self.gas_usage * 100.0).round() as i64).to_string(),
+            ram_usage: self.ram_usage.iter().map(|(account, usage)| (account.clone(), usage.to_string())).collect(),
+            status: iost_chain::spv::TxReceiptStatus {
+                code: self.status_code.code(),
+                message: self.message.clone(),
+            },
+            returns: self.returns.clone(),
+            receipts: self.receipts.iter().map(|r| iost_chain::Receipt {
+                func_name: r.func_name.clone(),
+                content: r.content.clone(),
+            }).collect(),
+        })
+    }
+}
+
 async fn get_tx_receipt_json (domain: &str, hash: &str) -> Result<TxReceipt, Error> {
     let url = format!("{}/getTxReceiptByTxHash/{}", domain, hash);
     let req = reqwest::get(&url).await.map_err(Error::Reqwest)?;
@@ -44,11 +71,28 @@ async fn get_tx_receipt_json (domain: &str, hash: &str) -> Result<TxReceipt, Err
 #[cfg(test)]
 mod test {
     use super::*;
+    use iost_mock_node::{Fixtures, MockNode};
+
+    #[test]
+    fn deployed_contract_id_should_be_read_from_returns() {
+        let receipt: TxReceipt = serde_json::from_value(serde_json::json!({
+            "tx_hash": "Dj8bmA4Fx4LHrwLtDB6EEkNbBFU8biENxf55mNaJewYw",
+            "gas_usage": 38795,
+            "ram_usage": {"admin": 1432},
+            "status_code": "SUCCESS",
+            "message": "",
+            "returns": ["[\"Contract6uHwbaqtHjnf3fpBGd5nsxHKbHGQR2JBzNUxH9XkBCYm\"]"],
+            "receipts": [],
+        })).unwrap();
+        assert_eq!(receipt.deployed_contract_id().unwrap(), "Contract6uHwbaqtHjnf3fpBGd5nsxHKbHGQR2JBzNUxH9XkBCYm");
+    }
 
     #[tokio::test]
     async fn get_tx_receipt_json_should_be_ok() {
-        let response = get_tx_receipt_json("http://api.iost.io", "Dj8bmA4Fx4LHrwLtDB6EEkNbBFU8biENxf55mNaJewYw").await;
-        assert!(response.is_ok());
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let response = get_tx_receipt_json(&node.url(), "CihRgNwEPjWnaXgogcFhK31r7EkWZyU9H6ZxzyWF37yK").await.unwrap();
+        assert_eq!(response.gas_usage, 38795.0);
+        assert_eq!(response.status_code, StatusCode::SUCCESS);
     }
 }
 
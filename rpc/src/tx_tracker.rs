@@ -0,0 +1,286 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use iost_chain::Tx;
+
+use crate::error::Error;
+use crate::status::Status;
+use crate::status_code::StatusCode;
+use crate::tx_receipt::TxReceipt;
+use crate::Client;
+
+/// Default interval between two polls of the node
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Default time `send_and_confirm` waits for the block of a packed transaction to become irreversible
+pub const DEFAULT_IRREVERSIBLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Default time a receipt is still waited for after the expiration of its transaction, six block
+/// intervals, since a transaction packed just before it expires is only seen in the next blocks
+pub const DEFAULT_EXPIRATION_GRACE: Duration = Duration::from_secs(3);
+
+#[derive(Debug)]
+pub struct TxConfirmation {
+    /// hash of the transaction
+    pub hash: String,
+    /// PENDING/PACKED - the block containing the transaction is still reversible; IRREVERSIBLE - it is irreversible
+    pub status: Status,
+    /// the number of the block which the tx is in
+    pub block_number: i64,
+    /// the receipt of the transaction
+    pub receipt: TxReceipt,
+}
+
+/// Follows a transaction from `sendTx` until its block becomes irreversible, over any `Client`.
+pub struct TxTracker<'a, C> {
+    client: &'a C,
+    poll_interval: Duration,
+    irreversible_timeout: Duration,
+    expiration_grace: Duration,
+}
+
+fn now_nanos() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or_default()
+}
+
+fn is_expired(expiration: i64, now: i64) -> bool {
+    now > expiration
+}
+
+/// Whether the node answered that it has no receipt of the transaction, as long as it is not
+/// packed. Any other error is not worth another poll
+fn is_receipt_missing(e: &Error) -> bool {
+    match e {
+        Error::ErrorMessage(message) => message.message.to_lowercase().contains("not found"),
+        #[cfg(feature = "grpc")]
+        Error::Grpc(status) => {
+            status.code() == tonic::Code::NotFound || status.message().to_lowercase().contains("not found")
+        }
+        _ => false,
+    }
+}
+
+impl<'a, C: Client> TxTracker<'a, C> {
+    pub fn new(client: &'a C) -> Self {
+        Self {
+            client,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            irreversible_timeout: DEFAULT_IRREVERSIBLE_TIMEOUT,
+            expiration_grace: DEFAULT_EXPIRATION_GRACE,
+        }
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn irreversible_timeout(mut self, irreversible_timeout: Duration) -> Self {
+        self.irreversible_timeout = irreversible_timeout;
+        self
+    }
+
+    pub fn expiration_grace(mut self, expiration_grace: Duration) -> Self {
+        self.expiration_grace = expiration_grace;
+        self
+    }
+
+    /// Submit a signed transaction and return its hash
+    pub async fn send(&self, tx: &Tx) -> Result<String, Error> {
+        let response = self.client.send_signed_tx(tx).await?;
+        Ok(response.hash)
+    }
+
+    /// Poll `getTxReceiptByTxHash` until the receipt appears, as long as the node answers that it is not found.
+    /// Fails with `Error::TxExpired` once `expiration` (Unixepoch in nanoseconds) and the expiration grace have
+    /// passed without a receipt, with `Error::InvalidTx` if `expiration` is not set, and with `Error::TxFailed`
+    /// if the transaction was executed with a status code other than SUCCESS.
+    pub async fn wait_receipt(&self, hash: &str, expiration: i64) -> Result<TxReceipt, Error> {
+        if expiration <= 0 {
+            return Err(Error::InvalidTx(format!("tx {} has no expiration to wait for its receipt", hash)));
+        }
+        let deadline = expiration.saturating_add(self.expiration_grace.as_nanos() as i64);
+        loop {
+            match self.client.get_tx_receipt_by_tx_hash(hash).await {
+                Ok(receipt) => {
+                    return match receipt.status_code {
+                        StatusCode::SUCCESS => Ok(receipt),
+                        status_code => Err(Error::TxFailed(status_code, receipt.message)),
+                    };
+                }
+                Err(ref e) if is_receipt_missing(e) => {}
+                Err(e) => return Err(e),
+            }
+            if is_expired(deadline, now_nanos()) {
+                return Err(Error::TxExpired(hash.to_string()));
+            }
+            tokio::time::delay_for(self.poll_interval).await;
+        }
+    }
+
+    /// Current status of a transaction and the number of its block, 0 while it is PENDING.
+    /// A PACKED transaction is IRREVERSIBLE once the last irreversible block has reached its block
+    pub async fn status(&self, hash: &str) -> Result<(Status, i64), Error> {
+        let tx = self.client.get_tx_by_hash(hash).await?;
+        let block_number = parse_number(&tx.block_number)?;
+        if tx.status != Status::PACKED || block_number <= 0 {
+            return Ok((tx.status, block_number));
+        }
+        let chain_info = self.client.get_chain_info().await?;
+        if parse_number(&chain_info.lib_block)? >= block_number {
+            Ok((Status::IRREVERSIBLE, block_number))
+        } else {
+            Ok((Status::PACKED, block_number))
+        }
+    }
+
+    /// Poll until the block containing the transaction is irreversible.
+    /// Fails with `Error::TxNotIrreversible` once `deadline` (Unixepoch in nanoseconds) has passed.
+    pub async fn wait_irreversible(&self, hash: &str, deadline: i64) -> Result<i64, Error> {
+        loop {
+            if let (Status::IRREVERSIBLE, block_number) = self.status(hash).await? {
+                return Ok(block_number);
+            }
+            if is_expired(deadline, now_nanos()) {
+                return Err(Error::TxNotIrreversible(hash.to_string()));
+            }
+            tokio::time::delay_for(self.poll_interval).await;
+        }
+    }
+
+    /// Send the transaction and wait for its receipt. The returned confirmation is usually still PENDING or PACKED.
+    pub async fn send_and_wait_receipt(&self, tx: &Tx) -> Result<TxConfirmation, Error> {
+        let hash = self.send(tx).await?;
+        let receipt = self.wait_receipt(&hash, tx.expiration).await?;
+        let (status, block_number) = self.status(&hash).await?;
+        Ok(TxConfirmation {
+            hash,
+            status,
+            block_number,
+            receipt,
+        })
+    }
+
+    /// Send the transaction, wait for its receipt and for its block to become irreversible,
+    /// at most `irreversible_timeout` after the receipt
+    pub async fn send_and_confirm(&self, tx: &Tx) -> Result<TxConfirmation, Error> {
+        let mut confirmation = self.send_and_wait_receipt(tx).await?;
+        if confirmation.status != Status::IRREVERSIBLE {
+            let deadline = now_nanos().saturating_add(self.irreversible_timeout.as_nanos() as i64);
+            confirmation.block_number =
+                self.wait_irreversible(&confirmation.hash, deadline).await?;
+            confirmation.status = Status::IRREVERSIBLE;
+        }
+        Ok(confirmation)
+    }
+}
+
+pub(crate) fn parse_number(number: &str) -> Result<i64, Error> {
+    number
+        .parse::<i64>()
+        .map_err(|_| Error::InvalidNumber(number.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ErrorMessage, IOST};
+    use iost_chain::{IostAction, TxBuilder, MAINNET_CHAIN_ID};
+    use iost_mock_node::{Fixtures, MockNode};
+    use serde_json::Value;
+
+    #[test]
+    fn tx_expiration_should_be_checked() {
+        assert!(!is_expired(1598918348274417000, 1598918258274417000));
+        assert!(is_expired(1598918348274417000, 1598918348274417001));
+        assert!(is_expired(0, 1598918348274417001));
+    }
+
+    #[test]
+    fn only_missing_receipts_should_be_waited_for() {
+        let reply = |message: &str| Error::ErrorMessage(ErrorMessage { code: 2, message: message.to_string() });
+        assert!(is_receipt_missing(&reply("receipt of tx 8UpN not found")));
+        assert!(is_receipt_missing(&reply("leveldb: not found")));
+        assert!(!is_receipt_missing(&reply("invalid hash")));
+        assert!(!is_receipt_missing(&Error::InvalidNumber("x".to_string())));
+    }
+
+    #[test]
+    fn block_number_should_be_parsed() {
+        assert_eq!(parse_number("3").unwrap(), 3);
+        assert!(parse_number("x").is_err());
+    }
+
+    fn signed_tx() -> Tx {
+        let mut tx = TxBuilder::new(MAINNET_CHAIN_ID)
+            .time(1598918258274417000)
+            .publisher("admin")
+            .action(IostAction::transfer("admin", "lispczz3", "10", "").unwrap())
+            .build()
+            .unwrap();
+        tx.sign("admin".to_string(), "SECP256K1", &[1; 32]).unwrap();
+        tx
+    }
+
+    #[tokio::test]
+    async fn tx_should_be_confirmed_by_mock_node() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let client = IOST::new(&node.url());
+        let tx = signed_tx();
+
+        let confirmation = TxTracker::new(&client)
+            .poll_interval(Duration::from_millis(10))
+            .send_and_confirm(&tx)
+            .await
+            .unwrap();
+        assert_eq!(confirmation.status, Status::IRREVERSIBLE);
+        assert_eq!(confirmation.block_number, 3);
+        assert_eq!(node.sent_txs()[0].0, confirmation.hash);
+    }
+
+    #[tokio::test]
+    async fn receipt_should_be_waited_for_until_the_grace_has_passed() {
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let client = IOST::new(&node.url());
+        let tracker = TxTracker::new(&client)
+            .poll_interval(Duration::from_millis(10))
+            .expiration_grace(Duration::from_millis(50));
+        match tracker.wait_receipt("unknown", 0).await {
+            Err(Error::InvalidTx(_)) => {}
+            r => panic!("unexpected {:?}", r),
+        }
+        let expiration = now_nanos();
+        match tracker.wait_receipt("unknown", expiration).await {
+            Err(Error::TxExpired(h)) => assert_eq!(h, "unknown"),
+            r => panic!("unexpected {:?}", r),
+        }
+        assert!(now_nanos() - expiration >= Duration::from_millis(50).as_nanos() as i64);
+    }
+
+    #[tokio::test]
+    async fn pending_tx_should_not_be_irreversible() {
+        // the answer of a node for a tx it has packed, turned back into a pending one
+        let node = MockNode::start(Fixtures::builtin()).unwrap();
+        let client = IOST::new(&node.url());
+        let hash = TxTracker::new(&client).send(&signed_tx()).await.unwrap();
+        let mut pending: Value = client.get(&format!("getTxByHash/{}", hash)).await.unwrap();
+        pending["status"] = Value::from("PENDING");
+        pending["block_number"] = Value::from("0");
+        let mut fixtures = Fixtures::builtin();
+        fixtures.txs.insert(hash.clone(), pending);
+
+        let node = MockNode::start(fixtures).unwrap();
+        let client = IOST::new(&node.url());
+        let tracker = TxTracker::new(&client).poll_interval(Duration::from_millis(10));
+        let (status, block_number) = tracker.status(&hash).await.unwrap();
+        assert_eq!(status, Status::PENDING);
+        assert_eq!(block_number, 0);
+        let deadline = now_nanos() + Duration::from_millis(50).as_nanos() as i64;
+        match tracker.wait_irreversible(&hash, deadline).await {
+            Err(Error::TxNotIrreversible(h)) => assert_eq!(h, hash),
+            r => panic!("unexpected {:?}", r),
+        }
+    }
+}